@@ -0,0 +1,203 @@
+use lib_lexer_types::{CodePoint, Lexeme, LexemeKind, Lexer, Span, Symbol, Token, TokenData};
+use lib_parser_types::{
+    visit::{self, Visitor},
+    Attribute, Block, Expr, HAst, MatchArm, Param, Pattern, TypeExpr,
+};
+
+use super::{GreenElement, GreenNode, GreenToken, NodeKind, TypedNode};
+
+/// builds a green tree from the lexemes of a source and its typed syntax tree
+///
+/// nodes are placed at the spans of the typed nodes, and every lexeme is put
+/// in the innermost node that is open when it is reached, so trivia between
+/// two nodes belongs to their parent
+pub(crate) struct Builder<'a, 'input> {
+    lexemes: &'a [Lexeme<'input>],
+    next: usize,
+    /// the rest of a lexeme that was split between two nodes
+    rest: Option<Lexeme<'input>>,
+    stack: Vec<(NodeKind, Vec<GreenElement>)>,
+}
+
+/// the tokens among lexemes, which lets the parser read the lexemes that the
+/// green tree is built from instead of lexing the text again
+pub(crate) struct Tokens<'a, 'input> {
+    lexemes: std::slice::Iter<'a, Lexeme<'input>>,
+}
+
+impl<'a, 'input> Tokens<'a, 'input> {
+    pub(crate) fn new(lexemes: &'a [Lexeme<'input>]) -> Self {
+        Self {
+            lexemes: lexemes.iter(),
+        }
+    }
+}
+
+impl<'input> Lexer<'input> for Tokens<'_, 'input> {
+    fn parse(&mut self) -> lib_lexer_types::Result<Option<Token<'input>>> {
+        Ok(self.lexemes.find_map(|lexeme| match lexeme.kind {
+            LexemeKind::Token(data) => Some(Token {
+                data,
+                span: lexeme.span,
+            }),
+            LexemeKind::Trivia(_) => None,
+        }))
+    }
+}
+
+/// lexes `text`, which starts at `start` in its source, with its trivia
+pub(crate) fn lex(text: &str, start: CodePoint) -> lib_lexer_types::Result<Vec<Lexeme<'_>>> {
+    let mut lexer = lib_lexer::Lexer::with_start(text, start);
+    let mut lexemes = Vec::new();
+
+    while let Some(lexeme) = lexer.parse_lossless()? {
+        lexemes.push(lexeme);
+    }
+
+    Ok(lexemes)
+}
+
+impl<'a, 'input> Builder<'a, 'input> {
+    pub(crate) fn new(lexemes: &'a [Lexeme<'input>]) -> Self {
+        Self {
+            lexemes,
+            next: 0,
+            rest: None,
+            stack: vec![(NodeKind::Root, Vec::new())],
+        }
+    }
+
+    pub(crate) fn finish(mut self) -> GreenNode {
+        while self.peek().is_some() {
+            self.bump();
+        }
+
+        let (kind, children) = self.stack.pop().expect("the root is never finished early");
+        GreenNode::new(kind, children)
+    }
+
+    fn peek(&self) -> Option<Lexeme<'input>> {
+        self.rest.or_else(|| self.lexemes.get(self.next).copied())
+    }
+
+    fn bump(&mut self) {
+        let lexeme = match self.rest.take() {
+            Some(rest) => rest,
+            None => {
+                self.next += 1;
+                self.lexemes[self.next - 1]
+            }
+        };
+
+        self.push_token(lexeme);
+    }
+
+    fn push_token(&mut self, lexeme: Lexeme<'input>) {
+        let token = GreenToken::new(lexeme.kind.into(), lexeme.text);
+
+        self.stack
+            .last_mut()
+            .expect("there is always an open node")
+            .1
+            .push(GreenElement::Token(token));
+    }
+
+    fn bump_while(&mut self, mut f: impl FnMut(&Lexeme<'input>) -> bool) {
+        while matches!(self.peek(), Some(ref lexeme) if f(lexeme)) {
+            self.bump();
+        }
+    }
+
+    /// the parser splits a `>=` that closes generic arguments, so the `>` is
+    /// put in the node that ends in the middle of it and the `=` is left over
+    fn split_greater_equal(&mut self, end: CodePoint) {
+        let lexeme = match self.peek() {
+            Some(lexeme) if lexeme.span.start() < end => lexeme,
+            _ => return,
+        };
+
+        debug_assert!(self.rest.is_none());
+        debug_assert_eq!(
+            lexeme.kind,
+            LexemeKind::Token(TokenData::Symbol(Symbol::GreaterEqual))
+        );
+
+        let symbol = |symbol| LexemeKind::Token(TokenData::Symbol(symbol));
+
+        self.next += 1;
+        self.push_token(Lexeme {
+            kind: symbol(Symbol::GreaterThan),
+            text: &lexeme.text[..1],
+            span: lexeme.span.start().span(end),
+        });
+        self.rest = Some(Lexeme {
+            kind: symbol(Symbol::Assign),
+            text: &lexeme.text[1..],
+            span: end.span(lexeme.span.end()),
+        });
+    }
+
+    fn start_node(&mut self, kind: NodeKind, span: Span) {
+        self.bump_while(|lexeme| lexeme.span.start() < span.start());
+        self.stack.push((kind, Vec::new()));
+    }
+
+    fn finish_node(&mut self, span: Span) {
+        self.bump_while(|lexeme| lexeme.span.end() <= span.end());
+        self.split_greater_equal(span.end());
+
+        let (kind, children) = self.stack.pop().expect("a node was started");
+
+        self.stack
+            .last_mut()
+            .expect("the root is always open")
+            .1
+            .push(GreenElement::Node(GreenNode::new(kind, children)));
+    }
+
+    fn typed_node(&mut self, node: &impl TypedNode, f: impl FnOnce(&mut Self)) {
+        let span = node.syntax_span();
+
+        self.start_node(node.syntax_kind(), span);
+        f(self);
+        self.finish_node(span);
+    }
+}
+
+impl<'input, 'hacx> Visitor<'input, 'hacx> for Builder<'_, 'input> {
+    fn visit_hast(&mut self, ast: &HAst<'input, 'hacx>) {
+        self.typed_node(ast, |this| visit::walk_hast(this, ast))
+    }
+
+    fn visit_attribute(&mut self, attr: &Attribute<'input>) {
+        self.typed_node(attr, |this| visit::walk_attribute(this, attr))
+    }
+
+    fn visit_param(&mut self, param: &Param<'input, 'hacx>) {
+        self.typed_node(param, |this| visit::walk_param(this, param))
+    }
+
+    fn visit_block(&mut self, block: &Block<'input, 'hacx>) {
+        self.typed_node(block, |this| visit::walk_block(this, block))
+    }
+
+    fn visit_match_arm(&mut self, arm: &MatchArm<'input, 'hacx>) {
+        self.typed_node(arm, |this| visit::walk_match_arm(this, arm))
+    }
+
+    fn visit_expr(&mut self, expr: &Expr<'input, 'hacx>) {
+        match expr {
+            // the block is its own node
+            Expr::Block(_) => visit::walk_expr(self, expr),
+            _ => self.typed_node(expr, |this| visit::walk_expr(this, expr)),
+        }
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern<'input>) {
+        self.typed_node(pattern, |this| visit::walk_pattern(this, pattern))
+    }
+
+    fn visit_type(&mut self, ty: &TypeExpr<'input, 'hacx>) {
+        self.typed_node(ty, |this| visit::walk_type(this, ty))
+    }
+}
@@ -0,0 +1,122 @@
+use std::fmt;
+use std::sync::Arc;
+
+use super::{NodeKind, TokenKind};
+
+/// an immutable node that only knows its kind, its children and the length
+/// of its text, so that equal subtrees can be shared between trees
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GreenNode(Arc<GreenNodeData>);
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct GreenNodeData {
+    kind: NodeKind,
+    text_len: usize,
+    children: Vec<GreenElement>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GreenToken(Arc<GreenTokenData>);
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct GreenTokenData {
+    kind: TokenKind,
+    text: Box<str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GreenElement {
+    Node(GreenNode),
+    Token(GreenToken),
+}
+
+impl GreenNode {
+    pub fn new(kind: NodeKind, children: Vec<GreenElement>) -> Self {
+        let text_len = children.iter().map(GreenElement::text_len).sum();
+
+        Self(Arc::new(GreenNodeData {
+            kind,
+            text_len,
+            children,
+        }))
+    }
+
+    pub fn kind(&self) -> NodeKind {
+        self.0.kind
+    }
+
+    /// the length of the text of this node in bytes
+    pub fn text_len(&self) -> usize {
+        self.0.text_len
+    }
+
+    pub fn children(&self) -> &[GreenElement] {
+        &self.0.children
+    }
+
+    /// whether both are the same node, rather than equal nodes, which is
+    /// cheap and tells which parts of a tree were reused by an edit
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// a copy of this node with the child at `index` replaced, the other
+    /// children are shared with this node
+    pub fn replace_child(&self, index: usize, child: GreenElement) -> Self {
+        let mut children = self.0.children.clone();
+        children[index] = child;
+        Self::new(self.kind(), children)
+    }
+}
+
+impl GreenToken {
+    pub fn new(kind: TokenKind, text: &str) -> Self {
+        Self(Arc::new(GreenTokenData {
+            kind,
+            text: text.into(),
+        }))
+    }
+
+    pub fn kind(&self) -> TokenKind {
+        self.0.kind
+    }
+
+    pub fn text(&self) -> &str {
+        &self.0.text
+    }
+
+    pub fn text_len(&self) -> usize {
+        self.0.text.len()
+    }
+}
+
+impl GreenElement {
+    pub fn text_len(&self) -> usize {
+        match self {
+            GreenElement::Node(node) => node.text_len(),
+            GreenElement::Token(token) => token.text_len(),
+        }
+    }
+}
+
+/// writes the source text of the node
+impl fmt::Display for GreenNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.children().iter().try_for_each(|child| child.fmt(f))
+    }
+}
+
+impl fmt::Display for GreenToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.text())
+    }
+}
+
+impl fmt::Display for GreenElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GreenElement::Node(node) => node.fmt(f),
+            GreenElement::Token(token) => token.fmt(f),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use lib_lexer_types::{LexemeKind, TokenType, TriviaKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    Whitespace,
+    Comment,
+    Token(TokenType),
+}
+
+impl TokenKind {
+    pub fn is_trivia(self) -> bool {
+        matches!(self, TokenKind::Whitespace | TokenKind::Comment)
+    }
+}
+
+impl From<LexemeKind<'_>> for TokenKind {
+    fn from(kind: LexemeKind<'_>) -> Self {
+        match kind {
+            LexemeKind::Trivia(TriviaKind::Whitespace) => TokenKind::Whitespace,
+            LexemeKind::Trivia(TriviaKind::Comment) => TokenKind::Comment,
+            LexemeKind::Token(data) => TokenKind::Token(data.tok_type()),
+        }
+    }
+}
+
+/// the kinds of nodes, which mostly follow the typed syntax tree
+///
+/// the fields of structs and variants, closure parameters and generics don't
+/// have nodes of their own, their tokens are part of the enclosing node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeKind {
+    Root,
+
+    Attribute,
+    Let,
+    Assign,
+    TypeAlias,
+    Function,
+    Param,
+    Struct,
+    Enum,
+    Mod,
+    Use,
+    ExprStmt,
+    Block,
+    MatchArm,
+
+    Literal,
+    Identifier,
+    PathExpr,
+    PrefixExpr,
+    PostfixExpr,
+    BinaryExpr,
+    LogicalExpr,
+    MatchExpr,
+    IfExpr,
+    CallExpr,
+    FieldExpr,
+    TupleIndexExpr,
+    MethodCallExpr,
+    ParenExpr,
+    TupleExpr,
+    ArrayExpr,
+    StructExpr,
+    ClosureExpr,
+    TurbofishExpr,
+
+    LiteralPattern,
+    BindingPattern,
+    PathPattern,
+    WildcardPattern,
+    OrPattern,
+    TuplePattern,
+    ArrayPattern,
+    StructPattern,
+    TupleStructPattern,
+
+    NamedType,
+    TupleType,
+    FunctionType,
+}
@@ -0,0 +1,384 @@
+//! a lossless concrete syntax tree
+//!
+//! The green tree is immutable and position independent, and holds every
+//! token of the source, including whitespace and comments, so the text of
+//! the root is always exactly the source it was built from. Red nodes are
+//! cursors over the green tree that know their parent and absolute offset.
+//!
+//! The typed `HAst` is parsed from the same lexemes as the green tree, and
+//! every typed node with a syntax node of its own maps to exactly that node
+//! with `Cst::syntax`. Edits are made by replacing green nodes, which keeps all
+//! of the trivia around them, and `Cst::reparse` updates both trees after a
+//! text edit by only reparsing the statement or block around it.
+
+use std::ops::Range;
+
+use lib_lexer_types::{CodePoint, Span};
+use lib_parser_types::{context::ContextRef, visit::Visitor as _, HAst, ParseError};
+
+mod build;
+mod green;
+mod kind;
+mod red;
+mod reparse;
+mod typed;
+
+pub use green::{GreenElement, GreenNode, GreenToken};
+pub use kind::{NodeKind, TokenKind};
+pub use red::{SyntaxElement, SyntaxNode, SyntaxToken};
+pub use reparse::TextEdit;
+pub use typed::TypedNode;
+
+pub struct Cst<'input, 'hacx> {
+    pub root: SyntaxNode,
+    pub items: Vec<HAst<'input, 'hacx>>,
+    /// the byte offset of the start of each line
+    lines: Vec<usize>,
+}
+
+/// parses `text` into both its typed syntax tree and its concrete syntax tree
+pub fn parse<'input, 'hacx>(
+    text: &'input str,
+    ctx: ContextRef<'input, 'hacx>,
+) -> Result<Cst<'input, 'hacx>, ParseError> {
+    let lexemes =
+        build::lex(text, CodePoint::new_unchecked(1, 1)).map_err(lib_parser_types::lex_error)?;
+
+    let mut parser = lib_parser::Parser::new(build::Tokens::new(&lexemes), ctx);
+    let mut items = Vec::new();
+
+    while let Some(item) = parser.parse()? {
+        items.push(item);
+    }
+
+    let mut builder = build::Builder::new(&lexemes);
+
+    for item in &items {
+        builder.visit_hast(item);
+    }
+
+    Ok(Cst {
+        root: SyntaxNode::new_root(builder.finish()),
+        items,
+        lines: line_starts(text),
+    })
+}
+
+fn line_starts(text: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+        .collect()
+}
+
+impl Cst<'_, '_> {
+    /// the byte range of a span in the source
+    pub fn range(&self, span: Span) -> Range<usize> {
+        let offset =
+            |point: CodePoint| self.lines[point.row() as usize - 1] + point.col() as usize - 1;
+
+        offset(span.start())..offset(span.end())
+    }
+
+    /// the syntax node of a typed node of this tree, or `None` if the node is
+    /// from another tree
+    pub fn syntax(&self, node: &impl TypedNode) -> Option<SyntaxNode> {
+        self.find_node(node.syntax_kind(), self.range(node.syntax_span()))
+    }
+
+    /// the node of `kind` that covers exactly `range`
+    pub(crate) fn find_node(&self, kind: NodeKind, range: Range<usize>) -> Option<SyntaxNode> {
+        if range.end > self.root.green().text_len() {
+            return None;
+        }
+
+        // the nodes that cover exactly `range` are a chain of ancestors
+        // starting at the innermost one
+        let mut node = self.root.covering_node(range.clone());
+
+        loop {
+            if node.text_range() != range {
+                return None;
+            }
+
+            if node.kind() == kind {
+                return Some(node);
+            }
+
+            node = node.parent()?.clone();
+        }
+    }
+}
+
+#[test]
+fn lossless() {
+    let ctx = lib_parser::context::LocalContext::default();
+    let text = "// header\nfn f(x: i32) -> i32 {\n    // body\n    let y = ((x + 1)) * 2; // two\n    -(y)\n}\n";
+
+    let cst = parse(text, ctx.as_ref()).unwrap();
+
+    assert_eq!(cst.root.text(), text);
+    assert_eq!(cst.root.green().text_len(), text.len());
+
+    let kinds = cst
+        .root
+        .descendants()
+        .map(|node| node.kind())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        kinds,
+        [
+            NodeKind::Root,
+            NodeKind::Function,
+            NodeKind::Param,
+            NodeKind::NamedType,
+            NodeKind::NamedType,
+            NodeKind::Block,
+            NodeKind::Let,
+            NodeKind::BindingPattern,
+            NodeKind::BinaryExpr,
+            NodeKind::ParenExpr,
+            NodeKind::ParenExpr,
+            NodeKind::BinaryExpr,
+            NodeKind::Identifier,
+            NodeKind::Literal,
+            NodeKind::Literal,
+            NodeKind::PrefixExpr,
+            NodeKind::ParenExpr,
+            NodeKind::Identifier,
+        ]
+    );
+
+    let mul = cst
+        .root
+        .descendants()
+        .find(|node| node.kind() == NodeKind::BinaryExpr)
+        .unwrap();
+    assert_eq!(mul.text(), "((x + 1)) * 2");
+
+    let neg = cst
+        .root
+        .descendants()
+        .find(|node| node.kind() == NodeKind::PrefixExpr)
+        .unwrap();
+    assert_eq!(neg.text(), "-(y)");
+
+    let comment = cst
+        .root
+        .token_at_offset(text.find("// two").unwrap())
+        .unwrap();
+    assert_eq!(comment.kind(), TokenKind::Comment);
+    assert_eq!(comment.parent().kind(), NodeKind::Block);
+}
+
+#[test]
+fn nested_parens() {
+    let ctx = lib_parser::context::LocalContext::default();
+    let text = "let t = f((a), ( (b + c) ), ((d), ((e // e\n))));";
+
+    let cst = parse(text, ctx.as_ref()).unwrap();
+    assert_eq!(cst.root.text(), text);
+
+    let nodes = cst
+        .root
+        .descendants()
+        .filter(|node| matches!(node.kind(), NodeKind::ParenExpr | NodeKind::TupleExpr))
+        .map(|node| (node.kind(), node.text()))
+        .collect::<Vec<_>>();
+
+    let expected = [
+        (NodeKind::ParenExpr, "(a)"),
+        (NodeKind::ParenExpr, "( (b + c) )"),
+        (NodeKind::ParenExpr, "(b + c)"),
+        (NodeKind::TupleExpr, "((d), ((e // e\n)))"),
+        (NodeKind::ParenExpr, "(d)"),
+        (NodeKind::ParenExpr, "((e // e\n))"),
+        (NodeKind::ParenExpr, "(e // e\n)"),
+    ];
+
+    assert_eq!(
+        nodes,
+        expected
+            .iter()
+            .map(|&(kind, text)| (kind, text.to_owned()))
+            .collect::<Vec<_>>()
+    );
+
+    let call = cst
+        .root
+        .descendants()
+        .find(|node| node.kind() == NodeKind::CallExpr)
+        .unwrap();
+    assert_eq!(call.text(), "f((a), ( (b + c) ), ((d), ((e // e\n))))");
+}
+
+#[test]
+fn typed_view_and_edit() {
+    let ctx = lib_parser::context::LocalContext::default();
+    let text = "fn f() -> i32 {\n    // keep me\n    1 + 2 // and me\n}";
+
+    let cst = parse(text, ctx.as_ref()).unwrap();
+
+    let tail = match cst.items[0] {
+        HAst::Function(ref function) => function.body.tail.as_ref().unwrap(),
+        ref item => panic!("expected a function, found {:?}", item),
+    };
+
+    let node = cst.syntax(&**tail).unwrap();
+    assert_eq!(node.kind(), NodeKind::BinaryExpr);
+    assert_eq!(node.text(), "1 + 2");
+    assert_eq!(
+        node.ancestors().map(|node| node.kind()).collect::<Vec<_>>(),
+        [
+            NodeKind::BinaryExpr,
+            NodeKind::Block,
+            NodeKind::Function,
+            NodeKind::Root
+        ]
+    );
+
+    // a statement and its expression cover the same text, but have their own nodes
+    let text = "if x { 1 } else { 2 }\n(y);";
+    let cst = parse(text, ctx.as_ref()).unwrap();
+
+    let stmt = &cst.items[0];
+    let expr = match stmt {
+        HAst::Expr(stmt) => &*stmt.expr,
+        item => panic!("expected an expression statement, found {:?}", item),
+    };
+
+    assert_eq!(cst.syntax(stmt).unwrap().kind(), NodeKind::ExprStmt);
+    assert_eq!(cst.syntax(expr).unwrap().kind(), NodeKind::IfExpr);
+    assert_eq!(
+        cst.syntax(stmt).unwrap().text_range(),
+        cst.syntax(expr).unwrap().text_range()
+    );
+
+    let replacement = parse("3;", ctx.as_ref()).unwrap();
+    let three = replacement.root.descendants().nth(2).unwrap();
+    assert_eq!(three.kind(), NodeKind::Literal);
+
+    let edited = SyntaxNode::new_root(node.replace_with(three.green().clone()));
+    assert_eq!(
+        edited.text(),
+        "fn f() -> i32 {\n    // keep me\n    3 // and me\n}"
+    );
+}
+
+#[test]
+fn reparse() {
+    let text = "fn f(x: i32) -> i32 {\n    let y = x + 1; // one\n    y * 2\n}\n\nfn g() {\n    f(1);\n}\n";
+
+    let at = |pattern: &str| {
+        let start = text.find(pattern).unwrap();
+        start..start + pattern.len()
+    };
+
+    // the edit, and which of the functions is reused
+    let edits = [
+        (TextEdit::new(at("x + 1"), "x +\n        10 * x"), Some(1)),
+        (TextEdit::new(at("y * 2"), "y"), Some(1)),
+        (
+            TextEdit::new(at("f(1);").start..at("f(1);").start, "f(2);\n    "),
+            Some(0),
+        ),
+        // the last statement becomes the tail, so the whole block is reparsed
+        (TextEdit::new(at("(1);"), "(1)"), Some(0)),
+        // crosses the end of the first function
+        (
+            TextEdit::new(at("2\n}\n\nfn g() {"), "2 + g()\n}\n\nfn g() {"),
+            None,
+        ),
+    ];
+
+    // the typed trees share the text of the old tree, so it must outlive them
+    let new_texts = edits
+        .iter()
+        .map(|(edit, _)| edit.apply(text))
+        .collect::<Vec<_>>();
+    let unbalanced = TextEdit::new(at("(1);"), "(1;");
+    let unbalanced_text = unbalanced.apply(text);
+
+    let ctx = lib_parser::context::LocalContext::default();
+    let old = parse(text, ctx.as_ref()).unwrap();
+
+    for ((edit, reused), new_text) in edits.iter().zip(&new_texts) {
+        let ctx = lib_parser::context::LocalContext::default();
+        let new = old.reparse(edit, new_text, ctx.as_ref()).unwrap();
+
+        let full_ctx = lib_parser::context::LocalContext::default();
+        let full = parse(new_text, full_ctx.as_ref()).unwrap();
+
+        assert_eq!(&new.root.text(), new_text);
+        assert_eq!(format!("{:#?}", new.root), format!("{:#?}", full.root));
+        assert_eq!(format!("{:?}", new.items), format!("{:?}", full.items));
+
+        if let Some(reused) = *reused {
+            let old_node = old.root.children().nth(reused).unwrap();
+            let new_node = new.root.children().nth(reused).unwrap();
+            assert!(old_node.green().ptr_eq(new_node.green()));
+        }
+    }
+
+    // unbalanced delimiters fall back to a full parse, which reports the error
+    let ctx = lib_parser::context::LocalContext::default();
+    assert!(old
+        .reparse(&unbalanced, &unbalanced_text, ctx.as_ref())
+        .is_err());
+}
+
+#[test]
+fn split_closing_angle() {
+    let ctx = lib_parser::context::LocalContext::default();
+    let text = "let x: Vec<Vec<u8>>= v;\n";
+
+    let cst = parse(text, ctx.as_ref()).unwrap();
+    assert_eq!(cst.root.text(), text);
+
+    let types = cst
+        .root
+        .descendants()
+        .filter(|node| node.kind() == NodeKind::NamedType)
+        .map(|node| node.text())
+        .collect::<Vec<_>>();
+    assert_eq!(types, ["Vec<Vec<u8>>", "Vec<u8>", "u8"]);
+
+    match cst.items[0] {
+        HAst::Let(lib_parser_types::Let {
+            ty: Some(ref ty), ..
+        }) => assert_eq!(cst.syntax(&ty.ty).unwrap().text(), "Vec<Vec<u8>>"),
+        ref ast => panic!("expected an annotated let, found {:?}", ast),
+    }
+}
+
+#[test]
+fn non_ascii_indentation() {
+    // an ideographic space is three bytes, and columns count bytes
+    let text = "\n\u{3000}let x = 1;\nfn f() {\n\u{3000}\u{3000}x\n}\n";
+
+    let ctx = lib_parser::context::LocalContext::default();
+    let cst = parse(text, ctx.as_ref()).unwrap();
+
+    assert_eq!(cst.syntax(&cst.items[0]).unwrap().text(), "let x = 1;");
+    assert_eq!(
+        cst.syntax(&cst.items[1]).unwrap().text(),
+        "fn f() {\n\u{3000}\u{3000}x\n}"
+    );
+
+    let start = text.rfind('x').unwrap();
+    let edit = TextEdit::new(start..start + 1, "x + 1");
+    let new_text = edit.apply(text);
+
+    let new_ctx = lib_parser::context::LocalContext::default();
+    let new = cst.reparse(&edit, &new_text, new_ctx.as_ref()).unwrap();
+
+    assert_eq!(new.root.text(), new_text);
+    assert_eq!(
+        new.syntax(&new.items[1]).unwrap().text(),
+        "fn f() {\n\u{3000}\u{3000}x + 1\n}"
+    );
+    // only the body of `f` is reparsed
+    let old_let = cst.root.children().next().unwrap();
+    let new_let = new.root.children().next().unwrap();
+    assert!(old_let.green().ptr_eq(new_let.green()));
+}
@@ -0,0 +1,259 @@
+use std::fmt;
+use std::ops::Range;
+use std::rc::Rc;
+
+use super::{GreenElement, GreenNode, GreenToken, NodeKind, TokenKind};
+
+/// a cursor into a green tree, which knows its parent and its absolute offset
+///
+/// red nodes are created on demand while walking the tree, and are cheap to clone
+#[derive(Clone)]
+pub struct SyntaxNode(Rc<NodeData>);
+
+struct NodeData {
+    green: GreenNode,
+    parent: Option<SyntaxNode>,
+    /// the index of this node in the children of its parent
+    index: usize,
+    offset: usize,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct SyntaxToken {
+    parent: SyntaxNode,
+    index: usize,
+    offset: usize,
+    green: GreenToken,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+}
+
+impl SyntaxNode {
+    pub fn new_root(green: GreenNode) -> Self {
+        Self(Rc::new(NodeData {
+            green,
+            parent: None,
+            index: 0,
+            offset: 0,
+        }))
+    }
+
+    pub fn green(&self) -> &GreenNode {
+        &self.0.green
+    }
+
+    pub fn kind(&self) -> NodeKind {
+        self.0.green.kind()
+    }
+
+    pub fn parent(&self) -> Option<&SyntaxNode> {
+        self.0.parent.as_ref()
+    }
+
+    /// the byte offset of the start of this node in the text of the root
+    pub fn offset(&self) -> usize {
+        self.0.offset
+    }
+
+    pub fn text_range(&self) -> Range<usize> {
+        self.offset()..self.offset() + self.green().text_len()
+    }
+
+    pub fn text(&self) -> String {
+        self.green().to_string()
+    }
+
+    pub fn children_with_tokens(&self) -> impl Iterator<Item = SyntaxElement> + '_ {
+        let mut offset = self.offset();
+
+        self.green()
+            .children()
+            .iter()
+            .enumerate()
+            .map(move |(index, child)| {
+                let start = offset;
+                offset += child.text_len();
+
+                match child {
+                    GreenElement::Node(green) => SyntaxElement::Node(Self(Rc::new(NodeData {
+                        green: green.clone(),
+                        parent: Some(self.clone()),
+                        index,
+                        offset: start,
+                    }))),
+                    GreenElement::Token(green) => SyntaxElement::Token(SyntaxToken {
+                        parent: self.clone(),
+                        index,
+                        offset: start,
+                        green: green.clone(),
+                    }),
+                }
+            })
+    }
+
+    pub fn children(&self) -> impl Iterator<Item = SyntaxNode> + '_ {
+        self.children_with_tokens().filter_map(|child| match child {
+            SyntaxElement::Node(node) => Some(node),
+            SyntaxElement::Token(_) => None,
+        })
+    }
+
+    /// this node and its ancestors, from the innermost to the root
+    pub fn ancestors(&self) -> impl Iterator<Item = SyntaxNode> {
+        std::iter::successors(Some(self.clone()), |node| node.parent().cloned())
+    }
+
+    /// this node and every node in it, in preorder
+    pub fn descendants(&self) -> impl Iterator<Item = SyntaxNode> {
+        let mut stack = vec![self.clone()];
+
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            let start = stack.len();
+            stack.extend(node.children());
+            stack[start..].reverse();
+            Some(node)
+        })
+    }
+
+    /// the token that contains the byte at `offset`
+    pub fn token_at_offset(&self, offset: usize) -> Option<SyntaxToken> {
+        let child = self
+            .children_with_tokens()
+            .find(|child| child.text_range().contains(&offset))?;
+
+        match child {
+            SyntaxElement::Node(node) => node.token_at_offset(offset),
+            SyntaxElement::Token(token) => Some(token),
+        }
+    }
+
+    /// the innermost node that contains all of `range`
+    pub fn covering_node(&self, range: Range<usize>) -> SyntaxNode {
+        let child = self.children().find(|child| {
+            let child_range = child.text_range();
+            child_range.start <= range.start && range.end <= child_range.end
+        });
+
+        match child {
+            Some(child) => child.covering_node(range),
+            None => self.clone(),
+        }
+    }
+
+    /// a new root where this node is replaced by `green`, everything outside
+    /// of this node, including trivia, is kept as is
+    pub fn replace_with(&self, green: GreenNode) -> GreenNode {
+        match self.parent() {
+            Some(parent) => {
+                let green = parent
+                    .green()
+                    .replace_child(self.0.index, GreenElement::Node(green));
+                parent.replace_with(green)
+            }
+            None => green,
+        }
+    }
+}
+
+/// nodes are equal if they have equal green nodes at the same offset
+impl PartialEq for SyntaxNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.offset() == other.offset() && self.green() == other.green()
+    }
+}
+
+impl Eq for SyntaxNode {}
+
+/// `Kind@start..end`, or the whole tree with `{:#?}`
+impl fmt::Debug for SyntaxNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            self.fmt_tree(f, 0)
+        } else {
+            write!(f, "{:?}@{:?}", self.kind(), self.text_range())
+        }
+    }
+}
+
+impl SyntaxNode {
+    fn fmt_tree(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        writeln!(
+            f,
+            "{:indent$}{:?}@{:?}",
+            "",
+            self.kind(),
+            self.text_range(),
+            indent = depth * 2
+        )?;
+
+        for child in self.children_with_tokens() {
+            match child {
+                SyntaxElement::Node(node) => node.fmt_tree(f, depth + 1)?,
+                SyntaxElement::Token(token) => {
+                    writeln!(f, "{:indent$}{:?}", "", token, indent = (depth + 1) * 2)?
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl SyntaxToken {
+    pub fn green(&self) -> &GreenToken {
+        &self.green
+    }
+
+    pub fn kind(&self) -> TokenKind {
+        self.green.kind()
+    }
+
+    pub fn text(&self) -> &str {
+        self.green.text()
+    }
+
+    pub fn parent(&self) -> &SyntaxNode {
+        &self.parent
+    }
+
+    pub fn text_range(&self) -> Range<usize> {
+        self.offset..self.offset + self.green.text_len()
+    }
+
+    /// a new root where this token is replaced by `green`
+    pub fn replace_with(&self, green: GreenToken) -> GreenNode {
+        let parent = self
+            .parent
+            .green()
+            .replace_child(self.index, GreenElement::Token(green));
+
+        self.parent.replace_with(parent)
+    }
+}
+
+/// `Kind@start..end "text"`
+impl fmt::Debug for SyntaxToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?}@{:?} {:?}",
+            self.kind(),
+            self.text_range(),
+            self.text()
+        )
+    }
+}
+
+impl SyntaxElement {
+    pub fn text_range(&self) -> Range<usize> {
+        match self {
+            SyntaxElement::Node(node) => node.text_range(),
+            SyntaxElement::Token(token) => token.text_range(),
+        }
+    }
+}
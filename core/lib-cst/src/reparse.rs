@@ -0,0 +1,302 @@
+use std::ops::Range;
+
+use lib_lexer_types::{CodePoint, LexemeKind, Symbol, Token, TokenData};
+use lib_parser_types::{
+    context::ContextRef,
+    fold::{self, Fold},
+    visit::{self, Visitor},
+    Block, HAst, ParseError, Spanned,
+};
+
+use super::build::{lex, Builder, Tokens};
+use super::{line_starts, Cst, GreenElement, NodeKind, SyntaxNode, TypedNode};
+
+/// replaces the text in `range` with `insert`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: Range<usize>,
+    pub insert: String,
+}
+
+impl TextEdit {
+    pub fn new(range: Range<usize>, insert: impl Into<String>) -> Self {
+        Self {
+            range,
+            insert: insert.into(),
+        }
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        let mut text = text.to_owned();
+        text.replace_range(self.range.clone(), &self.insert);
+        text
+    }
+
+    /// moves an offset after the edit to where it is after applying the edit
+    fn shift(&self, offset: usize) -> usize {
+        offset - self.range.len() + self.insert.len()
+    }
+}
+
+/// a node that may be reparsed on its own, from the innermost to the outermost
+struct Candidate {
+    kind: NodeKind,
+    /// the byte range of the typed node in the old text
+    range: Range<usize>,
+}
+
+/// finds the statements and blocks around an edit
+struct Candidates<'a, 'input, 'hacx> {
+    cst: &'a Cst<'input, 'hacx>,
+    edit: &'a Range<usize>,
+    found: Vec<Candidate>,
+}
+
+impl<'input, 'hacx> Visitor<'input, 'hacx> for Candidates<'_, 'input, 'hacx> {
+    fn visit_hast(&mut self, ast: &HAst<'input, 'hacx>) {
+        let range = self.cst.range(ast.span());
+
+        if range.start <= self.edit.start && self.edit.end <= range.end {
+            self.found.push(Candidate {
+                kind: ast.syntax_kind(),
+                range,
+            });
+            visit::walk_hast(self, ast);
+        }
+    }
+
+    fn visit_block(&mut self, block: &Block<'input, 'hacx>) {
+        let range = self.cst.range(block.span());
+
+        // an edit that touches the braces changes where the block ends
+        if range.start < self.edit.start && self.edit.end < range.end {
+            self.found.push(Candidate {
+                kind: NodeKind::Block,
+                range,
+            });
+            visit::walk_block(self, block);
+        }
+    }
+}
+
+enum Replacement<'input, 'new> {
+    Stmt(HAst<'input, 'new>),
+    Block(Block<'input, 'new>),
+}
+
+/// copies the old typed tree into the new context, with the reparsed node in
+/// place of the old one and every token after the edit moved to its new position
+struct Splice<'a, 'input, 'hacx, 'new> {
+    old: &'a Cst<'input, 'hacx>,
+    edit: &'a TextEdit,
+    lines: &'a [usize],
+    target: Range<usize>,
+    replacement: Option<Replacement<'input, 'new>>,
+    ctx: ContextRef<'input, 'new>,
+}
+
+impl<'input, 'new> Fold<'input, 'new> for Splice<'_, 'input, '_, 'new> {
+    fn context(&self) -> ContextRef<'input, 'new> {
+        self.ctx
+    }
+
+    fn fold_hast(&mut self, ast: &HAst<'input, '_>) -> HAst<'input, 'new> {
+        if let Some(Replacement::Stmt(_)) = self.replacement {
+            if self.old.range(ast.span()) == self.target {
+                match self.replacement.take() {
+                    Some(Replacement::Stmt(ast)) => return ast,
+                    _ => unreachable!(),
+                }
+            }
+        }
+
+        fold::walk_hast(self, ast)
+    }
+
+    fn fold_block(&mut self, block: &Block<'input, '_>) -> Block<'input, 'new> {
+        if let Some(Replacement::Block(_)) = self.replacement {
+            if self.old.range(block.span()) == self.target {
+                match self.replacement.take() {
+                    Some(Replacement::Block(block)) => return block,
+                    _ => unreachable!(),
+                }
+            }
+        }
+
+        fold::walk_block(self, block)
+    }
+
+    fn fold_token(&mut self, token: Token<'input>) -> Token<'input> {
+        let range = self.old.range(token.span);
+
+        if range.start < self.edit.range.end {
+            return token;
+        }
+
+        let start = point(self.lines, self.edit.shift(range.start));
+        let end = point(self.lines, self.edit.shift(range.end));
+
+        Token {
+            span: start.span(end),
+            ..token
+        }
+    }
+}
+
+/// the position of a byte offset, given the offsets of the starts of the lines
+fn point(lines: &[usize], offset: usize) -> CodePoint {
+    let row = lines.partition_point(|&start| start <= offset);
+
+    CodePoint::new_unchecked(row as u32, (offset - lines[row - 1] + 1) as u32)
+}
+
+/// whether every delimiter in `text` is closed by the matching delimiter
+fn is_balanced(text: &str, start: CodePoint) -> bool {
+    let mut lexer = lib_lexer::Lexer::with_start(text, start);
+    let mut open = Vec::new();
+
+    loop {
+        let symbol = match lexer.parse_lossless() {
+            Ok(Some(lexeme)) => match lexeme.kind {
+                LexemeKind::Token(TokenData::Symbol(symbol)) => symbol,
+                _ => continue,
+            },
+            Ok(None) => break open.is_empty(),
+            // the full parse reports the error
+            Err(_) => break false,
+        };
+
+        let close = match symbol {
+            Symbol::OpenParen => Symbol::CloseParen,
+            Symbol::OpenBracket => Symbol::CloseBracket,
+            Symbol::OpenBrace => Symbol::CloseBrace,
+            Symbol::CloseParen | Symbol::CloseBracket | Symbol::CloseBrace => {
+                if open.pop() != Some(symbol) {
+                    break false;
+                }
+
+                continue;
+            }
+            _ => continue,
+        };
+
+        open.push(close);
+    }
+}
+
+impl<'input, 'hacx> Cst<'input, 'hacx> {
+    /// parses `text`, which is the text of this tree after `edit`, reusing as
+    /// much of this tree as possible
+    ///
+    /// only the innermost statement or block around the edit is reparsed, and
+    /// the rest of the typed tree is copied into `ctx` from this tree, which
+    /// is why `text` must live as long as the text of this tree. If the edit
+    /// unbalances delimiters, or the reparsed text doesn't fit in place of the
+    /// old node, the whole text is parsed again.
+    pub fn reparse<'new>(
+        &self,
+        edit: &TextEdit,
+        text: &'input str,
+        ctx: ContextRef<'input, 'new>,
+    ) -> Result<Cst<'input, 'new>, ParseError> {
+        debug_assert_eq!(
+            edit.shift(self.root.green().text_len()),
+            text.len(),
+            "`text` is the text after `edit`"
+        );
+
+        let lines = line_starts(text);
+
+        let mut candidates = Candidates {
+            cst: self,
+            edit: &edit.range,
+            found: Vec::new(),
+        };
+
+        for item in &self.items {
+            candidates.visit_hast(item);
+        }
+
+        for candidate in candidates.found.into_iter().rev() {
+            let node = self
+                .find_node(candidate.kind, candidate.range.clone())
+                .expect("the typed node has a syntax node");
+
+            let old = node.text_range();
+            let new = old.start..edit.shift(old.end);
+            let start = point(&lines, new.start);
+
+            // the text around the node is balanced, so if the node isn't then
+            // neither is anything around it
+            if !is_balanced(&text[new.clone()], start) {
+                break;
+            }
+
+            if let Some(cst) = self.reparse_node(&node, candidate, edit, text, new, &lines, ctx) {
+                return Ok(cst);
+            }
+        }
+
+        super::parse(text, ctx)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn reparse_node<'new>(
+        &self,
+        node: &SyntaxNode,
+        candidate: Candidate,
+        edit: &TextEdit,
+        text: &'input str,
+        range: Range<usize>,
+        lines: &[usize],
+        ctx: ContextRef<'input, 'new>,
+    ) -> Option<Cst<'input, 'new>> {
+        let fragment = &text[range.clone()];
+        let start = point(lines, range.start);
+
+        let lexemes = lex(fragment, start).ok()?;
+        let mut parser = lib_parser::Parser::new(Tokens::new(&lexemes), ctx);
+        let mut builder = Builder::new(&lexemes);
+
+        let replacement = if candidate.kind == NodeKind::Block {
+            let block = parser.parse_block_expr().ok()?;
+            builder.visit_block(&block);
+            Replacement::Block(block)
+        } else {
+            let ast = parser.parse().ok()??;
+            builder.visit_hast(&ast);
+            Replacement::Stmt(ast)
+        };
+
+        // the fragment must be exactly one node
+        if !matches!(parser.parse(), Ok(None)) {
+            return None;
+        }
+
+        let green = match builder.finish().children() {
+            [GreenElement::Node(green)] => green.clone(),
+            _ => return None,
+        };
+
+        let mut splice = Splice {
+            old: self,
+            edit,
+            lines,
+            target: candidate.range,
+            replacement: Some(replacement),
+            ctx,
+        };
+
+        let items = self
+            .items
+            .iter()
+            .map(|item| splice.fold_hast(item))
+            .collect();
+
+        Some(Cst {
+            root: SyntaxNode::new_root(node.replace_with(green)),
+            items,
+            lines: lines.to_vec(),
+        })
+    }
+}
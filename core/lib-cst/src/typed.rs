@@ -0,0 +1,146 @@
+use lib_lexer_types::Span;
+use lib_parser_types::{Attribute, Block, Expr, HAst, MatchArm, Param, Pattern, Spanned, TypeExpr};
+
+use super::NodeKind;
+
+/// a typed node that has a syntax node of its own
+///
+/// the syntax node of a typed node is the one with the same kind and the same
+/// text range, which no other syntax node has, so `Cst::syntax` finds it exactly
+pub trait TypedNode {
+    fn syntax_kind(&self) -> NodeKind;
+
+    /// the span of the syntax node, which for most nodes is their `Spanned::span`
+    fn syntax_span(&self) -> Span;
+}
+
+impl TypedNode for HAst<'_, '_> {
+    fn syntax_kind(&self) -> NodeKind {
+        match self {
+            HAst::Let(_) => NodeKind::Let,
+            HAst::Assign(_) => NodeKind::Assign,
+            HAst::TypeAlias(_) => NodeKind::TypeAlias,
+            HAst::Function(_) => NodeKind::Function,
+            HAst::Struct(_) => NodeKind::Struct,
+            HAst::Enum(_) => NodeKind::Enum,
+            HAst::Mod(_) => NodeKind::Mod,
+            HAst::Use(_) => NodeKind::Use,
+            HAst::Expr(_) => NodeKind::ExprStmt,
+        }
+    }
+
+    fn syntax_span(&self) -> Span {
+        self.span()
+    }
+}
+
+impl TypedNode for Attribute<'_> {
+    fn syntax_kind(&self) -> NodeKind {
+        NodeKind::Attribute
+    }
+
+    fn syntax_span(&self) -> Span {
+        self.span()
+    }
+}
+
+impl TypedNode for Param<'_, '_> {
+    fn syntax_kind(&self) -> NodeKind {
+        NodeKind::Param
+    }
+
+    fn syntax_span(&self) -> Span {
+        self.ident.span.merge(self.ty.span())
+    }
+}
+
+impl TypedNode for Block<'_, '_> {
+    fn syntax_kind(&self) -> NodeKind {
+        NodeKind::Block
+    }
+
+    fn syntax_span(&self) -> Span {
+        self.span()
+    }
+}
+
+impl TypedNode for MatchArm<'_, '_> {
+    fn syntax_kind(&self) -> NodeKind {
+        NodeKind::MatchArm
+    }
+
+    fn syntax_span(&self) -> Span {
+        let end = match self.sym_comma {
+            Some(sym_comma) => sym_comma.span,
+            None => self.value.span(),
+        };
+
+        self.pattern.span().merge(end)
+    }
+}
+
+impl TypedNode for Expr<'_, '_> {
+    fn syntax_kind(&self) -> NodeKind {
+        match self {
+            // the block is the node of the expression
+            Expr::Block(_) => NodeKind::Block,
+            Expr::Literal(_) => NodeKind::Literal,
+            Expr::Identifier(_) => NodeKind::Identifier,
+            Expr::Path(_) => NodeKind::PathExpr,
+            Expr::Prefix(..) => NodeKind::PrefixExpr,
+            Expr::Postfix(..) => NodeKind::PostfixExpr,
+            Expr::Binary(..) => NodeKind::BinaryExpr,
+            Expr::Logical(..) => NodeKind::LogicalExpr,
+            Expr::Match(_) => NodeKind::MatchExpr,
+            Expr::If(_) => NodeKind::IfExpr,
+            Expr::Call(_) => NodeKind::CallExpr,
+            Expr::Field(_) => NodeKind::FieldExpr,
+            Expr::TupleIndex(_) => NodeKind::TupleIndexExpr,
+            Expr::MethodCall(_) => NodeKind::MethodCallExpr,
+            Expr::Paren(_) => NodeKind::ParenExpr,
+            Expr::Tuple(_) => NodeKind::TupleExpr,
+            Expr::Array(_) => NodeKind::ArrayExpr,
+            Expr::Struct(_) => NodeKind::StructExpr,
+            Expr::Closure(_) => NodeKind::ClosureExpr,
+            Expr::Turbofish(_) => NodeKind::TurbofishExpr,
+        }
+    }
+
+    fn syntax_span(&self) -> Span {
+        self.span()
+    }
+}
+
+impl TypedNode for Pattern<'_> {
+    fn syntax_kind(&self) -> NodeKind {
+        match self {
+            Pattern::Literal(_) => NodeKind::LiteralPattern,
+            Pattern::Binding(_) => NodeKind::BindingPattern,
+            Pattern::Path(_) => NodeKind::PathPattern,
+            Pattern::Wildcard(_) => NodeKind::WildcardPattern,
+            Pattern::Or(_) => NodeKind::OrPattern,
+            Pattern::Tuple(_) => NodeKind::TuplePattern,
+            Pattern::Array(_) => NodeKind::ArrayPattern,
+            Pattern::Struct(_) => NodeKind::StructPattern,
+            Pattern::TupleStruct(_) => NodeKind::TupleStructPattern,
+        }
+    }
+
+    fn syntax_span(&self) -> Span {
+        self.span()
+    }
+}
+
+impl TypedNode for TypeExpr<'_, '_> {
+    fn syntax_kind(&self) -> NodeKind {
+        match self {
+            TypeExpr::Named(_) => NodeKind::NamedType,
+            TypeExpr::Tuple(_) => NodeKind::TupleType,
+            TypeExpr::Function(_) => NodeKind::FunctionType,
+        }
+    }
+
+    fn syntax_span(&self) -> Span {
+        self.span()
+    }
+}
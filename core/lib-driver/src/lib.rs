@@ -0,0 +1,321 @@
+#![allow(clippy::try_err)]
+
+use std::path::{Path, PathBuf};
+
+use lib_arena::Arena;
+use lib_error::WithContext as _;
+use lib_lexer_types::TokenData;
+use lib_parser_types::{context::ContextRef, HAst, ParseError};
+use lib_str_interner::ThinStr;
+
+mod parallel;
+
+pub use parallel::{parse_files, Module, Parsed, SyncContext};
+
+pub type LoadError = lib_error::Error<Error, ParseError>;
+pub type Result<T, E = LoadError> = std::result::Result<T, E>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// the file couldn't be read
+    Io(PathBuf, std::io::ErrorKind),
+    /// neither `name.ext` nor `name/mod.ext` exists for `mod name;` in the given file
+    ModuleNotFound(FileId, ThinStr),
+    /// both `name.ext` and `name/mod.ext` exist for `mod name;` in the given file
+    AmbiguousModule(FileId, ThinStr),
+    ParseError(FileId),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io(path, kind) => write!(
+                f,
+                "couldn't read {}: {}",
+                path.display(),
+                std::io::Error::from(*kind)
+            ),
+            Error::ModuleNotFound(_, name) => {
+                write!(f, "couldn't find the file of module `{}`", name)
+            }
+            Error::AmbiguousModule(_, name) => {
+                write!(
+                    f,
+                    "module `{}` has both a `{0}` file and a `{0}/mod` file",
+                    name
+                )
+            }
+            Error::ParseError(_) => f.write_str("couldn't parse the file"),
+        }
+    }
+}
+
+/// identifies a file loaded by a `Loader`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FileId(u32);
+
+impl FileId {
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+pub struct SourceFile<'input, 'hacx> {
+    pub path: PathBuf,
+    /// the file that declared this module, or `None` for the root
+    pub parent: Option<FileId>,
+    pub text: &'input str,
+    pub items: Vec<HAst<'input, 'hacx>>,
+    /// the modules declared by `mod name;` items in this file, in declaration order
+    pub modules: Vec<(ThinStr, FileId)>,
+}
+
+/// loads a root file and every module it declares, transitively
+///
+/// `mod name;` in a file `dir/file.ext` is looked up at `dir/file/name.ext`
+/// and `dir/file/name/mod.ext`, except in the root file and in `mod.ext`
+/// files, where it is looked up next to the declaring file. All modules use
+/// the extension of the root file.
+pub struct Loader<'input, 'hacx> {
+    sources: &'input dyn Arena<Value = String>,
+    ctx: ContextRef<'input, 'hacx>,
+    files: Vec<SourceFile<'input, 'hacx>>,
+}
+
+impl<'input, 'hacx> Loader<'input, 'hacx> {
+    pub fn new(sources: &'input dyn Arena<Value = String>, ctx: ContextRef<'input, 'hacx>) -> Self {
+        Self {
+            sources,
+            ctx,
+            files: Vec::new(),
+        }
+    }
+
+    pub fn load(&mut self, root: impl AsRef<Path>) -> Result<FileId> {
+        let root = root.as_ref();
+        let extension = root.extension().map(ToOwned::to_owned);
+        let root_id = self.load_file(root.to_owned(), None)?;
+
+        let mut next = root_id.index();
+
+        while let Some(file) = self.files.get(next) {
+            let id = FileId(next as u32);
+            let dir = module_dir(&file.path, file.parent.is_none());
+
+            let names = file
+                .items
+                .iter()
+                .filter_map(|item| match item {
+                    HAst::Mod(ast_mod) => match ast_mod.ident.data {
+                        TokenData::Identifier(name) => Some(name),
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+
+            for name in names {
+                let path = self.resolve(id, &dir, name, extension.as_deref())?;
+                let module = self.load_file(path, Some(id))?;
+
+                self.files[id.index()].modules.push((name, module));
+            }
+
+            next += 1;
+        }
+
+        Ok(root_id)
+    }
+
+    fn resolve(
+        &self,
+        id: FileId,
+        dir: &Path,
+        name: ThinStr,
+        extension: Option<&std::ffi::OsStr>,
+    ) -> Result<PathBuf> {
+        let mut file = dir.join(name.to_str());
+        let mut mod_file = file.join("mod");
+
+        if let Some(extension) = extension {
+            file.set_extension(extension);
+            mod_file.set_extension(extension);
+        }
+
+        match (file.is_file(), mod_file.is_file()) {
+            (true, false) => Ok(file),
+            (false, true) => Ok(mod_file),
+            (true, true) => Err(Error::AmbiguousModule(id, name))?,
+            (false, false) => Err(Error::ModuleNotFound(id, name))?,
+        }
+    }
+
+    fn load_file(&mut self, path: PathBuf, parent: Option<FileId>) -> Result<FileId> {
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) => Err(Error::Io(path.clone(), err.kind()))?,
+        };
+
+        let text: &'input str = self.sources.alloc(text);
+        let id = FileId(self.files.len() as u32);
+
+        let mut parser = lib_parser::Parser::new(lib_lexer::Lexer::new(text), self.ctx);
+        let mut items = Vec::new();
+
+        while let Some(item) = parser.parse().with_context(Error::ParseError(id))? {
+            items.push(item);
+        }
+
+        self.files.push(SourceFile {
+            path,
+            parent,
+            text,
+            items,
+            modules: Vec::new(),
+        });
+
+        Ok(id)
+    }
+
+    pub fn file(&self, id: FileId) -> &SourceFile<'input, 'hacx> {
+        &self.files[id.index()]
+    }
+
+    pub fn files(&self) -> impl Iterator<Item = (FileId, &SourceFile<'input, 'hacx>)> {
+        self.files
+            .iter()
+            .enumerate()
+            .map(|(index, file)| (FileId(index as u32), file))
+    }
+}
+
+/// the directory that holds the modules declared in the file at `path`
+fn module_dir(path: &Path, is_root: bool) -> PathBuf {
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+    if is_root || path.file_stem() == Some("mod".as_ref()) {
+        dir.to_owned()
+    } else {
+        dir.join(path.file_stem().unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+fn temp_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("lib-driver-{}-{}", name, std::process::id()));
+
+    for &(path, text) in files {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, text).unwrap();
+    }
+
+    dir
+}
+
+#[test]
+fn load_modules() {
+    let dir = temp_dir(
+        "load_modules",
+        &[
+            (
+                "main.lang",
+                "mod shapes; mod util; use shapes::circle::Circle;",
+            ),
+            ("shapes/mod.lang", "mod circle;"),
+            ("shapes/circle.lang", "struct Circle(f32);"),
+            ("util.lang", "mod math;"),
+            ("util/math.lang", "fn one() -> i32 { 1 }"),
+        ],
+    );
+
+    let sources = lib_arena::local::LocalArena::new();
+    let ctx = lib_parser::context::LocalContext::default();
+    let mut loader = Loader::new(&sources, ctx.as_ref());
+
+    let root = loader.load(dir.join("main.lang")).unwrap();
+
+    let paths = loader
+        .files()
+        .map(|(_, file)| file.path.strip_prefix(&dir).unwrap().to_owned())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        paths,
+        [
+            Path::new("main.lang"),
+            Path::new("shapes/mod.lang"),
+            Path::new("util.lang"),
+            Path::new("shapes/circle.lang"),
+            Path::new("util/math.lang"),
+        ]
+    );
+
+    let root = loader.file(root);
+    assert_eq!(root.items.len(), 3);
+    assert_eq!(root.modules.len(), 2);
+
+    let (name, circle) = loader.file(root.modules[0].1).modules[0];
+    assert_eq!(name.to_str(), "circle");
+    assert_eq!(loader.file(circle).parent, Some(root.modules[0].1));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn missing_module() {
+    let dir = temp_dir("missing_module", &[("main.lang", "mod missing;")]);
+
+    let sources = lib_arena::local::LocalArena::new();
+    let ctx = lib_parser::context::LocalContext::default();
+    let mut loader = Loader::new(&sources, ctx.as_ref());
+
+    let err = loader.load(dir.join("main.lang")).unwrap_err();
+
+    assert_eq!(
+        *err.err(),
+        Error::ModuleNotFound(FileId(0), lib_str_interner::intern("missing"))
+    );
+    assert_eq!(
+        err.to_string(),
+        "couldn't find the file of module `missing`"
+    );
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn parse_error_report() {
+    let dir = temp_dir("parse_error_report", &[("main.lang", "let x = $;")]);
+
+    let sources = lib_arena::local::LocalArena::new();
+    let ctx = lib_parser::context::LocalContext::default();
+    let mut loader = Loader::new(&sources, ctx.as_ref());
+
+    let err = loader.load(dir.join("main.lang")).unwrap_err();
+    let span = err.cause().unwrap().err().span;
+    let report = lib_error::Report::from(err).attach(span);
+
+    assert_eq!(report.downcast_ref(), Some(&Error::ParseError(FileId(0))));
+    assert_eq!(
+        report
+            .downcast_ref::<lib_lexer_types::Error>()
+            .map(|err| &err.err),
+        Some(&lib_lexer_types::ErrorType::UnknownCharacter('$'))
+    );
+    assert!(report.contains::<lib_parser_types::Error>());
+    assert_eq!(
+        report
+            .attachments::<lib_lexer_types::Span>()
+            .collect::<Vec<_>>(),
+        [&span]
+    );
+    assert_eq!(report.to_string(), "couldn't parse the file");
+    assert_eq!(
+        format!("{:#}", report),
+        "couldn't parse the file, caused by: couldn't read the next token at 1:9, \
+         caused by: unknown character '$' at 1:9"
+    );
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,165 @@
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+
+use lib_error::WithContext as _;
+use lib_parser_types::{
+    context, Assign, Enum, Expr, Function, HAst, Let, Mod, Struct, TypeAlias, TypeExpr, Use,
+};
+
+use super::{Error, FileId, LoadError};
+
+/// a context that many threads can allocate syntax trees in at once
+pub type SyncContext<'input, 'hacx> = context::SyncContext<
+    HAst<'input, 'hacx>,
+    Let<'input, 'hacx>,
+    Assign<'input, 'hacx>,
+    Expr<'input, 'hacx>,
+    TypeAlias<'input, 'hacx>,
+    TypeExpr<'input, 'hacx>,
+    Function<'input, 'hacx>,
+    Struct<'input, 'hacx>,
+    Enum<'input, 'hacx>,
+    Mod<'input>,
+    Use<'input>,
+>;
+
+/// the syntax tree of one file
+pub struct Module<'input, 'hacx> {
+    /// the index of the file in the files that were parsed
+    pub id: FileId,
+    pub path: &'input Path,
+    pub text: &'input str,
+    pub items: Vec<HAst<'input, 'hacx>>,
+}
+
+pub struct Parsed<'input, 'hacx> {
+    /// the files that parsed, in the order that they were given
+    pub modules: Vec<Module<'input, 'hacx>>,
+    /// the errors of the files that didn't parse, in the order that they were given
+    pub diagnostics: Vec<LoadError>,
+}
+
+/// parses every file on a pool of `threads` threads, or one thread per core if
+/// `threads` is 0, allocating all of the syntax trees in `ctx`
+///
+/// unlike `Loader`, this doesn't follow `mod name;` items
+pub fn parse_files<'input, 'hacx>(
+    files: &'input [(PathBuf, String)],
+    ctx: &'hacx SyncContext<'input, 'hacx>,
+    threads: usize,
+) -> Parsed<'input, 'hacx> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .expect("couldn't start the threads to parse on");
+
+    let results = pool.install(|| {
+        files
+            .par_iter()
+            .enumerate()
+            .map(|(index, (path, text))| parse_file(FileId(index as u32), path, text, ctx))
+            .collect::<Vec<_>>()
+    });
+
+    let mut parsed = Parsed {
+        modules: Vec::new(),
+        diagnostics: Vec::new(),
+    };
+
+    for result in results {
+        match result {
+            Ok(module) => parsed.modules.push(module),
+            Err(err) => parsed.diagnostics.push(err),
+        }
+    }
+
+    parsed
+}
+
+fn parse_file<'input, 'hacx>(
+    id: FileId,
+    path: &'input Path,
+    text: &'input str,
+    ctx: &'hacx SyncContext<'input, 'hacx>,
+) -> Result<Module<'input, 'hacx>, LoadError> {
+    let mut parser = lib_parser::Parser::new(lib_lexer::Lexer::new(text), ctx.as_ref());
+    let mut items = Vec::new();
+
+    while let Some(item) = parser.parse().with_context(Error::ParseError(id))? {
+        items.push(item);
+    }
+
+    Ok(Module {
+        id,
+        path,
+        text,
+        items,
+    })
+}
+
+#[test]
+fn parse_files_under_contention() {
+    use lib_lexer_types::TokenData;
+
+    let source = |file: usize| {
+        (0..50)
+            .map(|i| {
+                format!(
+                    "fn stress_{0}_{1}(x: i32) -> i32 {{ let shared_{2} = x * {1}; shared_{2} + stress_{0}_{1}(x) }}\n",
+                    file,
+                    i,
+                    i % 10
+                )
+            })
+            .collect::<String>()
+    };
+
+    let files = (0..64)
+        .map(|file| (PathBuf::from(format!("file{}.lang", file)), source(file)))
+        .chain(Some((
+            PathBuf::from("broken.lang"),
+            "fn broken( {".to_owned(),
+        )))
+        .collect::<Vec<_>>();
+
+    let ctx = SyncContext::default();
+    let parsed = parse_files(&files, &ctx, 8);
+
+    assert_eq!(parsed.modules.len(), 64);
+    assert_eq!(parsed.diagnostics.len(), 1);
+    assert_eq!(*parsed.diagnostics[0].err(), Error::ParseError(FileId(64)));
+    assert_eq!(ctx.node_function.value_count(), 64 * 50);
+
+    for (file, module) in parsed.modules.iter().enumerate() {
+        assert_eq!(module.id, FileId(file as u32));
+
+        // the trees are the same as when parsing on one thread
+        let local = lib_parser::context::LocalContext::default();
+        let mut parser =
+            lib_parser::Parser::new(lib_lexer::Lexer::new(module.text), local.as_ref());
+        let mut items = Vec::new();
+
+        while let Some(item) = parser.parse().unwrap() {
+            items.push(item);
+        }
+
+        assert_eq!(format!("{:?}", module.items), format!("{:?}", items));
+
+        // and every thread got the same interned identifiers
+        for (i, item) in module.items.iter().enumerate() {
+            let name = match item {
+                HAst::Function(function) => match function.ident.data {
+                    TokenData::Identifier(name) => name,
+                    ref data => panic!("expected an identifier, found {:?}", data),
+                },
+                item => panic!("expected a function, found {:?}", item),
+            };
+
+            assert_eq!(
+                name,
+                lib_str_interner::intern(&format!("stress_{}_{}", file, i))
+            );
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use lib_lexer_types::{
-    CodePoint, Error, ErrorType, Keyword, Real, Result, Symbol, Token, TokenData,
+    CodePoint, Error, ErrorType, Keyword, Lexeme, LexemeKind, Real, Result, Symbol, Token,
+    TokenData, TriviaKind,
 };
 
 macro_rules! get_token_ty_from_ident {
@@ -20,9 +21,45 @@ macro_rules! get_token_ty_from_ident {
     }
 }
 
+// longer symbols must come before any symbol that is a prefix of them
+const SYMBOLS: &[(&str, Symbol)] = &[
+    ("=>", Symbol::FatArrow),
+    ("->", Symbol::Arrow),
+    ("::", Symbol::PathSep),
+    ("==", Symbol::Equal),
+    ("!=", Symbol::NotEqual),
+    ("<=", Symbol::LessEqual),
+    (">=", Symbol::GreaterEqual),
+    ("&&", Symbol::LogicalAnd),
+    ("||", Symbol::LogicalOr),
+    ("+", Symbol::Add),
+    ("-", Symbol::Sub),
+    ("*", Symbol::Mul),
+    ("/", Symbol::Div),
+    ("%", Symbol::Rem),
+    ("<", Symbol::LessThan),
+    (">", Symbol::GreaterThan),
+    ("!", Symbol::Not),
+    (".", Symbol::Dot),
+    ("=", Symbol::Assign),
+    (";", Symbol::Semicolon),
+    (",", Symbol::Comma),
+    (":", Symbol::Colon),
+    ("|", Symbol::Or),
+    ("#", Symbol::Pound),
+    ("{", Symbol::OpenBrace),
+    ("}", Symbol::CloseBrace),
+    ("(", Symbol::OpenParen),
+    (")", Symbol::CloseParen),
+    ("[", Symbol::OpenBracket),
+    ("]", Symbol::CloseBracket),
+];
+
 pub struct Lexer<'input> {
     input: &'input str,
     start: CodePoint,
+    // numbers directly after a `.` are tuple indices, so `x.0.1` must not lex `0.1` as a float
+    after_dot: bool,
 }
 
 fn split_on_false<F: FnMut(char) -> bool>(s: &str, mut f: F) -> (&str, &str) {
@@ -46,25 +83,59 @@ impl<'input> Lexer<'input> {
         Self {
             input,
             start: CodePoint::new_unchecked(1, 1),
+            after_dot: false,
+        }
+    }
+
+    /// lexes `input` as a piece of a larger source that starts at `start`,
+    /// so that spans are positions in that source
+    pub fn with_start(input: &'input str, start: CodePoint) -> Self {
+        Self {
+            start,
+            ..Self::new(input)
         }
     }
 
     pub fn parse(&mut self) -> Result<Option<Token<'input>>> {
-        let first = match self.input.chars().next() {
-            Some(first) => first,
-            None => return Ok(None),
-        };
+        while self.parse_trivia().is_some() {}
+
+        self.parse_token()
+    }
+
+    /// lexes the next token or piece of trivia, along with its source text
+    ///
+    /// the text of all of the lexemes put together is the whole input
+    pub fn parse_lossless(&mut self) -> Result<Option<Lexeme<'input>>> {
+        if let Some(trivia) = self.parse_trivia() {
+            return Ok(Some(trivia));
+        }
+
+        let input = self.input;
+        let token = self.parse_token()?;
+
+        Ok(token.map(|token| Lexeme {
+            kind: LexemeKind::Token(token.data),
+            text: &input[..input.len() - self.input.len()],
+            span: token.span,
+        }))
+    }
+
+    /// lexes a run of whitespace or a line comment
+    fn parse_trivia(&mut self) -> Option<Lexeme<'input>> {
+        let first = self.input.chars().next()?;
+        let start = self.start;
 
         if first.is_whitespace() {
             let mut rows = 0;
             let mut cols = 0;
+            // columns are byte offsets in the line, like everywhere else
             let (lexeme, rest) = split_on_false(self.input, |c| {
                 let is_whitespace = c.is_whitespace();
                 if c == '\n' {
                     rows += 1;
                     cols = 1;
-                } else {
-                    cols += u32::from(is_whitespace);
+                } else if is_whitespace {
+                    cols += c.len_utf8() as u32;
                 }
 
                 is_whitespace
@@ -80,8 +151,31 @@ impl<'input> Lexer<'input> {
                     cols
                 },
             );
-        };
 
+            Some(Lexeme {
+                kind: LexemeKind::Trivia(TriviaKind::Whitespace),
+                text: lexeme,
+                span: start.span(self.start),
+            })
+        } else if self.input.starts_with("//") {
+            // the newline that ends the comment is whitespace
+            let len = self.input.find('\n').unwrap_or(self.input.len());
+            let (lexeme, rest) = self.input.split_at(len);
+
+            self.input = rest;
+            self.start = CodePoint::new_unchecked(start.row(), start.col() + len as u32);
+
+            Some(Lexeme {
+                kind: LexemeKind::Trivia(TriviaKind::Comment),
+                text: lexeme,
+                span: start.span(self.start),
+            })
+        } else {
+            None
+        }
+    }
+
+    fn parse_token(&mut self) -> Result<Option<Token<'input>>> {
         let first = match self.input.chars().next() {
             Some(first) => first,
             None => return Ok(None),
@@ -104,12 +198,34 @@ impl<'input> Lexer<'input> {
                 Continue => "continue",
                 Return => "return",
                 Type => "type",
+                Fn => "fn",
+                If => "if",
+                Else => "else",
+                True => "true",
+                False => "false",
+                Struct => "struct",
+                Enum => "enum",
+                Mod => "mod",
+                Use => "use",
             );
 
-            (get_tok_ty(ident), (end, rest))
+            let data = if ident == "_" {
+                TokenData::Symbol(Symbol::Underscore)
+            } else {
+                get_tok_ty(ident)
+            };
+
+            (data, (end, rest))
         } else if first.is_numeric() {
             let (first, rest) = split_on_false(self.input, |c| c.is_alphanumeric() || c == '_');
-            if let Some('.') = rest.chars().next() {
+
+            // only a digit after the `.` makes a float, so that `1.max(2)` is a method call
+            let mut fraction = rest.chars();
+            let is_float = !self.after_dot
+                && fraction.next() == Some('.')
+                && matches!(fraction.next(), Some(c) if c.is_ascii_digit());
+
+            if is_float {
                 let (second, rest) =
                     split_on_false(&rest[1..], |c| c.is_alphanumeric() || c == '_');
 
@@ -140,33 +256,31 @@ impl<'input> Lexer<'input> {
                 (TokenData::Integer(int), (end, rest))
             }
         } else {
-            let data = match first {
-                '+' => TokenData::Symbol(Symbol::Add),
-                '-' => TokenData::Symbol(Symbol::Sub),
-                '*' => TokenData::Symbol(Symbol::Mul),
-                '/' => TokenData::Symbol(Symbol::Div),
-                '.' => TokenData::Symbol(Symbol::Dot),
-                '=' => TokenData::Symbol(Symbol::Assign),
-                ';' => TokenData::Symbol(Symbol::Semicolon),
-                c => {
-                    let end = make_end(c.len_utf8());
+            let (symbol, lexeme) = match SYMBOLS
+                .iter()
+                .find(|(lexeme, _)| self.input.starts_with(lexeme))
+            {
+                Some(&(lexeme, symbol)) => (symbol, lexeme),
+                None => {
+                    let end = make_end(first.len_utf8());
 
                     #[allow(clippy::try_err)]
                     Err(Error {
-                        err: ErrorType::UnknownCharacter(c),
+                        err: ErrorType::UnknownCharacter(first),
                         span: self.start.span(end),
                     })?
                 }
             };
 
-            let (_, rest) = self.input.split_at(first.len_utf8());
-            let end = make_end(first.len_utf8());
+            let (_, rest) = self.input.split_at(lexeme.len());
+            let end = make_end(lexeme.len());
 
-            (data, (end, rest))
+            (TokenData::Symbol(symbol), (end, rest))
         };
 
         self.input = rest;
-        let start = end;
+        self.after_dot = data == TokenData::Symbol(Symbol::Dot);
+        let start = std::mem::replace(&mut self.start, end);
 
         Ok(Some(Token {
             data,
@@ -174,3 +288,158 @@ impl<'input> Lexer<'input> {
         }))
     }
 }
+
+#[cfg(test)]
+fn lex_all(input: &str) -> Vec<Token<'_>> {
+    let mut lexer = Lexer::new(input);
+    let mut tokens = Vec::new();
+
+    while let Some(token) = lexer.parse().unwrap() {
+        tokens.push(token);
+    }
+
+    tokens
+}
+
+#[test]
+fn symbols() {
+    let tokens = lex_all("=> -> - = == != ! < <= && || , : :: | # { } ( ) [ ] _");
+
+    let data = tokens.iter().map(|token| token.data).collect::<Vec<_>>();
+
+    assert_eq!(
+        data,
+        [
+            TokenData::Symbol(Symbol::FatArrow),
+            TokenData::Symbol(Symbol::Arrow),
+            TokenData::Symbol(Symbol::Sub),
+            TokenData::Symbol(Symbol::Assign),
+            TokenData::Symbol(Symbol::Equal),
+            TokenData::Symbol(Symbol::NotEqual),
+            TokenData::Symbol(Symbol::Not),
+            TokenData::Symbol(Symbol::LessThan),
+            TokenData::Symbol(Symbol::LessEqual),
+            TokenData::Symbol(Symbol::LogicalAnd),
+            TokenData::Symbol(Symbol::LogicalOr),
+            TokenData::Symbol(Symbol::Comma),
+            TokenData::Symbol(Symbol::Colon),
+            TokenData::Symbol(Symbol::PathSep),
+            TokenData::Symbol(Symbol::Or),
+            TokenData::Symbol(Symbol::Pound),
+            TokenData::Symbol(Symbol::OpenBrace),
+            TokenData::Symbol(Symbol::CloseBrace),
+            TokenData::Symbol(Symbol::OpenParen),
+            TokenData::Symbol(Symbol::CloseParen),
+            TokenData::Symbol(Symbol::OpenBracket),
+            TokenData::Symbol(Symbol::CloseBracket),
+            TokenData::Symbol(Symbol::Underscore),
+        ]
+    );
+}
+
+#[test]
+fn spans() {
+    let tokens = lex_all("let x =\n  10;");
+
+    let spans = tokens
+        .iter()
+        .map(|token| {
+            let (start, end) = (token.span.start(), token.span.end());
+            ((start.row(), start.col()), (end.row(), end.col()))
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        spans,
+        [
+            ((1, 1), (1, 4)),
+            ((1, 5), (1, 6)),
+            ((1, 7), (1, 8)),
+            ((2, 3), (2, 5)),
+            ((2, 5), (2, 6)),
+        ]
+    );
+
+    // columns count bytes, so non-ASCII indentation moves the token further
+    let y = lex_all("x\n\u{a0}\u{3000}y")[1].span.start();
+    assert_eq!((y.row(), y.col()), (2, 6));
+}
+
+#[test]
+fn tuple_index() {
+    let tokens = lex_all("x.0.1 1.5 1.max");
+
+    let data = tokens.iter().map(|token| token.data).collect::<Vec<_>>();
+
+    assert_eq!(
+        data,
+        [
+            TokenData::Identifier(lib_str_interner::intern("x")),
+            TokenData::Symbol(Symbol::Dot),
+            TokenData::Integer(0),
+            TokenData::Symbol(Symbol::Dot),
+            TokenData::Integer(1),
+            TokenData::Float(Real::new(1.5).unwrap()),
+            TokenData::Integer(1),
+            TokenData::Symbol(Symbol::Dot),
+            TokenData::Identifier(lib_str_interner::intern("max")),
+        ]
+    );
+}
+
+#[test]
+fn comments() {
+    let tokens = lex_all("x // a / comment\n/ y // end");
+
+    let data = tokens.iter().map(|token| token.data).collect::<Vec<_>>();
+
+    assert_eq!(
+        data,
+        [
+            TokenData::Identifier(lib_str_interner::intern("x")),
+            TokenData::Symbol(Symbol::Div),
+            TokenData::Identifier(lib_str_interner::intern("y")),
+        ]
+    );
+}
+
+#[test]
+fn lossless() {
+    let input = "let x = // one\n  1;\n";
+    let mut lexer = Lexer::new(input);
+    let mut lexemes = Vec::new();
+
+    while let Some(lexeme) = lexer.parse_lossless().unwrap() {
+        lexemes.push(lexeme);
+    }
+
+    let text = lexemes.iter().map(|lexeme| lexeme.text).collect::<String>();
+    assert_eq!(text, input);
+
+    let trivia = lexemes
+        .iter()
+        .filter_map(|lexeme| match lexeme.kind {
+            LexemeKind::Trivia(kind) => Some((kind, lexeme.text)),
+            LexemeKind::Token(_) => None,
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        trivia,
+        [
+            (TriviaKind::Whitespace, " "),
+            (TriviaKind::Whitespace, " "),
+            (TriviaKind::Whitespace, " "),
+            (TriviaKind::Comment, "// one"),
+            (TriviaKind::Whitespace, "\n  "),
+            (TriviaKind::Whitespace, "\n"),
+        ]
+    );
+
+    let comment = lexemes[6];
+    assert_eq!(comment.kind, LexemeKind::Trivia(TriviaKind::Comment));
+    assert_eq!(
+        (comment.span.start().col(), comment.span.end().col()),
+        (9, 15)
+    );
+}
@@ -0,0 +1,368 @@
+//! `unused_variables`, `unused_mut` and `shadowed_bindings`, which follow every
+//! binding from where it's declared to the end of its scope
+//!
+//! there is no name resolution yet, so an identifier refers to the innermost
+//! binding of the same name that is in scope, and only assignments mutate a
+//! binding.
+
+use lib_diagnostics::{Applicability, Diagnostic, DiagnosticSink, Label, SourceId, Suggestion};
+use lib_lexer_types::{Span, Token, TokenData};
+use lib_parser_types::{
+    visit::{self, Visitor},
+    Assign, Attribute, Binding as BindingPattern, Block, Closure, FieldInit, FieldPattern,
+    Function, HAst, Let, MatchArm, Pattern, Spanned,
+};
+use lib_str_interner::ThinStr;
+
+use super::{find, Level, Lint, LintLevels, SHADOWED_BINDINGS, UNUSED_MUT, UNUSED_VARIABLES};
+
+/// the level of a lint at some place in the file
+#[derive(Debug, Clone, Copy)]
+struct LintLevel {
+    lint: &'static Lint,
+    level: Level,
+    /// the attribute that set the level, if any
+    attr: Option<Span>,
+    /// whether this is the default level of the lint
+    default: bool,
+}
+
+struct Binding {
+    name: ThinStr,
+    ident: Span,
+    kw_mut: Option<Span>,
+    /// bound by `Name { field }`
+    shorthand: bool,
+    read: bool,
+    assigned: bool,
+    unused: LintLevel,
+    unused_mut: LintLevel,
+}
+
+struct Bindings<'a> {
+    file: SourceId,
+    levels: &'a LintLevels,
+    sink: &'a mut DiagnosticSink,
+    /// the levels set by the attributes around the current node, from the
+    /// outermost to the innermost
+    attrs: Vec<(&'static Lint, Level, Span)>,
+    scopes: Vec<Vec<Binding>>,
+    /// where the bindings of the pattern being declared start in the innermost
+    /// scope
+    pattern_start: usize,
+}
+
+fn name(token: Token<'_>) -> ThinStr {
+    match token.data {
+        TokenData::Identifier(name) => name,
+        data => unreachable!("bindings are identifiers, found {:?}", data),
+    }
+}
+
+pub(super) fn lint(
+    items: &[HAst<'_, '_>],
+    file: SourceId,
+    levels: &LintLevels,
+    sink: &mut DiagnosticSink,
+) {
+    let mut bindings = Bindings {
+        file,
+        levels,
+        sink,
+        attrs: Vec::new(),
+        scopes: Vec::new(),
+        pattern_start: 0,
+    };
+
+    bindings.scoped(|bindings| {
+        for item in items {
+            bindings.visit_hast(item);
+        }
+    });
+}
+
+impl Bindings<'_> {
+    fn level(&self, lint: &'static Lint) -> LintLevel {
+        let (level, default) = self.levels.level(self.file, lint);
+        let mut current = LintLevel {
+            lint,
+            level,
+            attr: None,
+            default,
+        };
+
+        for &(_, level, attr) in self.attrs.iter().filter(|(l, ..)| *l == lint) {
+            if current.level != Level::Forbid {
+                current = LintLevel {
+                    lint,
+                    level,
+                    attr: Some(attr),
+                    default: false,
+                };
+            }
+        }
+
+        current
+    }
+
+    fn emit(&mut self, level: LintLevel, mut diagnostic: Diagnostic) {
+        let severity = match level.level.severity() {
+            Some(severity) => severity,
+            None => return,
+        };
+
+        diagnostic.severity = severity;
+
+        if let Some(attr) = level.attr {
+            diagnostic = diagnostic.with_label(Label::secondary(
+                self.file,
+                attr,
+                "the lint level is defined here",
+            ));
+        }
+
+        if level.default {
+            diagnostic.notes.insert(
+                0,
+                format!(
+                    "`#[{}({})]` on by default",
+                    level.level.name(),
+                    level.lint.name
+                ),
+            );
+        }
+
+        self.sink.emit(diagnostic);
+    }
+
+    /// runs `f` with the lint levels that `attrs` set
+    fn with_attrs(&mut self, attrs: &[Attribute<'_>], f: impl FnOnce(&mut Self)) {
+        let len = self.attrs.len();
+
+        for attr in attrs {
+            let level = attr
+                .name
+                .as_ident()
+                .and_then(|ident| Level::from_name(name(ident).to_str()));
+
+            let (level, args) = match (level, &attr.args) {
+                (Some(level), Some(args)) => (level, args),
+                _ => continue,
+            };
+
+            for arg in &args.args {
+                if let TokenData::Identifier(lint) = arg.data {
+                    if let Some(lint) = find(lint.to_str()) {
+                        self.attrs.push((lint, level, attr.span()));
+                    }
+                }
+            }
+        }
+
+        f(self);
+        self.attrs.truncate(len);
+    }
+
+    /// runs `f` in a new scope, and reports the bindings that it leaves unused
+    fn scoped(&mut self, f: impl FnOnce(&mut Self)) {
+        self.scopes.push(Vec::new());
+        f(self);
+
+        for binding in self.scopes.pop().unwrap() {
+            self.report(binding);
+        }
+    }
+
+    /// declares every binding in `pattern` in the innermost scope
+    fn declare(&mut self, pattern: &Pattern<'_>) {
+        self.pattern_start = self.scopes.last().unwrap().len();
+        self.visit_pattern(pattern);
+    }
+
+    fn bind(&mut self, kw_mut: Option<Token<'_>>, ident: Token<'_>, shorthand: bool) {
+        let name = name(ident);
+
+        // capitalized names are almost always unit variants or constants
+        if name.to_str().starts_with(char::is_uppercase) {
+            return;
+        }
+
+        let scope = self.scopes.last().unwrap();
+
+        // the alternatives of an or-pattern bind the same names
+        if scope[self.pattern_start..]
+            .iter()
+            .any(|binding| binding.name == name)
+        {
+            return;
+        }
+
+        if let Some(earlier) = self.resolve(name).map(|binding| binding.ident) {
+            let level = self.level(&SHADOWED_BINDINGS);
+            let diagnostic = Diagnostic::warning(format!("`{}` shadows an earlier binding", name))
+                .with_label(Label::primary(self.file, ident.span, ""))
+                .with_label(Label::secondary(
+                    self.file,
+                    earlier,
+                    format!("`{}` was bound here first", name),
+                ));
+
+            self.emit(level, diagnostic);
+        }
+
+        let binding = Binding {
+            name,
+            ident: ident.span,
+            kw_mut: kw_mut.map(|kw_mut| kw_mut.span),
+            shorthand,
+            read: false,
+            assigned: false,
+            unused: self.level(&UNUSED_VARIABLES),
+            unused_mut: self.level(&UNUSED_MUT),
+        };
+
+        self.scopes.last_mut().unwrap().push(binding);
+    }
+
+    /// the innermost binding called `name`
+    fn resolve(&mut self, name: ThinStr) -> Option<&mut Binding> {
+        self.scopes
+            .iter_mut()
+            .rev()
+            .flat_map(|scope| scope.iter_mut().rev())
+            .find(|binding| binding.name == name)
+    }
+
+    fn read(&mut self, ident: Token<'_>) {
+        if let Some(binding) = self.resolve(name(ident)) {
+            binding.read = true;
+        }
+    }
+
+    fn report(&mut self, binding: Binding) {
+        let name = binding.name;
+
+        if !binding.read && !name.to_str().starts_with('_') {
+            let message = if binding.assigned {
+                format!("variable `{}` is assigned to, but never used", name)
+            } else {
+                format!("unused variable: `{}`", name)
+            };
+
+            // `Name { field }` has to keep the name of the field
+            let suggestion = if binding.shorthand {
+                Suggestion::replace(
+                    "try ignoring the field",
+                    self.file,
+                    binding.ident,
+                    format!("{}: _", name),
+                    Applicability::MachineApplicable,
+                )
+            } else {
+                Suggestion::replace(
+                    "if this is intentional, prefix it with an underscore",
+                    self.file,
+                    binding.ident,
+                    format!("_{}", name),
+                    Applicability::MaybeIncorrect,
+                )
+            };
+
+            let diagnostic = Diagnostic::warning(message)
+                .with_label(Label::primary(self.file, binding.ident, ""))
+                .with_suggestion(suggestion);
+
+            self.emit(binding.unused, diagnostic);
+        }
+
+        if let (Some(kw_mut), false) = (binding.kw_mut, binding.assigned) {
+            let diagnostic = Diagnostic::warning("variable does not need to be mutable")
+                .with_label(Label::primary(self.file, kw_mut.merge(binding.ident), ""))
+                .with_suggestion(Suggestion::remove(
+                    "remove this `mut`",
+                    self.file,
+                    kw_mut.start(),
+                    binding.ident.start(),
+                    Applicability::MachineApplicable,
+                ));
+
+            self.emit(binding.unused_mut, diagnostic);
+        }
+    }
+}
+
+impl<'input, 'hacx> Visitor<'input, 'hacx> for Bindings<'_> {
+    fn visit_let(&mut self, ast_let: &Let<'input, 'hacx>) {
+        // the value can't see the bindings of its own pattern
+        self.visit_expr(&ast_let.value);
+
+        self.with_attrs(&ast_let.attrs, |this| this.declare(&ast_let.pattern));
+    }
+
+    fn visit_assign(&mut self, assign: &Assign<'input, 'hacx>) {
+        self.visit_expr(&assign.value);
+
+        if let Some(binding) = self.resolve(name(assign.ident)) {
+            binding.assigned = true;
+        }
+    }
+
+    fn visit_function(&mut self, function: &Function<'input, 'hacx>) {
+        self.with_attrs(&function.attrs, |this| {
+            this.scoped(|this| {
+                // each parameter is a pattern of its own
+                for param in &function.params {
+                    this.pattern_start = this.scopes.last().unwrap().len();
+                    this.bind(None, param.ident, false);
+                }
+
+                this.visit_block(&function.body);
+            })
+        });
+    }
+
+    fn visit_block(&mut self, block: &Block<'input, 'hacx>) {
+        self.scoped(|this| visit::walk_block(this, block));
+    }
+
+    fn visit_match_arm(&mut self, arm: &MatchArm<'input, 'hacx>) {
+        self.scoped(|this| {
+            this.declare(&arm.pattern);
+            this.visit_expr(&arm.value);
+        });
+    }
+
+    fn visit_closure(&mut self, closure: &Closure<'input, 'hacx>) {
+        self.scoped(|this| {
+            for param in &closure.params {
+                this.declare(&param.pattern);
+            }
+
+            this.visit_expr(closure.body);
+        });
+    }
+
+    fn visit_identifier(&mut self, ident: &Token<'input>) {
+        self.read(*ident);
+    }
+
+    fn visit_field_init(&mut self, field: &FieldInit<'input, 'hacx>) {
+        match &field.value {
+            Some(_) => visit::walk_field_init(self, field),
+            // `Name { field }` reads the variable `field`
+            None => self.read(field.ident),
+        }
+    }
+
+    fn visit_binding(&mut self, binding: &BindingPattern<'input>) {
+        self.bind(binding.kw_mut, binding.ident, false);
+    }
+
+    fn visit_field_pattern(&mut self, field: &FieldPattern<'input>) {
+        match &field.pattern {
+            Some(_) => visit::walk_field_pattern(self, field),
+            // `Name { field }` binds the variable `field`
+            None => self.bind(None, field.ident, true),
+        }
+    }
+}
@@ -0,0 +1,372 @@
+//! lints, which report code that parses but is probably a mistake
+//!
+//! every lint has a default level, which `LintLevels` overrides for every file
+//! or for a single file, and `#[allow(..)]`, `#[warn(..)]`, `#[deny(..)]` or
+//! `#[forbid(..)]` on a `let` or an item overrides for everything in it. Once
+//! a lint is forbidden, nothing inside can lower its level again.
+
+use std::collections::HashMap;
+
+use lib_diagnostics::{DiagnosticSink, Severity, SourceId};
+use lib_parser_types::HAst;
+
+mod bindings;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Level {
+    Allow,
+    Warn,
+    Deny,
+    /// like `Deny`, but can't be overridden
+    Forbid,
+}
+
+impl Level {
+    /// the level of the attribute `name`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "allow" => Some(Level::Allow),
+            "warn" => Some(Level::Warn),
+            "deny" => Some(Level::Deny),
+            "forbid" => Some(Level::Forbid),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Level::Allow => "allow",
+            Level::Warn => "warn",
+            Level::Deny => "deny",
+            Level::Forbid => "forbid",
+        }
+    }
+
+    /// the severity of the lint's diagnostics, or `None` if they aren't reported
+    pub fn severity(self) -> Option<Severity> {
+        match self {
+            Level::Allow => None,
+            Level::Warn => Some(Severity::Warning),
+            Level::Deny | Level::Forbid => Some(Severity::Error),
+        }
+    }
+
+    /// this level overridden by `level`, unless this level is `Forbid`
+    fn with(self, level: Level) -> Self {
+        match self {
+            Level::Forbid => Level::Forbid,
+            _ => level,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct Lint {
+    /// the name used in attributes, like `#[allow(unused_variables)]`
+    pub name: &'static str,
+    pub default: Level,
+    pub description: &'static str,
+}
+
+pub static UNUSED_VARIABLES: Lint = Lint {
+    name: "unused_variables",
+    default: Level::Warn,
+    description: "a variable that is never read, assigning to it doesn't count",
+};
+
+pub static UNUSED_MUT: Lint = Lint {
+    name: "unused_mut",
+    default: Level::Warn,
+    description: "a `mut` binding that is never assigned to",
+};
+
+pub static SHADOWED_BINDINGS: Lint = Lint {
+    name: "shadowed_bindings",
+    default: Level::Allow,
+    description: "a binding with the same name as a variable that is still in scope",
+};
+
+/// every lint
+pub static LINTS: &[&Lint] = &[&UNUSED_VARIABLES, &UNUSED_MUT, &SHADOWED_BINDINGS];
+
+/// the lint called `name`
+pub fn find(name: &str) -> Option<&'static Lint> {
+    LINTS.iter().copied().find(|lint| lint.name == name)
+}
+
+/// the levels of the lints, for every file and for single files
+#[derive(Debug, Default)]
+pub struct LintLevels {
+    all: HashMap<&'static str, Level>,
+    files: HashMap<SourceId, HashMap<&'static str, Level>>,
+}
+
+impl LintLevels {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&mut self, lint: &'static Lint, level: Level) {
+        self.all.insert(lint.name, level);
+    }
+
+    pub fn set_for_file(&mut self, file: SourceId, lint: &'static Lint, level: Level) {
+        self.files.entry(file).or_default().insert(lint.name, level);
+    }
+
+    /// the level of `lint` in `file`, and whether that is its default level
+    pub fn level(&self, file: SourceId, lint: &Lint) -> (Level, bool) {
+        let overrides = self
+            .all
+            .get(lint.name)
+            .into_iter()
+            .chain(self.files.get(&file).and_then(|file| file.get(lint.name)))
+            .collect::<Vec<_>>();
+
+        let level = overrides
+            .iter()
+            .fold(lint.default, |level, &&new| level.with(new));
+
+        (level, overrides.is_empty())
+    }
+}
+
+/// runs every lint over the items of `file`
+pub fn lint(
+    items: &[HAst<'_, '_>],
+    file: SourceId,
+    levels: &LintLevels,
+    sink: &mut DiagnosticSink,
+) {
+    bindings::lint(items, file, levels, sink)
+}
+
+/// adds `text` to `sources` as `main.lang` and lints it
+#[cfg(test)]
+fn lint_source(
+    sources: &mut lib_diagnostics::SourceMap,
+    text: &str,
+    levels: &LintLevels,
+) -> DiagnosticSink {
+    let ctx = lib_parser_types::context::LocalContext::default();
+    let mut parser = lib_parser::Parser::new(lib_lexer::Lexer::new(text), ctx.as_ref());
+    let mut items = Vec::new();
+
+    while let Some(item) = parser.parse().unwrap() {
+        items.push(item);
+    }
+
+    let file = sources.add("main.lang", text);
+    let mut sink = DiagnosticSink::new();
+    lint(&items, file, levels, &mut sink);
+    sink
+}
+
+#[cfg(test)]
+fn lint_text(text: &str, levels: &LintLevels) -> DiagnosticSink {
+    lint_source(&mut lib_diagnostics::SourceMap::new(), text, levels)
+}
+
+#[cfg(test)]
+fn messages(sink: &DiagnosticSink) -> Vec<String> {
+    sink.diagnostics()
+        .iter()
+        .map(|diagnostic| format!("{}: {}", diagnostic.severity, diagnostic.message))
+        .collect()
+}
+
+#[test]
+fn default_lints() {
+    let sink = lint_text(
+        "
+        fn f(a: i32, _b: i32) -> i32 {
+            let mut unused = 0;
+            let mut changed = 1;
+            changed = changed + a;
+            let mut assigned = 0;
+            assigned = 1;
+            let kept = 2;
+            let kept = kept + 1;
+            let p = Point { x: 0, y: kept };
+            match p {
+                Point { x, y: 0 } => x,
+                Point { x, y } => y,
+            }
+        }
+        ",
+        &LintLevels::new(),
+    );
+
+    assert_eq!(
+        messages(&sink),
+        [
+            "warning: unused variable: `x`",
+            "warning: unused variable: `unused`",
+            "warning: variable does not need to be mutable",
+            "warning: variable `assigned` is assigned to, but never used",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>()
+    );
+    assert_eq!(sink.warnings(), 4);
+    assert_eq!(sink.exit_code(), 0);
+}
+
+#[test]
+fn shadowed_bindings() {
+    let text = "let x = 0; { let x = x + 1; x }; match x { y => y, }";
+
+    assert_eq!(lint_text(text, &LintLevels::new()).diagnostics().len(), 0);
+
+    let mut levels = LintLevels::new();
+    levels.set(&SHADOWED_BINDINGS, Level::Warn);
+    let sink = lint_text(text, &levels);
+
+    assert_eq!(messages(&sink), ["warning: `x` shadows an earlier binding"]);
+    assert_eq!(sink.diagnostics()[0].labels.len(), 2);
+
+    // the bindings of the alternatives of an or-pattern are the same binding
+    let text = "match 0 { A(v) | B(v) => v, }";
+    assert_eq!(lint_text(text, &levels).diagnostics().len(), 0);
+
+    // but every parameter is a pattern of its own
+    for text in ["let _f = |a, a| a;", "fn f(a: i32, a: i32) -> i32 { a }"].iter() {
+        assert_eq!(
+            messages(&lint_text(text, &levels)),
+            [
+                "warning: `a` shadows an earlier binding",
+                "warning: unused variable: `a`",
+            ]
+        );
+    }
+}
+
+#[test]
+fn level_overrides() {
+    let text = "
+        #[allow(unused_variables)]
+        fn f() {
+            let a = 0;
+            #[warn(unused_variables)]
+            let b = 0;
+        }
+        #[deny(unused_variables, unused_mut)]
+        let mut c = 0;
+        ";
+
+    let sink = lint_text(text, &LintLevels::new());
+    assert_eq!(
+        messages(&sink),
+        [
+            "warning: unused variable: `b`",
+            "error: unused variable: `c`",
+            "error: variable does not need to be mutable",
+        ]
+    );
+    assert_eq!(sink.exit_code(), 1);
+
+    // the label points at the attribute that set the level
+    let c = &sink.diagnostics()[1];
+    assert_eq!(c.labels[1].message, "the lint level is defined here");
+    assert!(c.notes.is_empty());
+
+    // a forbidden lint can't be allowed by an attribute
+    let mut levels = LintLevels::new();
+    levels.set(&UNUSED_VARIABLES, Level::Forbid);
+    assert_eq!(
+        messages(&lint_text(text, &levels))[..2],
+        ["error: unused variable: `a`", "error: unused variable: `b`"]
+    );
+
+    // or by the level of a file
+    let mut sources = lib_diagnostics::SourceMap::new();
+    let sink = lint_source(&mut sources, text, &levels);
+    // the file that was linted, as the diagnostics point into it
+    let file = sink.diagnostics()[0].labels[0].source;
+    assert_eq!(sources.get(file).name, "main.lang");
+
+    levels.set_for_file(file, &UNUSED_VARIABLES, Level::Allow);
+    assert_eq!(
+        levels.level(file, &UNUSED_VARIABLES),
+        (Level::Forbid, false)
+    );
+
+    let mut levels = LintLevels::new();
+    levels.set_for_file(file, &UNUSED_MUT, Level::Allow);
+    assert_eq!(levels.level(file, &UNUSED_MUT), (Level::Allow, false));
+    assert_eq!(levels.level(file, &UNUSED_VARIABLES), (Level::Warn, true));
+}
+
+#[test]
+fn rendered_lint() {
+    let mut sources = lib_diagnostics::SourceMap::new();
+    let sink = lint_source(&mut sources, "let mut count = 0;\n", &LintLevels::new());
+
+    let rendered = sink
+        .diagnostics()
+        .iter()
+        .map(|diagnostic| {
+            lib_diagnostics::render(diagnostic, &sources, lib_diagnostics::Style::Plain)
+        })
+        .collect::<String>();
+
+    assert_eq!(
+        rendered,
+        "\
+warning: unused variable: `count`
+ --> main.lang:1:9
+  |
+1 | let mut count = 0;
+  |         ^^^^^
+  |
+  = note: `#[warn(unused_variables)]` on by default
+help: if this is intentional, prefix it with an underscore
+  |
+1 | let mut _count = 0;
+  |         ~~~~~~
+warning: variable does not need to be mutable
+ --> main.lang:1:5
+  |
+1 | let mut count = 0;
+  |     ^^^^^^^^^
+  |
+  = note: `#[warn(unused_mut)]` on by default
+help: remove this `mut`
+  |
+1 | let mut count = 0;
+  |     ----
+"
+    );
+}
+
+#[test]
+fn fix_lints() {
+    let text = "
+        fn f(a: i32) -> i32 {
+            let mut b = a;
+            let mut c = 0;
+            c = b;
+            let p = Point { x: 0, y: c };
+            match p {
+                Point { x, y } => y,
+            }
+        }
+        ";
+    let mut sources = lib_diagnostics::SourceMap::new();
+    sources.add("other.lang", "");
+    let sink = lint_source(&mut sources, text, &LintLevels::new());
+    let fixed = lib_diagnostics::apply_all_fixes(&sources, sink.diagnostics());
+
+    assert_eq!(fixed.len(), 1);
+    assert_eq!(sources.get(fixed[0].0).name, "main.lang");
+    assert_eq!(
+        fixed[0].1.text,
+        text.replace("let mut b", "let b")
+            .replace("{ x, y }", "{ x: _, y }")
+    );
+
+    // nothing is left to fix
+    let sink = lint_text(&fixed[0].1.text, &LintLevels::new());
+    assert_eq!(messages(&sink), Vec::<String>::new());
+}
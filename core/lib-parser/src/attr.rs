@@ -0,0 +1,89 @@
+use super::Parser;
+
+use lib_lexer_types::{Keyword, Lexer, Symbol, TokenType};
+
+use lib_parser_types::{AttrArgs, Attribute, Result};
+
+const ATTR_ARG: &[TokenType] = &[
+    TokenType::Identifier,
+    TokenType::Integer,
+    TokenType::Float,
+    TokenType::StringLiteral,
+    TokenType::Keyword(Keyword::True),
+    TokenType::Keyword(Keyword::False),
+];
+
+impl<'input, 'hacx, L: Lexer<'input>> Parser<'input, 'hacx, L> {
+    /// parses any number of attributes, which may be followed by anything
+    pub(crate) fn parse_attributes(&mut self) -> Result<Vec<Attribute<'input>>> {
+        let mut attrs = Vec::new();
+
+        while let Some(sym_pound) = self.eat(TokenType::Symbol(Symbol::Pound))? {
+            let sym_open = self.expect(TokenType::Symbol(Symbol::OpenBracket))?;
+            let first = self.expect(TokenType::Identifier)?;
+            let name = self.parse_plain_path(first)?;
+
+            let args = match self.eat(TokenType::Symbol(Symbol::OpenParen))? {
+                Some(sym_open) => {
+                    let (args, sym_close) = self.parse_punctuated(Symbol::CloseParen, |this| {
+                        this.expect_any(ATTR_ARG.iter().copied())
+                    })?;
+
+                    Some(AttrArgs {
+                        sym_open,
+                        args,
+                        sym_close,
+                    })
+                }
+                None => None,
+            };
+
+            let sym_close = self.expect(TokenType::Symbol(Symbol::CloseBracket))?;
+
+            attrs.push(Attribute {
+                sym_pound,
+                sym_open,
+                name,
+                args,
+                sym_close,
+            });
+        }
+
+        Ok(attrs)
+    }
+}
+
+#[cfg(test)]
+use super::{context, parse_one};
+#[cfg(test)]
+use lib_parser_types::{ErrorType, HAst, Spanned};
+
+#[test]
+fn parse_attributes() {
+    let ctx = context::LocalContext::default();
+    let ctx = ctx.as_ref();
+
+    let function = match parse_one("#[test] #[allow(unused, 1)] fn f() { }", ctx) {
+        HAst::Function(function) => function,
+        ast => panic!("expected a function, found {:?}", ast),
+    };
+
+    match function.attrs[..] {
+        [ref test, ref allow] => {
+            assert!(test.args.is_none());
+            assert_eq!(allow.args.as_ref().map(|args| args.args.len()), Some(2));
+            assert_eq!(allow.span().start(), allow.sym_pound.span.start());
+            assert_eq!(allow.span().end(), allow.sym_close.span.end());
+        }
+        ref attrs => panic!("expected two attributes, found {:?}", attrs),
+    }
+
+    match parse_one("#[inline::always] let x = 0;", ctx) {
+        HAst::Let(ast_let) => assert_eq!(ast_let.attrs[0].name.segments.len(), 2),
+        ast => panic!("expected a let, found {:?}", ast),
+    }
+
+    let mut parser = super::Parser::new(lib_lexer::Lexer::new("#[test] x = 1;"), ctx);
+    let err = parser.parse().unwrap_err();
+    assert_eq!(err.err().err, ErrorType::InvalidAttributeTarget);
+}
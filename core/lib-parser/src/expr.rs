@@ -0,0 +1,886 @@
+use super::Parser;
+
+use lib_lexer_types::{Keyword, Lexer, Symbol, Token, TokenType};
+
+use lib_parser_types::{
+    ArrayExpr, Call, Closure, ClosureParam, Else, ErrorType, Expr, Field, FieldInit, GenericArgs,
+    If, Literal, Match, MatchArm, MethodCall, ParenExpr, Path, Precedence, Punctuated, Result,
+    StructExpr, TupleExpr, TupleIndex, Turbofish, TypeAnnotation,
+};
+
+const EXPR_START: &[TokenType] = &[
+    TokenType::Identifier,
+    TokenType::Integer,
+    TokenType::Float,
+    TokenType::StringLiteral,
+    TokenType::Keyword(Keyword::True),
+    TokenType::Keyword(Keyword::False),
+    TokenType::Keyword(Keyword::Match),
+    TokenType::Keyword(Keyword::If),
+    TokenType::Symbol(Symbol::OpenBrace),
+    TokenType::Symbol(Symbol::OpenParen),
+    TokenType::Symbol(Symbol::OpenBracket),
+    TokenType::Symbol(Symbol::Or),
+    TokenType::Symbol(Symbol::LogicalOr),
+    TokenType::Symbol(Symbol::Sub),
+    TokenType::Symbol(Symbol::Not),
+];
+
+impl<'input, 'hacx, L: Lexer<'input>> Parser<'input, 'hacx, L> {
+    pub(crate) fn parse_expr(&mut self) -> Result<Expr<'input, 'hacx>> {
+        self.parse_binary(Precedence::LogicalOr)
+    }
+
+    /// parses binary operators that bind at least as tightly as `min_prec`
+    fn parse_binary(&mut self, min_prec: Precedence) -> Result<Expr<'input, 'hacx>> {
+        let mut left = self.parse_prefix()?;
+
+        loop {
+            let symbol = match self.peek_type()? {
+                Some(TokenType::Symbol(symbol)) => symbol,
+                _ => break Ok(left),
+            };
+
+            let prec = match Precedence::of_binary(symbol) {
+                Some(prec) if prec >= min_prec => prec,
+                _ => break Ok(left),
+            };
+
+            let op = self.expect(TokenType::Symbol(symbol))?;
+
+            let right = self.parse_binary(prec.next())?;
+            let left_ptr = self.ctx.alloc(left);
+            let right = self.ctx.alloc(right);
+
+            left = match symbol {
+                Symbol::LogicalAnd | Symbol::LogicalOr => Expr::Logical(left_ptr, op, right),
+                _ => Expr::Binary(left_ptr, op, right),
+            };
+        }
+    }
+
+    fn parse_prefix(&mut self) -> Result<Expr<'input, 'hacx>> {
+        match self.peek_type()? {
+            Some(TokenType::Symbol(symbol)) if Precedence::is_prefix(symbol) => {
+                let op = self.expect(TokenType::Symbol(symbol))?;
+
+                let expr = self.parse_prefix()?;
+
+                Ok(Expr::Prefix(op, self.ctx.alloc(expr)))
+            }
+            _ => self.parse_postfix(),
+        }
+    }
+
+    fn parse_postfix(&mut self) -> Result<Expr<'input, 'hacx>> {
+        let mut expr = self.parse_primary()?;
+
+        loop {
+            expr = match self.peek_type()? {
+                Some(TokenType::Symbol(Symbol::OpenParen)) => {
+                    let sym_open = self.expect(TokenType::Symbol(Symbol::OpenParen))?;
+                    let (args, sym_close) = self.parse_args()?;
+
+                    Expr::Call(Call {
+                        callee: self.ctx.alloc(expr),
+                        sym_open,
+                        args,
+                        sym_close,
+                    })
+                }
+                Some(TokenType::Symbol(Symbol::Dot)) => {
+                    let sym_dot = self.expect(TokenType::Symbol(Symbol::Dot))?;
+                    self.parse_dot(expr, sym_dot)?
+                }
+                Some(TokenType::Symbol(Symbol::PathSep)) => {
+                    let args = self.parse_turbofish()?;
+
+                    Expr::Turbofish(Turbofish {
+                        base: self.ctx.alloc(expr),
+                        args,
+                    })
+                }
+                _ => break Ok(expr),
+            };
+        }
+    }
+
+    /// parses `::<T, ...>`, generic arguments can only be given to an
+    /// expression this way because `<` is the less than operator
+    fn parse_turbofish(&mut self) -> Result<GenericArgs<'input, 'hacx>> {
+        let sym_path_sep = self.expect(TokenType::Symbol(Symbol::PathSep))?;
+        let sym_open = self.expect(TokenType::Symbol(Symbol::LessThan))?;
+
+        self.parse_generic_args(Some(sym_path_sep), sym_open)
+    }
+
+    /// parses the arguments of a call after the opening parenthesis
+    fn parse_args(&mut self) -> Result<(Punctuated<'input, Expr<'input, 'hacx>>, Token<'input>)> {
+        self.with_struct_literals(true, |this| {
+            this.parse_punctuated(Symbol::CloseParen, Self::parse_expr)
+        })
+    }
+
+    /// parses whatever comes after the `.` in field accesses, tuple indexing and method calls
+    fn parse_dot(
+        &mut self,
+        base: Expr<'input, 'hacx>,
+        sym_dot: Token<'input>,
+    ) -> Result<Expr<'input, 'hacx>> {
+        let token = self.expect_any(any!(TokenType::Identifier, TokenType::Integer))?;
+        let base = self.ctx.alloc(base);
+
+        if token.data.tok_type() == TokenType::Integer {
+            return Ok(Expr::TupleIndex(TupleIndex {
+                base,
+                sym_dot,
+                index: token,
+            }));
+        }
+
+        let generics = match self.peek_type()? {
+            Some(TokenType::Symbol(Symbol::PathSep)) => Some(self.parse_turbofish()?),
+            _ => None,
+        };
+
+        match self.eat(TokenType::Symbol(Symbol::OpenParen))? {
+            Some(sym_open) => {
+                let (args, sym_close) = self.parse_args()?;
+
+                Ok(Expr::MethodCall(MethodCall {
+                    receiver: base,
+                    sym_dot,
+                    method: token,
+                    generics,
+                    sym_open,
+                    args,
+                    sym_close,
+                }))
+            }
+            None if generics.is_some() => {
+                Err(self.error_here(ErrorType::Expected(TokenType::Symbol(Symbol::OpenParen))))?
+            }
+            None => Ok(Expr::Field(Field {
+                base,
+                sym_dot,
+                ident: token,
+            })),
+        }
+    }
+
+    pub(crate) fn parse_primary(&mut self) -> Result<Expr<'input, 'hacx>> {
+        let first = match self.next_token()? {
+            Some(first) => first,
+            None => Err(self.error_at(ErrorType::ExpectedOneOf(EXPR_START.to_vec()), None))?,
+        };
+
+        match first.data.tok_type() {
+            TokenType::Identifier => {
+                let (path, sym_path_sep) = self.parse_path(first)?;
+
+                if let Some(sym_path_sep) = sym_path_sep {
+                    let sym_open = self.expect(TokenType::Symbol(Symbol::LessThan))?;
+                    let args = self.parse_generic_args(Some(sym_path_sep), sym_open)?;
+
+                    return Ok(Expr::Turbofish(Turbofish {
+                        base: self.ctx.alloc(Self::path_expr(path)),
+                        args,
+                    }));
+                }
+
+                if self.struct_literals {
+                    if let Some(sym_open) = self.eat(TokenType::Symbol(Symbol::OpenBrace))? {
+                        return self.parse_struct_expr(path, sym_open);
+                    }
+                }
+
+                Ok(Self::path_expr(path))
+            }
+            TokenType::Integer => Ok(Expr::Literal(Literal::Integer(first))),
+            TokenType::Float => Ok(Expr::Literal(Literal::Float(first))),
+            TokenType::StringLiteral => Ok(Expr::Literal(Literal::String(first))),
+            TokenType::Keyword(Keyword::True) | TokenType::Keyword(Keyword::False) => {
+                Ok(Expr::Literal(Literal::Bool(first)))
+            }
+            TokenType::Keyword(Keyword::Match) => self.parse_match(first),
+            TokenType::Keyword(Keyword::If) => self.parse_if(first),
+            TokenType::Symbol(Symbol::OpenBrace) => self
+                .with_struct_literals(true, |this| this.parse_block(first))
+                .map(Expr::Block),
+            TokenType::Symbol(Symbol::OpenParen) => {
+                let (mut items, sym_close) = self.with_struct_literals(true, |this| {
+                    this.parse_punctuated(Symbol::CloseParen, Self::parse_expr)
+                })?;
+
+                if items.len() == 1 && !items.has_trailing() {
+                    Ok(Expr::Paren(ParenExpr {
+                        sym_open: first,
+                        expr: self.ctx.alloc(items.items.pop().unwrap()),
+                        sym_close,
+                    }))
+                } else {
+                    Ok(Expr::Tuple(TupleExpr {
+                        sym_open: first,
+                        items,
+                        sym_close,
+                    }))
+                }
+            }
+            TokenType::Symbol(Symbol::OpenBracket) => {
+                let (items, sym_close) = self.with_struct_literals(true, |this| {
+                    this.parse_punctuated(Symbol::CloseBracket, Self::parse_expr)
+                })?;
+
+                Ok(Expr::Array(ArrayExpr {
+                    sym_open: first,
+                    items,
+                    sym_close,
+                }))
+            }
+            TokenType::Symbol(Symbol::Or) => {
+                let (params, sym_close) =
+                    self.parse_punctuated(Symbol::Or, Self::parse_closure_param)?;
+
+                self.parse_closure(first, params, Some(sym_close))
+            }
+            TokenType::Symbol(Symbol::LogicalOr) => {
+                self.parse_closure(first, Punctuated::new(), None)
+            }
+            TokenType::Symbol(_) | TokenType::Keyword(_) => {
+                Err(self.error_at(ErrorType::ExpectedOneOf(EXPR_START.to_vec()), Some(first)))?
+            }
+        }
+    }
+
+    fn path_expr(path: Path<'input>) -> Expr<'input, 'hacx> {
+        match path.as_ident() {
+            Some(ident) => Expr::Identifier(ident),
+            None => Expr::Path(path),
+        }
+    }
+
+    fn parse_closure_param(&mut self) -> Result<ClosureParam<'input, 'hacx>> {
+        // `|` closes the parameter list, so or-patterns must be in parentheses
+        let pattern = self.parse_primary_pattern()?;
+
+        let ty = match self.eat(TokenType::Symbol(Symbol::Colon))? {
+            Some(sym_colon) => Some(TypeAnnotation {
+                sym_colon,
+                ty: self.parse_type()?,
+            }),
+            None => None,
+        };
+
+        Ok(ClosureParam { pattern, ty })
+    }
+
+    /// parses the return type and body of a closure after the parameters
+    fn parse_closure(
+        &mut self,
+        sym_open: Token<'input>,
+        params: Punctuated<'input, ClosureParam<'input, 'hacx>>,
+        sym_close: Option<Token<'input>>,
+    ) -> Result<Expr<'input, 'hacx>> {
+        let ret = self.parse_return_type()?;
+
+        let body = if ret.is_some() {
+            let sym_open = self.expect(TokenType::Symbol(Symbol::OpenBrace))?;
+            Expr::Block(self.with_struct_literals(true, |this| this.parse_block(sym_open))?)
+        } else {
+            self.parse_expr()?
+        };
+
+        Ok(Expr::Closure(Closure {
+            sym_open,
+            params,
+            sym_close,
+            ret,
+            body: self.ctx.alloc(body),
+        }))
+    }
+
+    fn parse_struct_expr(
+        &mut self,
+        name: Path<'input>,
+        sym_open: Token<'input>,
+    ) -> Result<Expr<'input, 'hacx>> {
+        let (fields, sym_close) = self.parse_punctuated(Symbol::CloseBrace, |this| {
+            let ident = this.expect(TokenType::Identifier)?;
+
+            let value = match this.eat(TokenType::Symbol(Symbol::Colon))? {
+                Some(sym_colon) => Some((sym_colon, this.parse_expr()?)),
+                None => None,
+            };
+
+            Ok(FieldInit { ident, value })
+        })?;
+
+        Ok(Expr::Struct(StructExpr {
+            name,
+            sym_open,
+            fields,
+            sym_close,
+        }))
+    }
+
+    fn parse_match(&mut self, kw_match: Token<'input>) -> Result<Expr<'input, 'hacx>> {
+        let scrutinee = self.with_struct_literals(false, Self::parse_expr)?;
+        let scrutinee = self.ctx.alloc(scrutinee);
+        let sym_open = self.expect(TokenType::Symbol(Symbol::OpenBrace))?;
+
+        let mut arms = Vec::new();
+
+        let sym_close = loop {
+            if let Some(sym_close) = self.eat(TokenType::Symbol(Symbol::CloseBrace))? {
+                break sym_close;
+            }
+
+            let pattern = self.parse_pattern()?;
+            let sym_fat_arrow = self.expect(TokenType::Symbol(Symbol::FatArrow))?;
+            let value = self.with_struct_literals(true, Self::parse_expr)?;
+            let sym_comma = self.eat(TokenType::Symbol(Symbol::Comma))?;
+
+            // the comma is optional after a block-like arm
+            let is_last = sym_comma.is_none() && !value.is_block_like();
+
+            arms.push(MatchArm {
+                pattern,
+                sym_fat_arrow,
+                value,
+                sym_comma,
+            });
+
+            if is_last {
+                break self.expect(TokenType::Symbol(Symbol::CloseBrace))?;
+            }
+        };
+
+        Ok(Expr::Match(Match {
+            kw_match,
+            scrutinee,
+            sym_open,
+            arms,
+            sym_close,
+        }))
+    }
+
+    fn parse_if(&mut self, kw_if: Token<'input>) -> Result<Expr<'input, 'hacx>> {
+        let condition = self.with_struct_literals(false, Self::parse_expr)?;
+        let condition = self.ctx.alloc(condition);
+
+        let sym_open = self.expect(TokenType::Symbol(Symbol::OpenBrace))?;
+        let then_branch = self.with_struct_literals(true, |this| this.parse_block(sym_open))?;
+
+        let else_branch = match self.eat(TokenType::Keyword(Keyword::Else))? {
+            Some(kw_else) => {
+                let token = self.expect_any(any!(
+                    TokenType::Keyword(Keyword::If),
+                    TokenType::Symbol(Symbol::OpenBrace)
+                ))?;
+
+                let value = if token.data.tok_type() == TokenType::Keyword(Keyword::If) {
+                    self.parse_if(token)?
+                } else {
+                    Expr::Block(self.with_struct_literals(true, |this| this.parse_block(token))?)
+                };
+
+                Some(Else {
+                    kw_else,
+                    value: self.ctx.alloc(value),
+                })
+            }
+            None => None,
+        };
+
+        Ok(Expr::If(If {
+            kw_if,
+            condition,
+            then_branch,
+            else_branch,
+        }))
+    }
+}
+
+#[cfg(test)]
+use super::{context, parse_one};
+#[cfg(test)]
+use lib_parser_types::{Block, ExprStmt, HAst, Let, Pattern, StructPattern, TuplePattern};
+
+#[test]
+fn parse_match() {
+    let ctx = context::LocalContext::default();
+
+    let ast = parse_one(
+        "let x = match y { 0 | 1 => a, _ => { b } z => z };",
+        ctx.as_ref(),
+    );
+
+    let ast_match = match ast {
+        HAst::Let(Let {
+            value: Expr::Match(ast_match),
+            ..
+        }) => ast_match,
+        ast => panic!("expected a match expression, found {:?}", ast),
+    };
+
+    assert_eq!(ast_match.arms.len(), 3);
+
+    match &ast_match.arms[0].pattern {
+        Pattern::Or(patterns) => assert_eq!(patterns.len(), 2),
+        pattern => panic!("expected an or-pattern, found {:?}", pattern),
+    }
+
+    match ast_match.arms[1].pattern {
+        Pattern::Wildcard(_) => (),
+        ref pattern => panic!("expected a wildcard, found {:?}", pattern),
+    }
+
+    match ast_match.arms[2].pattern {
+        Pattern::Binding(_) => (),
+        ref pattern => panic!("expected a binding, found {:?}", pattern),
+    }
+
+    assert!(ast_match.arms[1].sym_comma.is_none());
+    assert!(ast_match.arms[2].sym_comma.is_none());
+}
+
+#[test]
+fn parse_if_else() {
+    let ctx = context::LocalContext::default();
+
+    let ast = parse_one(
+        "let x = if a < b && !c { 1 } else if d { 2 } else { 3 };",
+        ctx.as_ref(),
+    );
+
+    let ast_if = match ast {
+        HAst::Let(Let {
+            value: Expr::If(ast_if),
+            ..
+        }) => ast_if,
+        ast => panic!("expected an if expression, found {:?}", ast),
+    };
+
+    match ast_if.condition {
+        Expr::Logical(
+            Expr::Binary(_, op!(LessThan), _),
+            op!(LogicalAnd),
+            Expr::Prefix(op!(Not), _),
+        ) => {}
+        ref condition => panic!("unexpected condition {:?}", condition),
+    }
+
+    let else_if = match ast_if.else_branch {
+        Some(Else {
+            value: Expr::If(ref else_if),
+            ..
+        }) => else_if,
+        ref else_branch => panic!("expected an else-if, found {:?}", else_branch),
+    };
+
+    match else_if.else_branch {
+        Some(Else {
+            value: Expr::Block(_),
+            ..
+        }) => (),
+        ref else_branch => panic!("expected an else block, found {:?}", else_branch),
+    }
+}
+
+#[test]
+fn parse_precedence() {
+    let ctx = context::LocalContext::default();
+
+    let ast = parse_one("let x = -1 + 2 * (3 - 4) == 7 || false;", ctx.as_ref());
+
+    let value = match ast {
+        HAst::Let(Let { value, .. }) => value,
+        ast => panic!("expected a let, found {:?}", ast),
+    };
+
+    let (left, right) = match value {
+        Expr::Logical(left, op!(LogicalOr), right) => (left, right),
+        value => panic!("expected `||` at the root, found {:?}", value),
+    };
+
+    match right {
+        Expr::Literal(Literal::Bool(_)) => (),
+        right => panic!("expected a bool, found {:?}", right),
+    }
+
+    let sum = match left {
+        Expr::Binary(sum, op!(Equal), _) => sum,
+        left => panic!("expected `==`, found {:?}", left),
+    };
+
+    match sum {
+        Expr::Binary(
+            Expr::Prefix(op!(Sub), _),
+            op!(Add),
+            Expr::Binary(
+                _,
+                op!(Mul),
+                Expr::Paren(ParenExpr {
+                    expr: Expr::Binary(_, op!(Sub), _),
+                    ..
+                }),
+            ),
+        ) => (),
+        sum => panic!("unexpected sum {:?}", sum),
+    }
+}
+
+#[test]
+fn parse_call() {
+    let ctx = context::LocalContext::default();
+
+    let ast = parse_one("let x = -f(a, g(b)(),)(c);", ctx.as_ref());
+
+    let call = match ast {
+        HAst::Let(Let {
+            value: Expr::Prefix(op!(Sub), Expr::Call(call)),
+            ..
+        }) => call,
+        ast => panic!("expected a negated call, found {:?}", ast),
+    };
+
+    assert_eq!(call.args.len(), 1);
+
+    let inner = match call.callee {
+        Expr::Call(ref inner) => inner,
+        ref callee => panic!("expected a call, found {:?}", callee),
+    };
+
+    assert_eq!(inner.args.len(), 2);
+    assert!(inner.args.has_trailing());
+
+    match inner.args.items[1] {
+        Expr::Call(Call {
+            callee: Expr::Call(_),
+            ref args,
+            ..
+        }) => assert!(args.is_empty()),
+        ref arg => panic!("expected a nested call, found {:?}", arg),
+    }
+}
+
+#[test]
+fn parse_field_access() {
+    let ctx = context::LocalContext::default();
+
+    let ast = parse_one("let v = -a.b.0.1.c(d).e * 2;", ctx.as_ref());
+
+    let field = match ast {
+        HAst::Let(Let {
+            value: Expr::Binary(Expr::Prefix(op!(Sub), Expr::Field(field)), op!(Mul), _),
+            ..
+        }) => field,
+        ast => panic!("expected a negated field access, found {:?}", ast),
+    };
+
+    let method_call = match field.base {
+        Expr::MethodCall(ref method_call) => method_call,
+        ref base => panic!("expected a method call, found {:?}", base),
+    };
+
+    assert_eq!(method_call.args.len(), 1);
+
+    match method_call.receiver {
+        Expr::TupleIndex(TupleIndex {
+            base:
+                Expr::TupleIndex(TupleIndex {
+                    base: Expr::Field(_),
+                    ..
+                }),
+            ..
+        }) => (),
+        ref receiver => panic!("expected nested tuple indices, found {:?}", receiver),
+    }
+}
+
+#[test]
+fn parse_composite_literals() {
+    let ctx = context::LocalContext::default();
+
+    let ast = parse_one(
+        "let v = [Point { x: 1, y }, (a, (b), ()), (c,)];",
+        ctx.as_ref(),
+    );
+
+    let array = match ast {
+        HAst::Let(Let {
+            value: Expr::Array(array),
+            ..
+        }) => array,
+        ast => panic!("expected an array, found {:?}", ast),
+    };
+
+    match array.items.items[..] {
+        [Expr::Struct(ref point), Expr::Tuple(ref triple), Expr::Tuple(ref single)] => {
+            assert!(point.fields.items[0].value.is_some());
+            assert!(point.fields.items[1].value.is_none());
+
+            match triple.items.items[..] {
+                [Expr::Identifier(_), Expr::Paren(_), Expr::Tuple(ref unit)] => {
+                    assert!(unit.items.is_empty())
+                }
+                ref items => panic!("unexpected tuple items {:?}", items),
+            }
+
+            assert_eq!(single.items.len(), 1);
+        }
+        ref items => panic!("unexpected array items {:?}", items),
+    }
+}
+
+#[test]
+fn parse_struct_literal_restriction() {
+    let ctx = context::LocalContext::default();
+
+    let ast = parse_one(
+        "match p { Point { x, y: 0 } => x, Point { x: (a, _) } => a }",
+        ctx.as_ref(),
+    );
+
+    let ast_match = match ast {
+        HAst::Expr(ExprStmt {
+            expr: Expr::Match(ast_match),
+            ..
+        }) => ast_match,
+        ast => panic!("expected a match, found {:?}", ast),
+    };
+
+    match ast_match.scrutinee {
+        Expr::Identifier(_) => (),
+        ref scrutinee => panic!("expected an identifier, found {:?}", scrutinee),
+    }
+
+    match ast_match.arms[1].pattern {
+        Pattern::Struct(StructPattern { ref fields, .. }) => match fields.items[0].pattern {
+            Some((_, Pattern::Tuple(TuplePattern { ref items, .. }))) => assert_eq!(items.len(), 2),
+            ref pattern => panic!("expected a tuple pattern, found {:?}", pattern),
+        },
+        ref pattern => panic!("expected a struct pattern, found {:?}", pattern),
+    }
+
+    let ast = parse_one("if (Point { x: 1 }) == p { }", ctx.as_ref());
+
+    match ast {
+        HAst::Expr(ExprStmt {
+            expr:
+                Expr::If(If {
+                    condition:
+                        Expr::Binary(
+                            Expr::Paren(ParenExpr {
+                                expr: Expr::Struct(_),
+                                ..
+                            }),
+                            op!(Equal),
+                            _,
+                        ),
+                    ..
+                }),
+            ..
+        }) => (),
+        ast => panic!(
+            "expected a struct literal in the condition, found {:?}",
+            ast
+        ),
+    }
+
+    // the braces of a match or an if in the head open a new context
+    match parse_one("if match x { _ => P { a: 1 } } == q {}", ctx.as_ref()) {
+        HAst::Expr(ExprStmt {
+            expr:
+                Expr::If(If {
+                    condition: Expr::Binary(Expr::Match(ast_match), op!(Equal), _),
+                    ..
+                }),
+            ..
+        }) => match ast_match.arms[0].value {
+            Expr::Struct(_) => (),
+            ref value => panic!("expected a struct literal, found {:?}", value),
+        },
+        ast => panic!("expected a match in the condition, found {:?}", ast),
+    }
+
+    match parse_one(
+        "match if c { P { a: 1 } } else { q } { _ => 1 }",
+        ctx.as_ref(),
+    ) {
+        HAst::Expr(ExprStmt {
+            expr: Expr::Match(ast_match),
+            ..
+        }) => match ast_match.scrutinee {
+            Expr::If(If {
+                then_branch:
+                    Block {
+                        tail: Some(Expr::Struct(_)),
+                        ..
+                    },
+                ..
+            }) => assert_eq!(ast_match.arms.len(), 1),
+            ref scrutinee => panic!(
+                "expected an if with a struct literal, found {:?}",
+                scrutinee
+            ),
+        },
+        ast => panic!("expected a match, found {:?}", ast),
+    }
+}
+
+#[test]
+fn parse_closures() {
+    let ctx = context::LocalContext::default();
+    let ctx = ctx.as_ref();
+
+    match parse_one("let f = |a: i32, (b, c)| a + b * c;", ctx) {
+        HAst::Let(Let {
+            value: Expr::Closure(closure),
+            ..
+        }) => {
+            assert_eq!(closure.params.len(), 2);
+            assert!(closure.params.items[0].ty.is_some());
+            assert!(closure.sym_close.is_some());
+
+            match closure.body {
+                Expr::Binary(_, op!(Add), _) => (),
+                ref body => panic!("expected the closure to own the sum, found {:?}", body),
+            }
+        }
+        ast => panic!("expected a closure, found {:?}", ast),
+    }
+
+    match parse_one("let g = || -> i32 { 1 };", ctx) {
+        HAst::Let(Let {
+            value: Expr::Closure(closure),
+            ..
+        }) => {
+            assert!(closure.params.is_empty());
+            assert!(closure.sym_close.is_none());
+            assert!(closure.ret.is_some());
+        }
+        ast => panic!("expected a closure, found {:?}", ast),
+    }
+
+    match parse_one("let h = x || |y| y || z;", ctx) {
+        HAst::Let(Let {
+            value: Expr::Logical(Expr::Identifier(_), op!(LogicalOr), Expr::Closure(closure)),
+            ..
+        }) => match closure.body {
+            Expr::Logical(_, op!(LogicalOr), _) => (),
+            ref body => panic!("expected the closure to own the `||`, found {:?}", body),
+        },
+        ast => panic!("expected a logical or, found {:?}", ast),
+    }
+}
+
+#[test]
+fn parse_turbofish() {
+    let ctx = context::LocalContext::default();
+    let ctx = ctx.as_ref();
+
+    match parse_one("let x = f::<i32, Vec<u8>>(a).g::<T>();", ctx) {
+        HAst::Let(Let {
+            value: Expr::MethodCall(method_call),
+            ..
+        }) => {
+            assert!(method_call.generics.is_some());
+
+            match method_call.receiver {
+                Expr::Call(Call {
+                    callee: Expr::Turbofish(Turbofish { ref args, .. }),
+                    ..
+                }) => assert_eq!(args.args.len(), 2),
+                ref receiver => panic!("expected a call with a turbofish, found {:?}", receiver),
+            }
+        }
+        ast => panic!("expected a method call, found {:?}", ast),
+    }
+
+    // without the turbofish `<` and `>` are comparisons
+    match parse_one("let y = f<T>(a);", ctx) {
+        HAst::Let(Let {
+            value: Expr::Binary(Expr::Binary(_, op!(LessThan), _), op!(GreaterThan), _),
+            ..
+        }) => (),
+        ast => panic!("expected comparisons, found {:?}", ast),
+    }
+}
+
+#[test]
+fn parse_paths() {
+    let ctx = context::LocalContext::default();
+
+    let ast = parse_one(
+        "let x = a::b::<T>(shapes::Point { x: 1 }, Shape::Empty);",
+        ctx.as_ref(),
+    );
+
+    let call = match ast {
+        HAst::Let(Let {
+            value: Expr::Call(call),
+            ..
+        }) => call,
+        ast => panic!("expected a call, found {:?}", ast),
+    };
+
+    match call.callee {
+        Expr::Turbofish(Turbofish {
+            base: Expr::Path(ref path),
+            ref args,
+        }) => {
+            assert_eq!(path.segments.len(), 2);
+            assert!(args.sym_path_sep.is_some());
+        }
+        ref callee => panic!("expected a path with a turbofish, found {:?}", callee),
+    }
+
+    match call.args.items[..] {
+        [Expr::Struct(ref point), Expr::Path(ref variant)] => {
+            assert_eq!(point.name.segments.len(), 2);
+            assert_eq!(variant.segments.len(), 2);
+        }
+        ref args => panic!("unexpected arguments {:?}", args),
+    }
+}
+
+#[test]
+fn expr_spans() {
+    use lib_parser_types::Spanned;
+
+    let ctx = context::LocalContext::default();
+
+    let ast = parse_one("let x = -a.b(c)\n  + (d);", ctx.as_ref());
+
+    let span = |span: lib_lexer_types::Span| {
+        let (start, end) = (span.start(), span.end());
+        ((start.row(), start.col()), (end.row(), end.col()))
+    };
+
+    assert_eq!(span(ast.span()), ((1, 1), (2, 9)));
+
+    let (left, op, right) = match ast {
+        HAst::Let(Let {
+            value: Expr::Binary(left, op, right),
+            ..
+        }) => (left, op, right),
+        ast => panic!("expected a binary expression, found {:?}", ast),
+    };
+
+    assert_eq!(span(left.span()), ((1, 9), (1, 16)));
+    assert_eq!(span(op.span), ((2, 3), (2, 4)));
+    // the parentheses are part of the expression
+    assert_eq!(span(right.span()), ((2, 5), (2, 8)));
+
+    match right {
+        Expr::Paren(paren) => assert_eq!(span(paren.expr.span()), ((2, 6), (2, 7))),
+        right => panic!("expected a parenthesized expression, found {:?}", right),
+    }
+
+    let ast = parse_one("(a + b) * c;", ctx.as_ref());
+    assert_eq!(span(ast.span()), ((1, 1), (1, 13)));
+
+    let ast = parse_one("(a).b;", ctx.as_ref());
+    assert_eq!(span(ast.span()), ((1, 1), (1, 7)));
+}
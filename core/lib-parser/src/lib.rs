@@ -1,16 +1,16 @@
 #![allow(clippy::try_err)]
 
-use lib_error::WithContext as _;
+use lib_lexer_types::{CodePoint, Lexer, Peekable, Span, Symbol, Token, TokenType};
 
-use lib_lexer_types::{Keyword, Lexer, Peekable, Symbol, Token, TokenData, TokenType};
-
-use lib_parser_types::{context::ContextRef, Error, Expr, HAst, Literal, Result};
+use lib_parser_types::{
+    context::ContextRef, Block, Error, ErrorType, HAst, ParseError, Path, Punctuated, Result,
+};
 
 pub use lib_parser_types::context;
 
 macro_rules! try_lex {
     ($lexer:expr) => {
-        $lexer.with_context(Error::LexError)?
+        $lexer.map_err(lib_parser_types::lex_error)?
     };
 }
 
@@ -21,9 +21,32 @@ macro_rules! any {
     }
 }
 
+/// matches an operator token of the given symbol
+#[cfg(test)]
+macro_rules! op {
+    ($symbol:ident) => {
+        lib_lexer_types::Token {
+            data: lib_lexer_types::TokenData::Symbol(lib_lexer_types::Symbol::$symbol),
+            ..
+        }
+    };
+}
+
+mod attr;
+mod expr;
+mod pattern;
+mod stmt;
+mod ty;
+
 pub struct Parser<'input, 'hacx, L> {
     lexer: Peekable<'input, L>,
     ctx: ContextRef<'input, 'hacx>,
+    // struct literals are ambiguous with the block after an `if` condition or `match` scrutinee
+    struct_literals: bool,
+    /// the span of the last token, where errors at the end of the input are reported
+    last: Option<Span>,
+    /// the span of the token before the last token
+    prev: Option<Span>,
 }
 
 impl<'input, 'hacx, L: Lexer<'input>> Parser<'input, 'hacx, L> {
@@ -31,15 +54,148 @@ impl<'input, 'hacx, L: Lexer<'input>> Parser<'input, 'hacx, L> {
         Self {
             lexer: lexer.peekable(),
             ctx,
+            struct_literals: true,
+            last: None,
+            prev: None,
         }
     }
 
-    fn expect(&mut self, tok_type: TokenType) -> Result<Token<'input>> {
+    fn with_struct_literals<T>(
+        &mut self,
+        allowed: bool,
+        f: impl FnOnce(&mut Self) -> Result<T>,
+    ) -> Result<T> {
+        let old = std::mem::replace(&mut self.struct_literals, allowed);
+        let result = f(self);
+        self.struct_literals = old;
+        result
+    }
+
+    fn next_token(&mut self) -> Result<Option<Token<'input>>> {
         let token = try_lex!(self.lexer.parse());
 
+        if let Some(token) = token {
+            self.prev = self.last;
+            self.last = Some(token.span);
+        }
+
+        Ok(token)
+    }
+
+    /// the character after the last token, or the first character if there were no tokens
+    fn end_span(&self) -> Span {
+        let start = match self.last {
+            Some(last) => last.end(),
+            None => CodePoint::new_unchecked(1, 1),
+        };
+
+        start.span(CodePoint::new_unchecked(start.row(), start.col() + 1))
+    }
+
+    /// an error at `token`, or at the end of the input if there are no tokens left
+    fn error_at(&self, err: ErrorType, token: Option<Token<'input>>) -> ParseError {
+        let span = match token {
+            Some(token) => token.span,
+            None => self.end_span(),
+        };
+
+        // `token` may already be taken, which makes it the last token
+        let prev = match token {
+            Some(token) if Some(token.span) == self.last => self.prev,
+            _ => self.last,
+        };
+
+        Error::new(err, span)
+            .with_prev_end(prev.map(|prev| prev.end()))
+            .into()
+    }
+
+    /// an error at the next token
+    fn error_here(&mut self, err: ErrorType) -> ParseError {
+        match self.lexer.peek() {
+            Ok(token) => self.error_at(err, token),
+            Err(lex) => lib_parser_types::lex_error(lex),
+        }
+    }
+
+    fn expect(&mut self, tok_type: TokenType) -> Result<Token<'input>> {
+        let token = self.next_token()?;
+
         match token {
             Some(token) if token.data.tok_type() == tok_type => Ok(token),
-            _ => Err(Error::Expected(tok_type))?,
+            _ => Err(self.error_at(ErrorType::Expected(tok_type), token))?,
+        }
+    }
+
+    fn peek_type(&mut self) -> Result<Option<TokenType>> {
+        let token = try_lex!(self.lexer.peek());
+
+        Ok(token.map(|token| token.data.tok_type()))
+    }
+
+    fn eat(&mut self, tok_type: TokenType) -> Result<Option<Token<'input>>> {
+        if self.peek_type()? == Some(tok_type) {
+            self.next_token()
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// parses a comma separated list of items up to and including the `close` symbol
+    fn parse_punctuated<T>(
+        &mut self,
+        close: Symbol,
+        mut parse_item: impl FnMut(&mut Self) -> Result<T>,
+    ) -> Result<(Punctuated<'input, T>, Token<'input>)> {
+        let mut items = Punctuated::new();
+
+        loop {
+            if let Some(sym_close) = self.eat(TokenType::Symbol(close))? {
+                break Ok((items, sym_close));
+            }
+
+            items.items.push(parse_item(self)?);
+
+            match self.eat(TokenType::Symbol(Symbol::Comma))? {
+                Some(sym_comma) => items.separators.push(sym_comma),
+                None => break Ok((items, self.expect(TokenType::Symbol(close))?)),
+            }
+        }
+    }
+
+    /// parses the rest of a path after its first segment
+    ///
+    /// if a `::` isn't followed by an identifier then the path ends before it,
+    /// and that `::` is returned alongside the path, so that callers can
+    /// parse a turbofish or report an error
+    fn parse_path(
+        &mut self,
+        first: Token<'input>,
+    ) -> Result<(Path<'input>, Option<Token<'input>>)> {
+        let mut segments = Punctuated::new();
+        segments.items.push(first);
+
+        loop {
+            let sym_path_sep = match self.eat(TokenType::Symbol(Symbol::PathSep))? {
+                Some(sym_path_sep) => sym_path_sep,
+                None => break Ok((Path { segments }, None)),
+            };
+
+            match self.eat(TokenType::Identifier)? {
+                Some(ident) => {
+                    segments.separators.push(sym_path_sep);
+                    segments.items.push(ident);
+                }
+                None => break Ok((Path { segments }, Some(sym_path_sep))),
+            }
+        }
+    }
+
+    /// parses a path that may not be followed by a turbofish
+    fn parse_plain_path(&mut self, first: Token<'input>) -> Result<Path<'input>> {
+        match self.parse_path(first)? {
+            (path, None) => Ok(path),
+            (_, Some(_)) => Err(self.error_here(ErrorType::Expected(TokenType::Identifier)))?,
         }
     }
 
@@ -47,7 +203,7 @@ impl<'input, 'hacx, L: Lexer<'input>> Parser<'input, 'hacx, L> {
         &mut self,
         tok_type: impl Clone + IntoIterator<Item = TokenType>,
     ) -> Result<Token<'input>> {
-        let token = try_lex!(self.lexer.parse());
+        let token = self.next_token()?;
 
         match token {
             Some(token)
@@ -58,90 +214,380 @@ impl<'input, 'hacx, L: Lexer<'input>> Parser<'input, 'hacx, L> {
             {
                 Ok(token)
             }
-            _ => Err(Error::ExpectedOneOf(tok_type.into_iter().collect()))?,
+            _ => Err(self.error_at(
+                ErrorType::ExpectedOneOf(tok_type.into_iter().collect()),
+                token,
+            ))?,
         }
     }
 
+    /// parses a block, `{ ... }`
+    pub fn parse_block_expr(&mut self) -> Result<Block<'input, 'hacx>> {
+        let sym_open = self.expect(TokenType::Symbol(Symbol::OpenBrace))?;
+
+        self.parse_block(sym_open)
+    }
+
     pub fn parse(&mut self) -> Result<Option<HAst<'input, 'hacx>>> {
-        let token = try_lex!(self.lexer.parse());
-        let token = match token {
-            Some(token) => token,
-            None => return Ok(None),
-        };
+        if self.peek_type()?.is_none() {
+            return Ok(None);
+        }
 
-        match token.data {
-            TokenData::Keyword(Keyword::Let) => self.parse_let(token),
-            _ => Ok(None),
+        match self.parse_stmt()? {
+            stmt::Stmt::Item(ast) => Ok(Some(ast)),
+            stmt::Stmt::Expr(expr) => {
+                if expr.is_block_like() {
+                    Ok(Some(self.expr_stmt(expr, None)))
+                } else {
+                    Err(self.error_here(ErrorType::Expected(TokenType::Symbol(Symbol::Semicolon))))?
+                }
+            }
         }
     }
+}
 
-    fn parse_let(&mut self, kw_let: Token<'input>) -> Result<Option<HAst<'input, 'hacx>>> {
-        let token = self.expect_any(any!(
-            TokenType::Keyword(Keyword::Mut),
-            TokenType::Identifier
-        ))?;
+impl<'input, 'hacx, L: Lexer<'input>> lib_parser_types::Parser<'input, 'hacx>
+    for Parser<'input, 'hacx, L>
+{
+    fn parse(&mut self) -> Result<Option<HAst<'input, 'hacx>>> {
+        self.parse()
+    }
+}
 
-        let (kw_mut, ident) = if let TokenData::Keyword(Keyword::Mut) = token.data {
-            let ident = self.expect(TokenType::Identifier)?;
+#[cfg(test)]
+fn parse_one<'input, 'hacx>(
+    input: &'input str,
+    ctx: ContextRef<'input, 'hacx>,
+) -> HAst<'input, 'hacx> {
+    let mut parser = Parser::new(lib_lexer::Lexer::new(input), ctx);
 
-            (Some(token), ident)
-        } else {
-            (None, token)
-        };
+    parser.parse().unwrap().expect("no statement was parsed")
+}
 
-        let sym_assign = self.expect(TokenType::Symbol(Symbol::Assign))?;
-        let value = self.parse_expr()?;
-        let sym_semi = self.expect(TokenType::Symbol(Symbol::Semicolon))?;
-
-        let ast_let = lib_parser_types::Let {
-            kw_let,
-            kw_mut,
-            ident,
-            sym_assign,
-            value,
-            sym_semi,
-        };
+#[test]
+fn visit_and_fold() {
+    use lib_lexer_types::Token;
+    use lib_lexer_types::{Keyword, TokenData};
+    use lib_parser_types::{fold, visit, visit_mut, Binding, Call, Expr, Literal};
 
-        Ok(Some(HAst::Let(self.ctx.alloc(ast_let))))
+    #[derive(Default)]
+    struct Calls {
+        calls: usize,
+        identifiers: usize,
+        bindings: usize,
     }
 
-    fn parse_expr(&mut self) -> Result<Expr<'input, 'hacx>> {
-        self.parse_primary()
+    impl<'input, 'hacx> visit::Visitor<'input, 'hacx> for Calls {
+        fn visit_call(&mut self, call: &Call<'input, 'hacx>) {
+            self.calls += 1;
+            visit::walk_call(self, call)
+        }
+
+        fn visit_identifier(&mut self, _ident: &Token<'input>) {
+            self.identifiers += 1;
+        }
+
+        fn visit_binding(&mut self, _binding: &Binding<'input>) {
+            self.bindings += 1;
+        }
     }
 
-    fn parse_primary(&mut self) -> Result<Expr<'input, 'hacx>> {
-        let first = match try_lex!(self.lexer.parse()) {
-            Some(first) => first,
-            None => Err(Error::ExpectedOneOf(vec![
-                TokenType::Identifier,
-                TokenType::Integer,
-                TokenType::Float,
-            ]))?,
-        };
+    struct Negate;
 
-        match first.data.tok_type() {
-            TokenType::Identifier => Ok(Expr::Identifier(first)),
-            TokenType::Integer => Ok(Expr::Literal(Literal::Integer(first))),
-            TokenType::Float => Ok(Expr::Literal(Literal::Float(first))),
-            TokenType::StringLiteral => Ok(Expr::Literal(Literal::String(first))),
-            TokenType::Symbol(_) | TokenType::Keyword(_) => unreachable!(),
+    impl<'input, 'hacx> visit_mut::VisitorMut<'input, 'hacx> for Negate {
+        fn visit_literal(&mut self, literal: &mut Literal<'input>) {
+            if let Literal::Bool(token) = literal {
+                token.data = match token.data {
+                    TokenData::Keyword(Keyword::True) => TokenData::Keyword(Keyword::False),
+                    _ => TokenData::Keyword(Keyword::True),
+                };
+            }
         }
     }
 
-    fn parse_arith_prod(&mut self) -> Result<Expr<'input, 'hacx>> {
-        let left = self.parse_primary()?;
+    struct Rebuild<'input, 'new>(ContextRef<'input, 'new>);
 
-        loop {
-            try_lex!(self.lexer.peek());
-            let right = self.parse_primary()?;
+    impl<'input, 'new> fold::Fold<'input, 'new> for Rebuild<'input, 'new> {
+        fn context(&self) -> ContextRef<'input, 'new> {
+            self.0
+        }
+    }
+
+    let ctx = context::LocalContext::default();
+    let source = "fn f(x: i32) -> bool { match g(x, |y| h(y)) { 0 => true, _ => { false } } }";
+    let mut ast = parse_one(source, ctx.as_ref());
+
+    let mut calls = Calls::default();
+    visit::Visitor::visit_hast(&mut calls, &ast);
+    assert_eq!(calls.calls, 2);
+    // `g`, `x`, `h` and `y` are read, and only the closure binds a pattern
+    assert_eq!(calls.identifiers, 4);
+    assert_eq!(calls.bindings, 1);
+
+    visit_mut::VisitorMut::visit_hast(&mut Negate, &mut ast);
+    visit_mut::VisitorMut::visit_hast(&mut Negate, &mut ast);
+    assert_eq!(ast, parse_one(source, ctx.as_ref()));
+
+    let new_ctx = context::LocalContext::default();
+    let copy = fold::Fold::fold_hast(&mut Rebuild(new_ctx.as_ref()), &ast);
+    assert_eq!(copy, ast);
+
+    visit_mut::VisitorMut::visit_hast(&mut Negate, &mut ast);
+    assert_ne!(copy, ast);
+
+    if let HAst::Function(function) = copy {
+        match function.body.tail {
+            Some(Expr::Match(ref ast_match)) => assert_eq!(ast_match.arms.len(), 2),
+            ref tail => panic!("expected a match, found {:?}", tail),
         }
     }
 }
 
-impl<'input, 'hacx, L: Lexer<'input>> lib_parser_types::Parser<'input, 'hacx>
-    for Parser<'input, 'hacx, L>
-{
-    fn parse(&mut self) -> Result<Option<HAst<'input, 'hacx>>> {
-        self.parse()
+/// replaces every span in the debug output of a tree with `_`, so that trees
+/// parsed from differently formatted sources can be compared
+#[cfg(test)]
+fn without_spans(debug: &str) -> String {
+    const SPAN: &str = "span: Span {";
+
+    let mut out = String::new();
+    let mut rest = debug;
+
+    while let Some(start) = rest.find(SPAN) {
+        out.push_str(&rest[..start]);
+        out.push_str("span: _");
+
+        let mut depth = 0;
+        let end = rest[start..]
+            .char_indices()
+            .find_map(|(i, c)| {
+                match c {
+                    '{' => depth += 1,
+                    '}' if depth == 1 => return Some(start + i + 1),
+                    '}' => depth -= 1,
+                    _ => (),
+                }
+
+                None
+            })
+            .expect("unbalanced braces in a span");
+
+        rest = &rest[end..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// parses `input`, prints it, and checks that the printed source parses to the
+/// same tree, ignoring spans, and that printing it again gives the same source
+#[cfg(test)]
+fn assert_round_trip(input: &str) -> String {
+    fn parse_all<'input, 'hacx>(
+        input: &'input str,
+        ctx: ContextRef<'input, 'hacx>,
+    ) -> Vec<HAst<'input, 'hacx>> {
+        let mut parser = Parser::new(lib_lexer::Lexer::new(input), ctx);
+        let mut items = Vec::new();
+
+        while let Some(item) = parser.parse().unwrap() {
+            items.push(item);
+        }
+
+        items
+    }
+
+    let ctx = context::LocalContext::default();
+    let items = parse_all(input, ctx.as_ref());
+    let printed = lib_parser_types::print::to_source(&items);
+
+    let reparsed = parse_all(&printed, ctx.as_ref());
+
+    assert_eq!(
+        without_spans(&format!("{:?}", reparsed)),
+        without_spans(&format!("{:?}", items)),
+        "the printed source parsed to a different tree:\n{}",
+        printed
+    );
+    assert_eq!(lib_parser_types::print::to_source(&reparsed), printed);
+
+    printed
+}
+
+#[test]
+fn print_round_trip() {
+    assert_round_trip(
+        "#[inline] #[cfg(test, 1)] fn add<T>(a: T, b: fn(T,) -> (T,),) -> T { let (x, mut y,): (T, T) = (a, b(a),); y = x; add::<T>(x.0.1, y.max::<i32>(1)) }
+        struct Point<T> { x: T, y: T, } struct Pair(i32, f32); struct Unit;
+        enum Shape { Circle(f32), Rect { w: f32, h: f32 }, Empty, }
+        type Ptr<T> = std::boxed::Box<Vec<T>>;
+        mod shapes; use shapes::circle::Circle;
+        let f = 1.5 + 2.0 * 3.25;",
+    );
+
+    assert_round_trip(
+        "fn main() {
+            let v = if a == (Point { x: 1, y }) { Point { x, y: 2 } } else if !b { {} } else { [1, 2,] };
+            match (Point { x }) { Point { x: 0 | 1, y } => (), _ => {} }
+            match x { (b, c) => b, Some(a) | None => { a } [d, _] => c, a::B => d }
+            if x {} { 1 } - 1;
+            ({ 1 } - 1);
+            (match x {}).y;
+            let c = || -> i32 { 1 };
+            let d = | | x;
+            let e = |(a | b), c: i32| (|x| x)(a) + c;
+            (1).0;
+            if match x { _ => P { a: 1 } } == q {}
+            match if c { P { a: 1 } } else { q } { _ => 1 }
+        }",
+    );
+}
+
+#[test]
+fn print_minimal_parens() {
+    use lib_parser_types::{fold, Expr};
+
+    // the parentheses in the source are kept
+    let source = "let a = (1 + 2) * 3 - (4 - 5) - 6 / (7 % 8);
+let b = -(x + 1) - -(-y);
+let c = (a || b) && !(c && d) || e == (f < g);
+let d = (a + b).c((d)).e;
+let f = |x| |y| x + y;
+let g = (|x| x)(1);
+";
+    assert_eq!(assert_round_trip(source), source);
+
+    // a tree without them only gets the parentheses that it needs
+    struct StripParens<'input, 'new>(ContextRef<'input, 'new>);
+
+    impl<'input, 'new> fold::Fold<'input, 'new> for StripParens<'input, 'new> {
+        fn context(&self) -> ContextRef<'input, 'new> {
+            self.0
+        }
+
+        fn fold_expr(&mut self, expr: &Expr<'input, '_>) -> Expr<'input, 'new> {
+            match expr {
+                Expr::Paren(paren) => self.fold_expr(paren.expr),
+                _ => fold::walk_expr(self, expr),
+            }
+        }
+    }
+
+    let ctx = context::LocalContext::default();
+    let new_ctx = context::LocalContext::default();
+    let mut parser = Parser::new(lib_lexer::Lexer::new(source), ctx.as_ref());
+    let mut items = Vec::new();
+
+    while let Some(item) = parser.parse().unwrap() {
+        let mut strip = StripParens(new_ctx.as_ref());
+        items.push(fold::Fold::fold_hast(&mut strip, &item));
+    }
+
+    let printed = lib_parser_types::print::to_source(&items);
+
+    assert_eq!(
+        printed,
+        "let a = (1 + 2) * 3 - (4 - 5) - 6 / (7 % 8);
+let b = -(x + 1) - --y;
+let c = (a || b) && !(c && d) || e == (f < g);
+let d = (a + b).c(d).e;
+let f = |x| |y| x + y;
+let g = (|x| x)(1);
+"
+    );
+    assert_eq!(assert_round_trip(&printed), printed);
+}
+
+#[test]
+fn print_layout() {
+    let printed = assert_round_trip(
+        "#[test] fn f(x: i32) -> bool { let y = match x { 0 => true, _ => { false } }; if y { y } else { !y } }
+        enum E { A, B(i32) }",
+    );
+
+    assert_eq!(
+        printed,
+        "#[test]
+fn f(x: i32) -> bool {
+    let y = match x {
+        0 => true,
+        _ => {
+            false
+        }
+    };
+    if y {
+        y
+    } else {
+        !y
     }
 }
+enum E {
+    A,
+    B(i32)
+}
+"
+    );
+}
+
+#[test]
+fn dump_sexpr() {
+    let ctx = context::LocalContext::default();
+    let ast = parse_one(
+        "#[inline] fn f(x: i32) -> bool { let y = -x + 1; g(y).0 == z::W { a } }",
+        ctx.as_ref(),
+    );
+
+    assert_eq!(
+        lib_parser_types::dump::sexpr(&[ast]),
+        "(fn f
+  (attr inline)
+  (param x
+    (type i32))
+  (ret
+    (type bool))
+  (block
+    (let
+      (binding y)
+      (binary +
+        (prefix -
+          (ident x))
+        (int 1)))
+    (tail
+      (binary ==
+        (tuple-index 0
+          (call
+            (ident g)
+            (ident y)))
+        (struct-expr z::W
+          (field-init a))))))
+"
+    );
+}
+
+#[test]
+fn dump_json() {
+    let ctx = context::LocalContext::default();
+    let ast = parse_one("x + 1.5;", ctx.as_ref());
+    let json = lib_parser_types::dump::json(&[ast]);
+
+    assert!(json.contains(r#""Identifier": "x""#), "{}", json);
+    assert!(json.contains(r#""Symbol": "Add""#), "{}", json);
+    assert!(json.contains(r#""Float": 1.5"#), "{}", json);
+    assert!(json.contains(r#""row": 1"#), "{}", json);
+}
+
+#[test]
+fn error_display() {
+    let ctx = context::LocalContext::default();
+
+    let parse_err = |input| {
+        let mut parser = Parser::new(lib_lexer::Lexer::new(input), ctx.as_ref());
+        format!("{:#}", parser.parse().unwrap_err())
+    };
+
+    assert_eq!(
+        parse_err("let $ = 1;"),
+        "couldn't read the next token at 1:5, caused by: unknown character '$' at 1:5"
+    );
+    assert_eq!(parse_err("let x = 1"), "expected `;` at 1:10");
+    assert_eq!(parse_err("fn () {}"), "expected identifier at 1:4");
+}
@@ -0,0 +1,210 @@
+use super::Parser;
+
+use lib_lexer_types::{Keyword, Lexer, Symbol, Token, TokenType};
+
+use lib_parser_types::{
+    ArrayPattern, Binding, ErrorType, FieldPattern, Literal, Pattern, Punctuated, Result,
+    StructPattern, TuplePattern, TupleStructPattern,
+};
+
+impl<'input, 'hacx, L: Lexer<'input>> Parser<'input, 'hacx, L> {
+    pub(crate) fn parse_pattern(&mut self) -> Result<Pattern<'input>> {
+        let first = self.parse_primary_pattern()?;
+
+        let sym_or = match self.eat(TokenType::Symbol(Symbol::Or))? {
+            Some(sym_or) => sym_or,
+            None => return Ok(first),
+        };
+
+        let mut patterns = Punctuated::new();
+        patterns.items.push(first);
+        patterns.separators.push(sym_or);
+
+        loop {
+            patterns.items.push(self.parse_primary_pattern()?);
+
+            match self.eat(TokenType::Symbol(Symbol::Or))? {
+                Some(sym_or) => patterns.separators.push(sym_or),
+                None => break Ok(Pattern::Or(patterns)),
+            }
+        }
+    }
+
+    /// parses a pattern without a top level or-pattern
+    pub(crate) fn parse_primary_pattern(&mut self) -> Result<Pattern<'input>> {
+        let token = self.next_token()?;
+
+        match token.map(|token| (token, token.data.tok_type())) {
+            Some((token, TokenType::Identifier)) => self.parse_named_pattern(token),
+            Some((kw_mut, TokenType::Keyword(Keyword::Mut))) => Ok(Pattern::Binding(Binding {
+                kw_mut: Some(kw_mut),
+                ident: self.expect(TokenType::Identifier)?,
+            })),
+            Some((token, TokenType::Symbol(Symbol::Underscore))) => Ok(Pattern::Wildcard(token)),
+            Some((token, TokenType::Integer)) => Ok(Pattern::Literal(Literal::Integer(token))),
+            Some((token, TokenType::Float)) => Ok(Pattern::Literal(Literal::Float(token))),
+            Some((token, TokenType::StringLiteral)) => Ok(Pattern::Literal(Literal::String(token))),
+            Some((token, TokenType::Keyword(Keyword::True)))
+            | Some((token, TokenType::Keyword(Keyword::False))) => {
+                Ok(Pattern::Literal(Literal::Bool(token)))
+            }
+            Some((sym_open, TokenType::Symbol(Symbol::OpenParen))) => {
+                let (mut items, sym_close) =
+                    self.parse_punctuated(Symbol::CloseParen, Self::parse_pattern)?;
+
+                if items.len() == 1 && !items.has_trailing() {
+                    Ok(items.items.pop().unwrap())
+                } else {
+                    Ok(Pattern::Tuple(TuplePattern {
+                        sym_open,
+                        items,
+                        sym_close,
+                    }))
+                }
+            }
+            Some((sym_open, TokenType::Symbol(Symbol::OpenBracket))) => {
+                let (items, sym_close) =
+                    self.parse_punctuated(Symbol::CloseBracket, Self::parse_pattern)?;
+
+                Ok(Pattern::Array(ArrayPattern {
+                    sym_open,
+                    items,
+                    sym_close,
+                }))
+            }
+            _ => Err(self.error_at(
+                ErrorType::ExpectedOneOf(vec![
+                    TokenType::Identifier,
+                    TokenType::Keyword(Keyword::Mut),
+                    TokenType::Symbol(Symbol::Underscore),
+                    TokenType::Integer,
+                    TokenType::Float,
+                    TokenType::StringLiteral,
+                    TokenType::Keyword(Keyword::True),
+                    TokenType::Keyword(Keyword::False),
+                    TokenType::Symbol(Symbol::OpenParen),
+                    TokenType::Symbol(Symbol::OpenBracket),
+                ]),
+                token,
+            ))?,
+        }
+    }
+
+    /// parses the patterns that start with an identifier, which are bindings,
+    /// paths, struct patterns and tuple struct patterns
+    fn parse_named_pattern(&mut self, first: Token<'input>) -> Result<Pattern<'input>> {
+        let name = self.parse_plain_path(first)?;
+
+        if let Some(sym_open) = self.eat(TokenType::Symbol(Symbol::OpenBrace))? {
+            let (fields, sym_close) = self.parse_punctuated(Symbol::CloseBrace, |this| {
+                let ident = this.expect(TokenType::Identifier)?;
+
+                let pattern = match this.eat(TokenType::Symbol(Symbol::Colon))? {
+                    Some(sym_colon) => Some((sym_colon, this.parse_pattern()?)),
+                    None => None,
+                };
+
+                Ok(FieldPattern { ident, pattern })
+            })?;
+
+            Ok(Pattern::Struct(StructPattern {
+                name,
+                sym_open,
+                fields,
+                sym_close,
+            }))
+        } else if let Some(sym_open) = self.eat(TokenType::Symbol(Symbol::OpenParen))? {
+            let (items, sym_close) =
+                self.parse_punctuated(Symbol::CloseParen, Self::parse_pattern)?;
+
+            Ok(Pattern::TupleStruct(TupleStructPattern {
+                name,
+                sym_open,
+                items,
+                sym_close,
+            }))
+        } else {
+            match name.as_ident() {
+                Some(ident) => Ok(Pattern::Binding(Binding {
+                    kw_mut: None,
+                    ident,
+                })),
+                None => Ok(Pattern::Path(name)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+use super::{context, parse_one};
+#[cfg(test)]
+use lib_parser_types::{HAst, Let};
+
+#[test]
+fn parse_let_destructure() {
+    let ctx = context::LocalContext::default();
+
+    let ast = parse_one(
+        "let (mut a, [b, _], Some(c), P { x, y: 0 | 1 }) = v;",
+        ctx.as_ref(),
+    );
+
+    let items = match ast {
+        HAst::Let(Let {
+            pattern: Pattern::Tuple(TuplePattern { items, .. }),
+            ..
+        }) => items,
+        ast => panic!("expected a tuple pattern, found {:?}", ast),
+    };
+
+    match items.items[0] {
+        Pattern::Binding(Binding {
+            kw_mut: Some(_), ..
+        }) => (),
+        ref pattern => panic!("expected a mutable binding, found {:?}", pattern),
+    }
+
+    match items.items[1] {
+        Pattern::Array(ref array) => assert_eq!(array.items.len(), 2),
+        ref pattern => panic!("expected an array pattern, found {:?}", pattern),
+    }
+
+    match items.items[2] {
+        Pattern::TupleStruct(ref tuple_struct) => assert_eq!(tuple_struct.items.len(), 1),
+        ref pattern => panic!("expected a tuple struct pattern, found {:?}", pattern),
+    }
+
+    match items.items[3] {
+        Pattern::Struct(ref ast_struct) => match ast_struct.fields.items[1].pattern {
+            Some((_, Pattern::Or(_))) => (),
+            ref pattern => panic!("expected an or-pattern, found {:?}", pattern),
+        },
+        ref pattern => panic!("expected a struct pattern, found {:?}", pattern),
+    }
+}
+
+#[test]
+fn parse_path_patterns() {
+    let ctx = context::LocalContext::default();
+
+    let ast = parse_one(
+        "let (Shape::Empty, shape::Shape::Circle(r), x) = v;",
+        ctx.as_ref(),
+    );
+
+    let items = match ast {
+        HAst::Let(Let {
+            pattern: Pattern::Tuple(TuplePattern { items, .. }),
+            ..
+        }) => items,
+        ast => panic!("expected a tuple pattern, found {:?}", ast),
+    };
+
+    match items.items[..] {
+        [Pattern::Path(ref empty), Pattern::TupleStruct(ref circle), Pattern::Binding(_)] => {
+            assert_eq!(empty.segments.len(), 2);
+            assert_eq!(circle.name.segments.len(), 3);
+        }
+        ref items => panic!("unexpected patterns {:?}", items),
+    }
+}
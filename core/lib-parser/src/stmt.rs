@@ -0,0 +1,487 @@
+use super::Parser;
+
+use lib_lexer_types::{Keyword, Lexer, Symbol, Token, TokenType};
+
+use lib_parser_types::{
+    Assign, Attribute, Block, Enum, Error, ErrorType, Expr, ExprStmt, FieldDef, Fields, Function,
+    HAst, Let, Mod, NamedFields, Param, Result, Spanned, Struct, TupleFields, TypeAlias,
+    TypeAnnotation, Use, Variant,
+};
+
+#[allow(clippy::large_enum_variant)]
+pub(crate) enum Stmt<'input, 'hacx> {
+    Item(HAst<'input, 'hacx>),
+    /// an expression that wasn't followed by a semicolon, this is either
+    /// the tail of a block or a block-like expression statement
+    Expr(Expr<'input, 'hacx>),
+}
+
+impl<'input, 'hacx, L: Lexer<'input>> Parser<'input, 'hacx, L> {
+    pub(crate) fn parse_stmt(&mut self) -> Result<Stmt<'input, 'hacx>> {
+        let attrs = self.parse_attributes()?;
+
+        match self.peek_type()? {
+            Some(TokenType::Keyword(Keyword::Let)) => {
+                let kw_let = self.expect(TokenType::Keyword(Keyword::Let))?;
+                self.parse_let(attrs, kw_let).map(Stmt::Item)
+            }
+            Some(TokenType::Keyword(Keyword::Type)) => {
+                let kw_type = self.expect(TokenType::Keyword(Keyword::Type))?;
+                self.parse_type_alias(attrs, kw_type).map(Stmt::Item)
+            }
+            Some(TokenType::Keyword(Keyword::Fn)) => {
+                let kw_fn = self.expect(TokenType::Keyword(Keyword::Fn))?;
+                self.parse_function(attrs, kw_fn).map(Stmt::Item)
+            }
+            Some(TokenType::Keyword(Keyword::Struct)) => {
+                let kw_struct = self.expect(TokenType::Keyword(Keyword::Struct))?;
+                self.parse_struct(attrs, kw_struct).map(Stmt::Item)
+            }
+            Some(TokenType::Keyword(Keyword::Enum)) => {
+                let kw_enum = self.expect(TokenType::Keyword(Keyword::Enum))?;
+                self.parse_enum(attrs, kw_enum).map(Stmt::Item)
+            }
+            // only the items above can have attributes
+            _ if !attrs.is_empty() => {
+                let span = attrs[0].span().merge(attrs[attrs.len() - 1].span());
+
+                Err(Error::new(ErrorType::InvalidAttributeTarget, span))?
+            }
+            Some(TokenType::Keyword(Keyword::Mod)) => {
+                let kw_mod = self.expect(TokenType::Keyword(Keyword::Mod))?;
+                self.parse_mod(kw_mod).map(Stmt::Item)
+            }
+            Some(TokenType::Keyword(Keyword::Use)) => {
+                let kw_use = self.expect(TokenType::Keyword(Keyword::Use))?;
+                self.parse_use(kw_use).map(Stmt::Item)
+            }
+            Some(TokenType::Keyword(Keyword::If))
+            | Some(TokenType::Keyword(Keyword::Match))
+            | Some(TokenType::Symbol(Symbol::OpenBrace)) => {
+                // a block-like expression at the start of a statement ends the statement
+                let expr = self.parse_primary()?;
+
+                match self.eat(TokenType::Symbol(Symbol::Semicolon))? {
+                    Some(sym_semi) => Ok(Stmt::Item(self.expr_stmt(expr, Some(sym_semi)))),
+                    None => Ok(Stmt::Expr(expr)),
+                }
+            }
+            _ => {
+                let expr = self.parse_expr()?;
+
+                if let Some(sym_assign) = self.eat(TokenType::Symbol(Symbol::Assign))? {
+                    return self.parse_assign(expr, sym_assign).map(Stmt::Item);
+                }
+
+                match self.eat(TokenType::Symbol(Symbol::Semicolon))? {
+                    Some(sym_semi) => Ok(Stmt::Item(self.expr_stmt(expr, Some(sym_semi)))),
+                    None => Ok(Stmt::Expr(expr)),
+                }
+            }
+        }
+    }
+
+    pub(crate) fn expr_stmt(
+        &mut self,
+        expr: Expr<'input, 'hacx>,
+        sym_semi: Option<Token<'input>>,
+    ) -> HAst<'input, 'hacx> {
+        HAst::Expr(ExprStmt {
+            expr: self.ctx.alloc(expr),
+            sym_semi,
+        })
+    }
+
+    fn parse_let(
+        &mut self,
+        attrs: Vec<Attribute<'input>>,
+        kw_let: Token<'input>,
+    ) -> Result<HAst<'input, 'hacx>> {
+        let pattern = self.parse_pattern()?;
+
+        let ty = match self.eat(TokenType::Symbol(Symbol::Colon))? {
+            Some(sym_colon) => Some(TypeAnnotation {
+                sym_colon,
+                ty: self.parse_type()?,
+            }),
+            None => None,
+        };
+
+        let sym_assign = self.expect(TokenType::Symbol(Symbol::Assign))?;
+        let value = self.parse_expr()?;
+        let sym_semi = self.expect(TokenType::Symbol(Symbol::Semicolon))?;
+
+        let ast_let = Let {
+            attrs,
+            kw_let,
+            pattern,
+            ty,
+            sym_assign,
+            value,
+            sym_semi,
+        };
+
+        Ok(HAst::Let(self.ctx.alloc(ast_let)))
+    }
+
+    fn parse_assign(
+        &mut self,
+        target: Expr<'input, 'hacx>,
+        sym_assign: Token<'input>,
+    ) -> Result<HAst<'input, 'hacx>> {
+        // `(x) = value` assigns to `x`
+        let mut inner = &target;
+        while let Expr::Paren(paren) = inner {
+            inner = paren.expr;
+        }
+
+        let ident = match *inner {
+            Expr::Identifier(ident) => ident,
+            _ => Err(Error::new(ErrorType::InvalidAssignTarget, target.span()))?,
+        };
+
+        let value = self.parse_expr()?;
+        let sym_semi = self.expect(TokenType::Symbol(Symbol::Semicolon))?;
+
+        let assign = Assign {
+            ident,
+            sym_assign,
+            value,
+            sym_semi,
+        };
+
+        Ok(HAst::Assign(self.ctx.alloc(assign)))
+    }
+
+    fn parse_type_alias(
+        &mut self,
+        attrs: Vec<Attribute<'input>>,
+        kw_type: Token<'input>,
+    ) -> Result<HAst<'input, 'hacx>> {
+        let ident = self.expect(TokenType::Identifier)?;
+        let generics = self.parse_generic_params()?;
+        let sym_assign = self.expect(TokenType::Symbol(Symbol::Assign))?;
+        let ty = self.parse_type()?;
+        let sym_semi = self.expect(TokenType::Symbol(Symbol::Semicolon))?;
+
+        let type_alias = TypeAlias {
+            attrs,
+            kw_type,
+            ident,
+            generics,
+            sym_assign,
+            ty,
+            sym_semi,
+        };
+
+        Ok(HAst::TypeAlias(self.ctx.alloc(type_alias)))
+    }
+
+    fn parse_function(
+        &mut self,
+        attrs: Vec<Attribute<'input>>,
+        kw_fn: Token<'input>,
+    ) -> Result<HAst<'input, 'hacx>> {
+        let ident = self.expect(TokenType::Identifier)?;
+        let generics = self.parse_generic_params()?;
+        let sym_open = self.expect(TokenType::Symbol(Symbol::OpenParen))?;
+        let (params, sym_close) = self.parse_punctuated(Symbol::CloseParen, Self::parse_param)?;
+        let ret = self.parse_return_type()?;
+        let sym_body_open = self.expect(TokenType::Symbol(Symbol::OpenBrace))?;
+        let body = self.parse_block(sym_body_open)?;
+
+        let function = Function {
+            attrs,
+            kw_fn,
+            ident,
+            generics,
+            sym_open,
+            params,
+            sym_close,
+            ret,
+            body,
+        };
+
+        Ok(HAst::Function(self.ctx.alloc(function)))
+    }
+
+    fn parse_param(&mut self) -> Result<Param<'input, 'hacx>> {
+        let ident = self.expect(TokenType::Identifier)?;
+        let sym_colon = self.expect(TokenType::Symbol(Symbol::Colon))?;
+        let ty = self.parse_type()?;
+
+        Ok(Param {
+            ident,
+            sym_colon,
+            ty,
+        })
+    }
+
+    fn parse_struct(
+        &mut self,
+        attrs: Vec<Attribute<'input>>,
+        kw_struct: Token<'input>,
+    ) -> Result<HAst<'input, 'hacx>> {
+        let ident = self.expect(TokenType::Identifier)?;
+        let generics = self.parse_generic_params()?;
+        let fields = self.parse_fields()?;
+
+        let sym_semi = match fields {
+            Fields::Named(_) => None,
+            Fields::Tuple(_) | Fields::Unit => {
+                Some(self.expect(TokenType::Symbol(Symbol::Semicolon))?)
+            }
+        };
+
+        let ast_struct = Struct {
+            attrs,
+            kw_struct,
+            ident,
+            generics,
+            fields,
+            sym_semi,
+        };
+
+        Ok(HAst::Struct(self.ctx.alloc(ast_struct)))
+    }
+
+    fn parse_enum(
+        &mut self,
+        attrs: Vec<Attribute<'input>>,
+        kw_enum: Token<'input>,
+    ) -> Result<HAst<'input, 'hacx>> {
+        let ident = self.expect(TokenType::Identifier)?;
+        let generics = self.parse_generic_params()?;
+        let sym_open = self.expect(TokenType::Symbol(Symbol::OpenBrace))?;
+        let (variants, sym_close) = self.parse_punctuated(Symbol::CloseBrace, |this| {
+            Ok(Variant {
+                ident: this.expect(TokenType::Identifier)?,
+                fields: this.parse_fields()?,
+            })
+        })?;
+
+        let ast_enum = Enum {
+            attrs,
+            kw_enum,
+            ident,
+            generics,
+            sym_open,
+            variants,
+            sym_close,
+        };
+
+        Ok(HAst::Enum(self.ctx.alloc(ast_enum)))
+    }
+
+    fn parse_mod(&mut self, kw_mod: Token<'input>) -> Result<HAst<'input, 'hacx>> {
+        let ident = self.expect(TokenType::Identifier)?;
+        let sym_semi = self.expect(TokenType::Symbol(Symbol::Semicolon))?;
+
+        let ast_mod = Mod {
+            kw_mod,
+            ident,
+            sym_semi,
+        };
+
+        Ok(HAst::Mod(self.ctx.alloc(ast_mod)))
+    }
+
+    fn parse_use(&mut self, kw_use: Token<'input>) -> Result<HAst<'input, 'hacx>> {
+        let first = self.expect(TokenType::Identifier)?;
+        let path = self.parse_plain_path(first)?;
+        let sym_semi = self.expect(TokenType::Symbol(Symbol::Semicolon))?;
+
+        let ast_use = Use {
+            kw_use,
+            path,
+            sym_semi,
+        };
+
+        Ok(HAst::Use(self.ctx.alloc(ast_use)))
+    }
+
+    /// parses the fields of a struct or enum variant, if there are any
+    fn parse_fields(&mut self) -> Result<Fields<'input, 'hacx>> {
+        if let Some(sym_open) = self.eat(TokenType::Symbol(Symbol::OpenBrace))? {
+            let (fields, sym_close) = self.parse_punctuated(Symbol::CloseBrace, |this| {
+                Ok(FieldDef {
+                    ident: this.expect(TokenType::Identifier)?,
+                    sym_colon: this.expect(TokenType::Symbol(Symbol::Colon))?,
+                    ty: this.parse_type()?,
+                })
+            })?;
+
+            Ok(Fields::Named(NamedFields {
+                sym_open,
+                fields,
+                sym_close,
+            }))
+        } else if let Some(sym_open) = self.eat(TokenType::Symbol(Symbol::OpenParen))? {
+            let (fields, sym_close) =
+                self.parse_punctuated(Symbol::CloseParen, Self::parse_type)?;
+
+            Ok(Fields::Tuple(TupleFields {
+                sym_open,
+                fields,
+                sym_close,
+            }))
+        } else {
+            Ok(Fields::Unit)
+        }
+    }
+
+    /// parses the statements of a block after the opening brace
+    pub(crate) fn parse_block(&mut self, sym_open: Token<'input>) -> Result<Block<'input, 'hacx>> {
+        let mut stmts = Vec::new();
+
+        loop {
+            if let Some(sym_close) = self.eat(TokenType::Symbol(Symbol::CloseBrace))? {
+                break Ok(Block {
+                    sym_open,
+                    stmts,
+                    tail: None,
+                    sym_close,
+                });
+            }
+
+            match self.parse_stmt()? {
+                Stmt::Item(ast) => stmts.push(ast),
+                Stmt::Expr(expr) => {
+                    if let Some(sym_close) = self.eat(TokenType::Symbol(Symbol::CloseBrace))? {
+                        break Ok(Block {
+                            sym_open,
+                            stmts,
+                            tail: Some(self.ctx.alloc(expr)),
+                            sym_close,
+                        });
+                    } else if expr.is_block_like() {
+                        stmts.push(self.expr_stmt(expr, None));
+                    } else {
+                        Err(self
+                            .error_here(ErrorType::Expected(TokenType::Symbol(Symbol::Semicolon))))?
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+use super::{context, parse_one};
+
+#[test]
+fn parse_block_stmts() {
+    let ctx = context::LocalContext::default();
+
+    let ast = parse_one("{ let y = 1; (y) = 2; if y { } y }", ctx.as_ref());
+
+    let block = match ast {
+        HAst::Expr(ExprStmt {
+            expr: Expr::Block(block),
+            sym_semi: None,
+        }) => block,
+        ast => panic!("expected a block statement, found {:?}", ast),
+    };
+
+    match block.stmts[..] {
+        [HAst::Let(_), HAst::Assign(_), HAst::Expr(_)] => (),
+        ref stmts => panic!("unexpected statements {:?}", stmts),
+    }
+
+    match block.tail {
+        Some(Expr::Identifier(_)) => (),
+        ref tail => panic!("expected an identifier tail, found {:?}", tail),
+    }
+}
+
+#[test]
+fn parse_function() {
+    let ctx = context::LocalContext::default();
+
+    let ast = parse_one(
+        "fn add<T>(a: T, b: T,) -> T { let c = a; add(c, b) }",
+        ctx.as_ref(),
+    );
+
+    let function = match ast {
+        HAst::Function(function) => function,
+        ast => panic!("expected a function, found {:?}", ast),
+    };
+
+    assert_eq!(
+        function
+            .generics
+            .as_ref()
+            .map(|generics| generics.params.len()),
+        Some(1)
+    );
+    assert_eq!(function.params.len(), 2);
+    assert!(function.params.has_trailing());
+    assert!(function.ret.is_some());
+    assert_eq!(function.body.stmts.len(), 1);
+
+    match function.body.tail {
+        Some(Expr::Call(ref call)) => assert_eq!(call.args.len(), 2),
+        ref tail => panic!("expected a call, found {:?}", tail),
+    }
+}
+
+#[test]
+fn parse_struct_and_enum() {
+    let ctx = context::LocalContext::default();
+    let ctx = ctx.as_ref();
+
+    match parse_one("struct Point { x: i32, y: i32 }", ctx) {
+        HAst::Struct(Struct {
+            fields: Fields::Named(fields),
+            sym_semi: None,
+            ..
+        }) => assert_eq!(fields.fields.len(), 2),
+        ast => panic!("expected a struct with named fields, found {:?}", ast),
+    }
+
+    match parse_one("struct Pair(i32, (u8, u8));", ctx) {
+        HAst::Struct(Struct {
+            fields: Fields::Tuple(fields),
+            sym_semi: Some(_),
+            ..
+        }) => assert_eq!(fields.fields.len(), 2),
+        ast => panic!("expected a tuple struct, found {:?}", ast),
+    }
+
+    let ast_enum = match parse_one(
+        "enum Shape { Empty, Circle(f32), Rect { w: f32, h: f32 }, }",
+        ctx,
+    ) {
+        HAst::Enum(ast_enum) => ast_enum,
+        ast => panic!("expected an enum, found {:?}", ast),
+    };
+
+    let kinds = ast_enum
+        .variants
+        .iter()
+        .map(|variant| match variant.fields {
+            Fields::Unit => "unit",
+            Fields::Tuple(_) => "tuple",
+            Fields::Named(_) => "named",
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(kinds, ["unit", "tuple", "named"]);
+}
+
+#[test]
+fn parse_mod_and_use() {
+    let ctx = context::LocalContext::default();
+    let ctx = ctx.as_ref();
+
+    match parse_one("mod shapes;", ctx) {
+        HAst::Mod(_) => (),
+        ast => panic!("expected a module declaration, found {:?}", ast),
+    }
+
+    match parse_one("use shapes::circle::Circle;", ctx) {
+        HAst::Use(ast_use) => assert_eq!(ast_use.path.segments.len(), 3),
+        ast => panic!("expected a use, found {:?}", ast),
+    }
+}
@@ -0,0 +1,266 @@
+use super::Parser;
+
+use lib_lexer_types::{CodePoint, Keyword, Lexer, Symbol, Token, TokenData, TokenType};
+
+use lib_parser_types::{
+    ErrorType, FunctionType, GenericArgs, GenericParams, NamedType, Result, ReturnType, TupleType,
+    TypeExpr,
+};
+
+impl<'input, 'hacx, L: Lexer<'input>> Parser<'input, 'hacx, L> {
+    pub(crate) fn parse_type(&mut self) -> Result<TypeExpr<'input, 'hacx>> {
+        let token = self.next_token()?;
+
+        match token.map(|token| (token, token.data.tok_type())) {
+            Some((first, TokenType::Identifier)) => {
+                let path = self.parse_plain_path(first)?;
+
+                // `<` after a name in a type is always the start of generic arguments
+                let args = match self.eat(TokenType::Symbol(Symbol::LessThan))? {
+                    Some(sym_open) => Some(self.parse_generic_args(None, sym_open)?),
+                    None => None,
+                };
+
+                Ok(TypeExpr::Named(NamedType { path, args }))
+            }
+            Some((sym_open, TokenType::Symbol(Symbol::OpenParen))) => {
+                let (mut items, sym_close) =
+                    self.parse_punctuated(Symbol::CloseParen, Self::parse_type)?;
+
+                if items.len() == 1 && !items.has_trailing() {
+                    Ok(items.items.pop().unwrap())
+                } else {
+                    Ok(TypeExpr::Tuple(TupleType {
+                        sym_open,
+                        items,
+                        sym_close,
+                    }))
+                }
+            }
+            Some((kw_fn, TokenType::Keyword(Keyword::Fn))) => {
+                let sym_open = self.expect(TokenType::Symbol(Symbol::OpenParen))?;
+                let (params, sym_close) =
+                    self.parse_punctuated(Symbol::CloseParen, Self::parse_type)?;
+                let ret = self.parse_return_type()?;
+
+                Ok(TypeExpr::Function(FunctionType {
+                    kw_fn,
+                    sym_open,
+                    params,
+                    sym_close,
+                    ret,
+                }))
+            }
+            _ => Err(self.error_at(
+                ErrorType::ExpectedOneOf(vec![
+                    TokenType::Identifier,
+                    TokenType::Symbol(Symbol::OpenParen),
+                    TokenType::Keyword(Keyword::Fn),
+                ]),
+                token,
+            ))?,
+        }
+    }
+
+    /// parses generic arguments after the opening `<`
+    pub(crate) fn parse_generic_args(
+        &mut self,
+        sym_path_sep: Option<Token<'input>>,
+        sym_open: Token<'input>,
+    ) -> Result<GenericArgs<'input, 'hacx>> {
+        let (args, sym_close) = self.parse_punctuated(Symbol::GreaterThan, |this| {
+            let ty = this.parse_type()?;
+            this.split_greater_equal()?;
+            Ok(ty)
+        })?;
+
+        Ok(GenericArgs {
+            sym_path_sep,
+            sym_open,
+            args,
+            sym_close,
+        })
+    }
+
+    /// splits a `>=` right after a generic argument into the `>` that closes the
+    /// arguments and a `=`, like in `let x: Vec<u8>= v;`
+    fn split_greater_equal(&mut self) -> Result<()> {
+        if self.peek_type()? != Some(TokenType::Symbol(Symbol::GreaterEqual)) {
+            return Ok(());
+        }
+
+        let token = try_lex!(self.lexer.parse()).expect("the token was peeked");
+        let start = token.span.start();
+        let mid = CodePoint::new_unchecked(start.row(), start.col() + 1);
+
+        // the tokens are taken from the top, so the `=` goes in first
+        self.lexer.push(Token {
+            data: TokenData::Symbol(Symbol::Assign),
+            span: mid.span(token.span.end()),
+        });
+        self.lexer.push(Token {
+            data: TokenData::Symbol(Symbol::GreaterThan),
+            span: start.span(mid),
+        });
+
+        Ok(())
+    }
+
+    /// parses the generic parameters of an item, if there are any
+    pub(crate) fn parse_generic_params(&mut self) -> Result<Option<GenericParams<'input>>> {
+        let sym_open = match self.eat(TokenType::Symbol(Symbol::LessThan))? {
+            Some(sym_open) => sym_open,
+            None => return Ok(None),
+        };
+
+        let (params, sym_close) = self.parse_punctuated(Symbol::GreaterThan, |this| {
+            this.expect(TokenType::Identifier)
+        })?;
+
+        Ok(Some(GenericParams {
+            sym_open,
+            params,
+            sym_close,
+        }))
+    }
+
+    pub(crate) fn parse_return_type(&mut self) -> Result<Option<ReturnType<'input, 'hacx>>> {
+        match self.eat(TokenType::Symbol(Symbol::Arrow))? {
+            Some(sym_arrow) => {
+                let ty = self.parse_type()?;
+
+                Ok(Some(ReturnType {
+                    sym_arrow,
+                    ty: self.ctx.alloc(ty),
+                }))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+use super::{context, parse_one};
+#[cfg(test)]
+use lib_parser_types::{HAst, Let, TypeAlias, TypeAnnotation};
+
+#[test]
+fn parse_type_alias() {
+    let ctx = context::LocalContext::default();
+
+    let ast = parse_one("type F = fn(i32, (u8, bool), (f32)) -> ();", ctx.as_ref());
+
+    let function = match ast {
+        HAst::TypeAlias(TypeAlias {
+            ty: TypeExpr::Function(function),
+            ..
+        }) => function,
+        ast => panic!("expected a function type alias, found {:?}", ast),
+    };
+
+    assert_eq!(function.params.len(), 3);
+
+    match &function.params.items[1] {
+        TypeExpr::Tuple(tuple) => assert_eq!(tuple.items.len(), 2),
+        ty => panic!("expected a tuple type, found {:?}", ty),
+    }
+
+    match function.params.items[2] {
+        TypeExpr::Named(NamedType { args: None, .. }) => (),
+        ref ty => panic!("expected a named type, found {:?}", ty),
+    }
+
+    match function.ret {
+        Some(ReturnType {
+            ty: TypeExpr::Tuple(ref unit),
+            ..
+        }) => assert!(unit.items.is_empty()),
+        ref ret => panic!("expected a unit return type, found {:?}", ret),
+    }
+}
+
+#[test]
+fn parse_let_type_annotation() {
+    let ctx = context::LocalContext::default();
+
+    let ast = parse_one("let x: i32 = 10;", ctx.as_ref());
+
+    match ast {
+        HAst::Let(Let {
+            ty:
+                Some(TypeAnnotation {
+                    ty: TypeExpr::Named(_),
+                    ..
+                }),
+            ..
+        }) => (),
+        ast => panic!("expected an annotated let, found {:?}", ast),
+    }
+}
+
+#[test]
+fn parse_generic_type_alias() {
+    let ctx = context::LocalContext::default();
+
+    let ast = parse_one("type Pair<A, B,> = Map<(A, B), Vec<A>>;", ctx.as_ref());
+
+    let type_alias = match ast {
+        HAst::TypeAlias(type_alias) => type_alias,
+        ast => panic!("expected a type alias, found {:?}", ast),
+    };
+
+    match type_alias.generics {
+        Some(ref generics) => assert_eq!(generics.params.len(), 2),
+        None => panic!("expected generic parameters"),
+    }
+
+    let args = match type_alias.ty {
+        TypeExpr::Named(NamedType {
+            args: Some(ref args),
+            ..
+        }) => args,
+        ref ty => panic!("expected a generic type, found {:?}", ty),
+    };
+
+    assert!(args.sym_path_sep.is_none());
+
+    match args.args.items[..] {
+        [TypeExpr::Tuple(_), TypeExpr::Named(NamedType { args: Some(_), .. })] => (),
+        ref args => panic!("unexpected generic arguments {:?}", args),
+    }
+}
+
+#[test]
+fn parse_glued_closing_angle() {
+    let ctx = context::LocalContext::default();
+    let span =
+        |start, end| CodePoint::new_unchecked(1, start).span(CodePoint::new_unchecked(1, end));
+
+    for (source, close) in [("let x: Vec<u8>= v;", 14), ("let y: Vec<Vec<u8>>= v;", 19)].iter() {
+        let (args, sym_assign) = match parse_one(source, ctx.as_ref()) {
+            HAst::Let(Let {
+                ty:
+                    Some(TypeAnnotation {
+                        ty:
+                            TypeExpr::Named(NamedType {
+                                args: Some(ref args),
+                                ..
+                            }),
+                        ..
+                    }),
+                sym_assign,
+                ..
+            }) => (args.sym_close.span, sym_assign.span),
+            ast => panic!("expected a generic type annotation, found {:?}", ast),
+        };
+
+        assert_eq!(args, span(*close, close + 1));
+        assert_eq!(sym_assign, span(close + 1, close + 2));
+    }
+
+    // `>>` is already two tokens
+    match parse_one("let z: Vec<Vec<u8>> = v;", ctx.as_ref()) {
+        HAst::Let(Let { ty: Some(_), .. }) => (),
+        ast => panic!("expected an annotated let, found {:?}", ast),
+    }
+}
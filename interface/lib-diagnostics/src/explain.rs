@@ -0,0 +1,112 @@
+//! the long-form explanations of error codes
+//!
+//! every code is stable, so docs and bug reports can refer to an error by its
+//! code. The lexer's errors are `E00xx` and the parser's are `E01xx`, and
+//! every later stage gets the next hundred.
+
+/// every error code, with its explanation in markdown
+pub static CODES: &[(&str, &str)] = &[
+    ("E0001", include_str!("explain/E0001.md")),
+    ("E0002", include_str!("explain/E0002.md")),
+    ("E0003", include_str!("explain/E0003.md")),
+    ("E0100", include_str!("explain/E0100.md")),
+    ("E0101", include_str!("explain/E0101.md")),
+    ("E0102", include_str!("explain/E0102.md")),
+    ("E0103", include_str!("explain/E0103.md")),
+    ("E0104", include_str!("explain/E0104.md")),
+];
+
+/// the explanation of `code`, or `None` if there's no such code
+pub fn explain(code: &str) -> Option<&'static str> {
+    CODES
+        .iter()
+        .find(|&&(c, _)| c == code)
+        .map(|&(_, explanation)| explanation)
+}
+
+#[test]
+fn every_variant_has_a_code() {
+    use lib_lexer_types::ErrorType as LexErrorType;
+    use lib_parser_types::ErrorType;
+
+    // adding a variant breaks these matches, as a reminder to add it below
+    let _ = |err: LexErrorType| match err {
+        LexErrorType::UnknownCharacter(_)
+        | LexErrorType::InvalidFloat(_)
+        | LexErrorType::InvalidInt(_) => (),
+    };
+    let _ = |err: ErrorType| match err {
+        ErrorType::LexError
+        | ErrorType::Expected(_)
+        | ErrorType::ExpectedOneOf(_)
+        | ErrorType::InvalidAssignTarget
+        | ErrorType::InvalidAttributeTarget => (),
+    };
+
+    let codes = vec![
+        LexErrorType::UnknownCharacter('$').code(),
+        LexErrorType::InvalidFloat(None).code(),
+        LexErrorType::InvalidInt("".parse::<u128>().unwrap_err()).code(),
+        ErrorType::LexError.code(),
+        ErrorType::Expected(lib_lexer_types::TokenType::Identifier).code(),
+        ErrorType::ExpectedOneOf(Vec::new()).code(),
+        ErrorType::InvalidAssignTarget.code(),
+        ErrorType::InvalidAttributeTarget.code(),
+    ];
+
+    for code in &codes {
+        assert!(explain(code).is_some(), "{} isn't registered", code);
+    }
+
+    let mut unique = codes.clone();
+    unique.sort_unstable();
+    unique.dedup();
+    assert_eq!(unique.len(), codes.len(), "two variants share a code");
+    assert_eq!(codes.len(), CODES.len(), "a registered code isn't used");
+}
+
+#[test]
+fn examples_report_their_code() {
+    let ctx = lib_parser_types::context::LocalContext::default();
+
+    let parse = |text| {
+        let mut parser = lib_parser::Parser::new(lib_lexer::Lexer::new(text), ctx.as_ref());
+
+        loop {
+            match parser.parse() {
+                Ok(Some(_)) => (),
+                Ok(None) => break None,
+                Err(err) => break Some(err),
+            }
+        }
+    };
+
+    for &(code, explanation) in CODES {
+        let mut examples = explanation.split("```text\n").skip(1).map(|block| {
+            let end = block.find("```").expect("every example is closed");
+            &block[..end]
+        });
+
+        let erroneous = examples.next().expect("every code has an example");
+
+        let err = parse(erroneous)
+            .unwrap_or_else(|| panic!("the example of {} doesn't have an error", code));
+        // the code of the parse error or of the lexer error that caused it
+        let codes = std::iter::once(err.err().err.code())
+            .chain(err.cause().map(|cause| cause.err().err.code()))
+            .collect::<Vec<_>>();
+
+        assert!(
+            codes.contains(&code),
+            "the example of {} reports {:?}",
+            code,
+            codes
+        );
+
+        for fixed in examples {
+            if let Some(err) = parse(fixed) {
+                panic!("the fixed example of {} reports {}", code, err);
+            }
+        }
+    }
+}
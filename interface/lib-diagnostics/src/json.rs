@@ -0,0 +1,273 @@
+//! diagnostics as JSON, in the same shape as rustc's `--error-format=json`
+//!
+//! every diagnostic is one object on its own line, with the spans given as
+//! both 1-based lines and character columns, and 0-based byte offsets
+
+use std::io;
+
+use serde::Serialize;
+
+use super::render::{display_col, render};
+use lib_lexer_types::CodePoint;
+
+use super::{explain, Diagnostic, Label, LabelStyle, SourceId, SourceMap, Style, Suggestion};
+
+#[derive(Serialize)]
+struct JsonDiagnostic<'a> {
+    #[serde(rename = "$message_type", skip_serializing_if = "Option::is_none")]
+    message_type: Option<&'static str>,
+    message: &'a str,
+    code: Option<JsonCode<'a>>,
+    level: String,
+    spans: Vec<JsonSpan<'a>>,
+    children: Vec<JsonDiagnostic<'a>>,
+    rendered: Option<String>,
+}
+
+#[derive(Serialize)]
+struct JsonCode<'a> {
+    code: &'a str,
+    explanation: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct JsonSpan<'a> {
+    file_name: &'a str,
+    byte_start: usize,
+    byte_end: usize,
+    line_start: u32,
+    line_end: u32,
+    column_start: usize,
+    column_end: usize,
+    is_primary: bool,
+    text: Vec<JsonLine<'a>>,
+    label: Option<&'a str>,
+    suggested_replacement: Option<String>,
+    suggestion_applicability: Option<&'static str>,
+    expansion: Option<()>,
+}
+
+/// a line of the source under a span
+#[derive(Serialize)]
+struct JsonLine<'a> {
+    text: &'a str,
+    highlight_start: usize,
+    highlight_end: usize,
+}
+
+fn span<'a>(
+    sources: &'a SourceMap,
+    source: SourceId,
+    start: CodePoint,
+    end: CodePoint,
+    is_primary: bool,
+) -> JsonSpan<'a> {
+    let source = sources.get(source);
+
+    let text = (start.row()..=end.row())
+        .filter(|&row| row as usize <= source.line_count())
+        .map(|row| {
+            let line = source.line(row);
+            let from = if row == start.row() {
+                display_col(line, start.col())
+            } else {
+                0
+            };
+            let to = if row == end.row() {
+                display_col(line, end.col())
+            } else {
+                line.chars().count()
+            };
+
+            JsonLine {
+                text: line,
+                highlight_start: from + 1,
+                highlight_end: to + 1,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    JsonSpan {
+        file_name: &source.name,
+        byte_start: source.offset(start),
+        byte_end: source.offset(end),
+        line_start: start.row(),
+        line_end: end.row(),
+        column_start: text.first().map_or(1, |line| line.highlight_start),
+        column_end: text.last().map_or(1, |line| line.highlight_end),
+        is_primary,
+        text,
+        label: None,
+        suggested_replacement: None,
+        suggestion_applicability: None,
+        expansion: None,
+    }
+}
+
+fn label<'a>(label: &'a Label, sources: &'a SourceMap) -> JsonSpan<'a> {
+    let is_primary = label.style == LabelStyle::Primary;
+    let (start, end) = (label.span.start(), label.span.end());
+
+    JsonSpan {
+        label: Some(label.message.as_str()).filter(|message| !message.is_empty()),
+        ..span(sources, label.source, start, end, is_primary)
+    }
+}
+
+/// a suggestion is a `help` child with the replacement in its span, like rustc
+fn suggestion<'a>(suggestion: &'a Suggestion, sources: &'a SourceMap) -> JsonDiagnostic<'a> {
+    let (start, end) = (suggestion.start, suggestion.end);
+
+    let span = JsonSpan {
+        suggested_replacement: Some(suggestion.replacement.clone()),
+        suggestion_applicability: Some(suggestion.applicability.name()),
+        ..span(sources, suggestion.source, start, end, true)
+    };
+
+    JsonDiagnostic {
+        spans: vec![span],
+        ..child("help", &suggestion.message)
+    }
+}
+
+fn child<'a>(level: &str, message: &'a str) -> JsonDiagnostic<'a> {
+    JsonDiagnostic {
+        message_type: None,
+        message,
+        code: None,
+        level: level.to_owned(),
+        spans: Vec::new(),
+        children: Vec::new(),
+        rendered: None,
+    }
+}
+
+/// `diagnostic` as one line of JSON, with the text that `render` prints for it
+/// in `rendered`
+pub fn to_json(diagnostic: &Diagnostic, sources: &SourceMap, style: Style) -> String {
+    let children = diagnostic
+        .notes
+        .iter()
+        .map(|note| child("note", note))
+        .chain(diagnostic.help.iter().map(|help| child("help", help)))
+        .chain(
+            diagnostic
+                .suggestions
+                .iter()
+                .map(|s| suggestion(s, sources)),
+        )
+        .collect();
+
+    let json = JsonDiagnostic {
+        message_type: Some("diagnostic"),
+        message: &diagnostic.message,
+        code: diagnostic.code.map(|code| JsonCode {
+            code,
+            explanation: explain(code),
+        }),
+        level: diagnostic.severity.to_string(),
+        spans: diagnostic
+            .labels
+            .iter()
+            .map(|l| label(l, sources))
+            .collect(),
+        children,
+        rendered: Some(render(diagnostic, sources, style)),
+    };
+
+    serde_json::to_string(&json).expect("diagnostics can always be serialized")
+}
+
+/// writes every diagnostic as JSON, one per line
+pub fn emit_json<'a>(
+    out: &mut dyn io::Write,
+    diagnostics: impl IntoIterator<Item = &'a Diagnostic>,
+    sources: &SourceMap,
+    style: Style,
+) -> io::Result<()> {
+    for diagnostic in diagnostics {
+        writeln!(out, "{}", to_json(diagnostic, sources, style))?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn json_shape() {
+    use super::Applicability;
+
+    let mut sources = SourceMap::new();
+    let main = sources.add("main.lang", "let é = 1\nlet y = 2;\n");
+
+    let diagnostic = Diagnostic::error("expected `;`")
+        .with_code("E0101")
+        .with_label(Label::primary(
+            main,
+            CodePoint::new_unchecked(1, 10).span(CodePoint::new_unchecked(1, 11)),
+            "expected `;`",
+        ))
+        .with_label(Label::secondary(
+            main,
+            CodePoint::new_unchecked(1, 1).span(CodePoint::new_unchecked(2, 4)),
+            "",
+        ))
+        .with_help("statements end with `;`")
+        .with_suggestion(Suggestion::insert(
+            "add `;` here",
+            main,
+            CodePoint::new_unchecked(1, 11),
+            ";",
+            Applicability::MachineApplicable,
+        ));
+
+    let json = to_json(&diagnostic, &sources, Style::Plain);
+    let rendered = serde_json::to_string(&render(&diagnostic, &sources, Style::Plain)).unwrap();
+
+    assert_eq!(
+        json,
+        String::new()
+            + r#"{"$message_type":"diagnostic","message":"expected `;`","#
+            + r#""code":{"code":"E0101","explanation":"#
+            + &serde_json::to_string(explain("E0101").unwrap()).unwrap()
+            + r#"},"level":"error","spans":["#
+            + r#"{"file_name":"main.lang","byte_start":9,"byte_end":10,"line_start":1,"line_end":1,"#
+            + r#""column_start":9,"column_end":10,"is_primary":true,"#
+            + r#""text":[{"text":"let é = 1","highlight_start":9,"highlight_end":10}],"#
+            + r#""label":"expected `;`","suggested_replacement":null,"#
+            + r#""suggestion_applicability":null,"expansion":null},"#
+            + r#"{"file_name":"main.lang","byte_start":0,"byte_end":14,"line_start":1,"line_end":2,"#
+            + r#""column_start":1,"column_end":4,"is_primary":false,"#
+            + r#""text":[{"text":"let é = 1","highlight_start":1,"highlight_end":10},"#
+            + r#"{"text":"let y = 2;","highlight_start":1,"highlight_end":4}],"#
+            + r#""label":null,"suggested_replacement":null,"#
+            + r#""suggestion_applicability":null,"expansion":null}],"#
+            + r#""children":[{"message":"statements end with `;`","code":null,"level":"help","#
+            + r#""spans":[],"children":[],"rendered":null},"#
+            + r#"{"message":"add `;` here","code":null,"level":"help","spans":["#
+            + r#"{"file_name":"main.lang","byte_start":10,"byte_end":10,"line_start":1,"line_end":1,"#
+            + r#""column_start":10,"column_end":10,"is_primary":true,"#
+            + r#""text":[{"text":"let é = 1","highlight_start":10,"highlight_end":10}],"#
+            + r#""label":null,"suggested_replacement":";","#
+            + r#""suggestion_applicability":"MachineApplicable","expansion":null}],"#
+            + r#""children":[],"rendered":null}],"#
+            + r#""rendered":"#
+            + &rendered
+            + "}"
+    );
+
+    let mut out = Vec::new();
+    emit_json(
+        &mut out,
+        &[diagnostic.clone(), diagnostic],
+        &sources,
+        Style::Ansi,
+    )
+    .unwrap();
+    let out = String::from_utf8(out).unwrap();
+
+    assert_eq!(out.lines().count(), 2);
+    for line in out.lines() {
+        let value = serde_json::from_str::<serde_json::Value>(line).unwrap();
+        assert!(value["rendered"].as_str().unwrap().starts_with("\x1b["));
+    }
+}
@@ -0,0 +1,215 @@
+//! diagnostics that point into the source, and a renderer that prints them
+//! like rustc does
+//!
+//! ```text
+//! error: expected `;`
+//!  --> main.lang:1:10
+//!   |
+//! 1 | let x = 1
+//!   |          ^ expected `;`
+//! ```
+//!
+//! `json` writes them in the shape of rustc's `--error-format=json` instead,
+//! for tools that read diagnostics, and `explain` describes every error code.
+//! `apply_fixes` rewrites a source with the suggestions of its diagnostics
+//! that are certain to be right.
+
+use std::fmt;
+
+use lib_lexer_types::{LexError, Span, Symbol, TokenType};
+use lib_parser_types::{ErrorType, ParseError};
+
+pub mod json;
+
+mod explain;
+mod render;
+mod sink;
+mod source;
+mod suggestion;
+pub use explain::{explain, CODES};
+pub use render::{render, Style};
+pub use sink::DiagnosticSink;
+pub use source::{Source, SourceId, SourceMap};
+pub use suggestion::{apply_all_fixes, apply_fixes, Applicability, Fixed, Suggestion};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LabelStyle {
+    /// where the problem is, underlined with `^`
+    Primary,
+    /// context for the problem, underlined with `-`
+    Secondary,
+}
+
+/// a message attached to a span of a source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Label {
+    pub style: LabelStyle,
+    pub source: SourceId,
+    pub span: Span,
+    pub message: String,
+}
+
+impl Label {
+    pub fn primary(source: SourceId, span: Span, message: impl Into<String>) -> Self {
+        Self {
+            style: LabelStyle::Primary,
+            source,
+            span,
+            message: message.into(),
+        }
+    }
+
+    pub fn secondary(source: SourceId, span: Span, message: impl Into<String>) -> Self {
+        Self {
+            style: LabelStyle::Secondary,
+            source,
+            span,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: Option<&'static str>,
+    pub message: String,
+    /// the labels in the order that they were added, the first primary label
+    /// is where the diagnostic is reported
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+    pub help: Vec<String>,
+    pub suggestions: Vec<Suggestion>,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            code: None,
+            message: message.into(),
+            labels: Vec::new(),
+            notes: Vec::new(),
+            help: Vec::new(),
+            suggestions: Vec::new(),
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, message)
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, message)
+    }
+
+    pub fn note(message: impl Into<String>) -> Self {
+        Self::new(Severity::Note, message)
+    }
+
+    pub fn with_code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    pub fn with_label(mut self, label: Label) -> Self {
+        self.labels.push(label);
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help.push(help.into());
+        self
+    }
+
+    pub fn with_suggestion(mut self, suggestion: Suggestion) -> Self {
+        self.suggestions.push(suggestion);
+        self
+    }
+
+    /// the label where the diagnostic is reported
+    pub fn primary(&self) -> Option<&Label> {
+        self.labels
+            .iter()
+            .find(|label| label.style == LabelStyle::Primary)
+    }
+}
+
+/// converts an error into a diagnostic in `source`
+pub trait ToDiagnostic {
+    fn to_diagnostic(&self, source: SourceId) -> Diagnostic;
+}
+
+impl ToDiagnostic for LexError {
+    fn to_diagnostic(&self, source: SourceId) -> Diagnostic {
+        let err = self.err();
+        let message = err.err.to_string();
+
+        Diagnostic::error(message.clone())
+            .with_code(err.err.code())
+            .with_label(Label::primary(source, err.span, message))
+    }
+}
+
+impl ToDiagnostic for ParseError {
+    fn to_diagnostic(&self, source: SourceId) -> Diagnostic {
+        let err = self.err();
+
+        // the lexer knows more about what went wrong
+        if let (ErrorType::LexError, Some(cause)) = (&err.err, self.cause()) {
+            return cause.to_diagnostic(source);
+        }
+
+        let message = err.err.to_string();
+        let label = match err.err {
+            ErrorType::InvalidAssignTarget => "can't assign to this".to_owned(),
+            ErrorType::InvalidAttributeTarget => "attributes aren't allowed here".to_owned(),
+            _ => message.clone(),
+        };
+
+        let diagnostic = Diagnostic::error(message)
+            .with_code(err.err.code())
+            .with_label(Label::primary(source, err.span, label));
+
+        match (&err.err, err.prev_end) {
+            (ErrorType::Expected(TokenType::Symbol(symbol)), Some(prev_end)) => {
+                // a missing `;` is the only missing symbol that is certain
+                let applicability = match symbol {
+                    Symbol::Semicolon => Applicability::MachineApplicable,
+                    _ => Applicability::MaybeIncorrect,
+                };
+
+                diagnostic.with_suggestion(Suggestion::insert(
+                    format!("add `{}` here", symbol.as_str()),
+                    source,
+                    prev_end,
+                    symbol.as_str(),
+                    applicability,
+                ))
+            }
+            _ => diagnostic,
+        }
+    }
+}
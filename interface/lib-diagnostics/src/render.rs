@@ -0,0 +1,474 @@
+use std::fmt::Write as _;
+
+use super::{Diagnostic, Label, LabelStyle, Severity, SourceId, SourceMap, Suggestion};
+
+/// how to render a diagnostic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    Plain,
+    /// colored with ANSI escape codes, for terminals
+    Ansi,
+}
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const GREEN: &str = "\x1b[1;32m";
+const CYAN: &str = "\x1b[1;36m";
+const BLUE: &str = "\x1b[1;34m";
+
+struct Painter {
+    style: Style,
+    out: String,
+}
+
+impl Painter {
+    fn paint(&mut self, color: &str, text: &str) {
+        match self.style {
+            Style::Plain => self.out.push_str(text),
+            Style::Ansi => {
+                self.out.push_str(color);
+                self.out.push_str(text);
+                self.out.push_str(RESET);
+            }
+        }
+    }
+
+    fn gutter(&mut self, width: usize, text: &str) {
+        let gutter = format!("{:width$} {}", "", text, width = width);
+        self.paint(BLUE, &gutter);
+    }
+}
+
+fn color(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => RED,
+        Severity::Warning => YELLOW,
+        Severity::Note => GREEN,
+    }
+}
+
+/// the number of characters before the 1-based byte column `col` of `line`,
+/// counting every byte past the end of the line as one character
+pub(crate) fn display_col(line: &str, col: u32) -> usize {
+    let byte = col as usize - 1;
+    let chars = line.char_indices().take_while(|&(i, _)| i < byte).count();
+
+    chars + byte.saturating_sub(line.len())
+}
+
+/// renders `diagnostic` like rustc does, with a snippet of the source around
+/// every label
+pub fn render(diagnostic: &Diagnostic, sources: &SourceMap, style: Style) -> String {
+    let mut painter = Painter {
+        style,
+        out: String::new(),
+    };
+    let color = color(diagnostic.severity);
+
+    let mut header = diagnostic.severity.to_string();
+    if let Some(code) = diagnostic.code {
+        write!(header, "[{}]", code).unwrap();
+    }
+    painter.paint(color, &header);
+    painter.paint(BOLD, &format!(": {}", diagnostic.message));
+    painter.out.push('\n');
+
+    let width = diagnostic
+        .labels
+        .iter()
+        .map(|label| label.span.end().row())
+        .chain(diagnostic.suggestions.iter().map(|s| s.end.row()))
+        .map(|row| row.to_string().len())
+        .max()
+        .unwrap_or(0);
+
+    // the source of the primary label comes first, then every other source in
+    // the order that their labels were added
+    let mut groups = Vec::<(SourceId, Vec<&Label>)>::new();
+    let primary = diagnostic.primary().into_iter();
+
+    for label in primary.chain(&diagnostic.labels) {
+        match groups
+            .iter_mut()
+            .find(|(source, _)| *source == label.source)
+        {
+            Some((_, labels)) => {
+                if !labels.iter().any(|&l| std::ptr::eq(l, label)) {
+                    labels.push(label)
+                }
+            }
+            None => groups.push((label.source, vec![label])),
+        }
+    }
+
+    for (i, (source, labels)) in groups.iter().enumerate() {
+        render_source(&mut painter, color, width, i == 0, sources, *source, labels);
+    }
+
+    if !diagnostic.notes.is_empty() || !diagnostic.help.is_empty() {
+        if !groups.is_empty() {
+            painter.gutter(width, "|");
+            painter.out.push('\n');
+        }
+
+        let children = diagnostic
+            .notes
+            .iter()
+            .map(|note| ("note", note))
+            .chain(diagnostic.help.iter().map(|help| ("help", help)));
+
+        for (kind, message) in children {
+            painter.gutter(width, "=");
+            painter.paint(BOLD, &format!(" {}:", kind));
+            writeln!(painter.out, " {}", message).unwrap();
+        }
+    }
+
+    for suggestion in &diagnostic.suggestions {
+        render_suggestion(&mut painter, width, sources, suggestion);
+    }
+
+    painter.out
+}
+
+/// shows the line that a suggestion changes, as it is after the change
+fn render_suggestion(
+    painter: &mut Painter,
+    width: usize,
+    sources: &SourceMap,
+    suggestion: &Suggestion,
+) {
+    painter.paint(CYAN, "help");
+    painter.paint(BOLD, &format!(": {}", suggestion.message));
+    painter.out.push('\n');
+
+    let (start, end) = (suggestion.start, suggestion.end);
+
+    // only changes inside of a line are shown
+    if start.row() != end.row() || suggestion.replacement.contains('\n') {
+        return;
+    }
+
+    let line = sources.get(suggestion.source).line(start.row());
+    let byte = |col: u32| (col as usize - 1).min(line.len());
+    let from = display_col(line, start.col());
+
+    // a removal underlines the removed text in the old line, anything else
+    // underlines the replacement in the new line
+    let (shown, (mark, color), marks) = if suggestion.replacement.is_empty() {
+        let to = display_col(line, end.col());
+        (line.to_owned(), ('-', RED), to.saturating_sub(from))
+    } else {
+        let shown = format!(
+            "{}{}{}",
+            &line[..byte(start.col())],
+            suggestion.replacement,
+            &line[byte(end.col())..]
+        );
+        let mark = if start == end {
+            ('+', GREEN)
+        } else {
+            ('~', YELLOW)
+        };
+        (shown, mark, suggestion.replacement.chars().count())
+    };
+
+    painter.gutter(width, "|");
+    painter.out.push('\n');
+    painter.paint(BLUE, &format!("{:>width$} |", start.row(), width = width));
+    writeln!(painter.out, " {}", shown).unwrap();
+    painter.gutter(width, "|");
+    write!(painter.out, " {:from$}", "", from = from).unwrap();
+    painter.paint(color, &mark.to_string().repeat(marks.max(1)));
+    painter.out.push('\n');
+}
+
+fn render_source(
+    painter: &mut Painter,
+    color: &str,
+    width: usize,
+    first: bool,
+    sources: &SourceMap,
+    id: SourceId,
+    labels: &[&Label],
+) {
+    let source = sources.get(id);
+    let start = labels[0].span.start();
+
+    let arrow = if first {
+        "-->"
+    } else {
+        painter.gutter(width, "|");
+        painter.out.push('\n');
+        ":::"
+    };
+    painter.paint(BLUE, &format!("{:width$}{} ", "", arrow, width = width));
+    writeln!(painter.out, "{}:{}", source.name, start).unwrap();
+    painter.gutter(width, "|");
+    painter.out.push('\n');
+
+    let mut rows = labels
+        .iter()
+        .flat_map(|label| label.span.start().row()..=label.span.end().row())
+        .filter(|&row| row as usize <= source.line_count())
+        .collect::<Vec<_>>();
+    rows.sort_unstable();
+    rows.dedup();
+
+    let mut labels = labels.to_vec();
+    labels.sort_by_key(|label| (label.span.start(), label.span.end()));
+
+    let mut last = None;
+
+    for row in rows {
+        if matches!(last, Some(last) if row > last + 1) {
+            painter.paint(BLUE, "...");
+            painter.out.push('\n');
+        }
+        last = Some(row);
+
+        let line = source.line(row);
+        painter.paint(BLUE, &format!("{:>width$} |", row, width = width));
+        if line.is_empty() {
+            painter.out.push('\n');
+        } else {
+            writeln!(painter.out, " {}", line).unwrap();
+        }
+
+        for label in &labels {
+            let (start, end) = (label.span.start(), label.span.end());
+
+            if row < start.row() || end.row() < row {
+                continue;
+            }
+
+            // lines inside of a span are underlined from their first
+            // character, and up to their end
+            let from = if row == start.row() {
+                display_col(line, start.col())
+            } else {
+                line.chars().take_while(|c| c.is_whitespace()).count()
+            };
+            let to = if row == end.row() {
+                display_col(line, end.col())
+            } else {
+                line.chars().count()
+            };
+
+            let (mark, mark_color) = match label.style {
+                LabelStyle::Primary => ('^', color),
+                LabelStyle::Secondary => ('-', BLUE),
+            };
+            let marks = mark.to_string().repeat(to.saturating_sub(from).max(1));
+
+            painter.gutter(width, "|");
+            write!(painter.out, " {:from$}", "", from = from).unwrap();
+
+            if row == end.row() && !label.message.is_empty() {
+                painter.paint(mark_color, &format!("{} {}", marks, label.message));
+            } else {
+                painter.paint(mark_color, &marks);
+            }
+
+            painter.out.push('\n');
+        }
+    }
+}
+
+#[cfg(test)]
+use super::ToDiagnostic;
+
+/// adds `text` to `sources` as `name`, and converts the first parse error in
+/// it to a diagnostic
+#[cfg(test)]
+fn parse_error(sources: &mut SourceMap, name: &str, text: &str) -> Diagnostic {
+    let ctx = lib_parser_types::context::LocalContext::default();
+    let id = sources.add(name, text);
+    let mut parser = lib_parser::Parser::new(lib_lexer::Lexer::new(text), ctx.as_ref());
+
+    loop {
+        match parser.parse() {
+            Ok(Some(_)) => (),
+            Ok(None) => panic!("expected an error in {:?}", text),
+            Err(err) => break err.to_diagnostic(id),
+        }
+    }
+}
+
+#[test]
+fn render_parse_errors() {
+    let mut sources = SourceMap::new();
+    let missing_semi = parse_error(&mut sources, "main.lang", "let x = 1");
+    let unknown_char = parse_error(&mut sources, "lexer.lang", "let a = 0;\nlet $ = 1;");
+
+    assert_eq!(
+        render(&missing_semi, &sources, Style::Plain),
+        "\
+error[E0101]: expected `;`
+ --> main.lang:1:10
+  |
+1 | let x = 1
+  |          ^ expected `;`
+help: add `;` here
+  |
+1 | let x = 1;
+  |          +
+"
+    );
+
+    assert_eq!(
+        render(&unknown_char, &sources, Style::Plain),
+        "\
+error[E0001]: unknown character '$'
+ --> lexer.lang:2:5
+  |
+2 | let $ = 1;
+  |     ^ unknown character '$'
+"
+    );
+}
+
+#[test]
+fn render_labels_and_children() {
+    use lib_lexer_types::CodePoint;
+
+    let span = |row, start, end| {
+        CodePoint::new_unchecked(row, start).span(CodePoint::new_unchecked(row, end))
+    };
+
+    let mut sources = SourceMap::new();
+    let main = sources.add(
+        "main.lang",
+        "fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n\n\n\n\n\n\nlet x = add(1);\n",
+    );
+    let lib = sources.add("lib.lang", "let y = 2;\n");
+
+    let diagnostic =
+        Diagnostic::error("this function takes 2 arguments but 1 argument was supplied")
+            .with_code("E0061")
+            .with_label(Label::primary(
+                main,
+                span(10, 9, 12),
+                "expected 2 arguments",
+            ))
+            .with_label(Label::secondary(main, span(10, 13, 14), "1 argument"))
+            .with_label(Label::secondary(main, span(1, 4, 7), "defined here"))
+            .with_label(Label::secondary(lib, span(1, 5, 6), "unrelated"))
+            .with_note("arguments are passed by position")
+            .with_help("add another argument");
+
+    assert_eq!(
+        render(&diagnostic, &sources, Style::Plain),
+        "\
+error[E0061]: this function takes 2 arguments but 1 argument was supplied
+  --> main.lang:10:9
+   |
+ 1 | fn add(a: i32, b: i32) -> i32 {
+   |    --- defined here
+...
+10 | let x = add(1);
+   |         ^^^ expected 2 arguments
+   |             - 1 argument
+   |
+  ::: lib.lang:1:5
+   |
+ 1 | let y = 2;
+   |     - unrelated
+   |
+   = note: arguments are passed by position
+   = help: add another argument
+"
+    );
+}
+
+#[test]
+fn render_multiline_and_ansi() {
+    use lib_lexer_types::CodePoint;
+
+    let mut sources = SourceMap::new();
+    let main = sources.add("main.lang", "fn main() {\n    let x = 1;\n}\n");
+
+    let diagnostic = Diagnostic::warning("unused function").with_label(Label::primary(
+        main,
+        CodePoint::new_unchecked(1, 1).span(CodePoint::new_unchecked(3, 2)),
+        "never called",
+    ));
+
+    assert_eq!(
+        render(&diagnostic, &sources, Style::Plain),
+        "\
+warning: unused function
+ --> main.lang:1:1
+  |
+1 | fn main() {
+  | ^^^^^^^^^^^
+2 |     let x = 1;
+  |     ^^^^^^^^^^
+3 | }
+  | ^ never called
+"
+    );
+
+    let ansi = render(&diagnostic, &sources, Style::Ansi);
+    assert!(ansi.starts_with("\x1b[1;33mwarning\x1b[0m\x1b[1m: unused function\x1b[0m\n"));
+    assert!(ansi.contains("\x1b[1;34m  |\x1b[0m \x1b[1;33m^ never called\x1b[0m\n"));
+}
+
+#[test]
+fn render_non_ascii_indentation() {
+    use super::{apply_fixes, Applicability};
+    use lib_lexer_types::{Keyword, TokenData};
+
+    // the indentation is two no-break spaces, which are two bytes each
+    let text = "fn f() {\n\u{a0}\u{a0}let mut x = 1;\n}";
+    let mut sources = SourceMap::new();
+    let main = sources.add("main.lang", text);
+
+    let mut lexer = lib_lexer::Lexer::new(text);
+    let mut tokens = std::iter::from_fn(|| lexer.parse().unwrap());
+    let kw_mut = tokens
+        .find(|token| token.data == TokenData::Keyword(Keyword::Mut))
+        .unwrap();
+    let x = tokens.next().unwrap();
+
+    let diagnostic = Diagnostic::warning("variable does not need to be mutable")
+        .with_label(Label::primary(main, kw_mut.span.merge(x.span), ""))
+        .with_suggestion(Suggestion::remove(
+            "remove this `mut`",
+            main,
+            kw_mut.span.start(),
+            x.span.start(),
+            Applicability::MachineApplicable,
+        ))
+        .with_suggestion(Suggestion::replace(
+            "prefix it with an underscore",
+            main,
+            x.span,
+            "_x",
+            Applicability::MaybeIncorrect,
+        ));
+
+    assert_eq!(
+        render(&diagnostic, &sources, Style::Plain),
+        "\
+warning: variable does not need to be mutable
+ --> main.lang:2:9
+  |
+2 | \u{a0}\u{a0}let mut x = 1;
+  |       ^^^^^
+help: remove this `mut`
+  |
+2 | \u{a0}\u{a0}let mut x = 1;
+  |       ----
+help: prefix it with an underscore
+  |
+2 | \u{a0}\u{a0}let mut _x = 1;
+  |           ~~
+"
+    );
+
+    let fixed = apply_fixes(sources.get(main), main, Some(&diagnostic));
+    assert_eq!(fixed.text, "fn f() {\n\u{a0}\u{a0}let x = 1;\n}");
+}
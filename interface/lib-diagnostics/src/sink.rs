@@ -0,0 +1,106 @@
+use super::{Diagnostic, Severity};
+
+/// collects the diagnostics of a run, and decides how the run ends
+#[derive(Debug, Default)]
+pub struct DiagnosticSink {
+    diagnostics: Vec<Diagnostic>,
+    errors: usize,
+    warnings: usize,
+}
+
+impl DiagnosticSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn emit(&mut self, diagnostic: Diagnostic) {
+        match diagnostic.severity {
+            Severity::Error => self.errors += 1,
+            Severity::Warning => self.warnings += 1,
+            Severity::Note => (),
+        }
+
+        self.diagnostics.push(diagnostic);
+    }
+
+    pub fn errors(&self) -> usize {
+        self.errors
+    }
+
+    pub fn warnings(&self) -> usize {
+        self.warnings
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.errors != 0
+    }
+
+    /// every diagnostic, in the order that they were emitted
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    pub fn into_diagnostics(self) -> Vec<Diagnostic> {
+        self.diagnostics
+    }
+
+    /// the exit status of the run, which only fails if there were errors
+    pub fn exit_code(&self) -> i32 {
+        if self.has_errors() {
+            1
+        } else {
+            0
+        }
+    }
+
+    /// the line that ends the output, like rustc's
+    /// `error: aborting due to 2 previous errors; 1 warning emitted`
+    pub fn summary(&self) -> Option<Diagnostic> {
+        let plural = |count: usize, what: &str| {
+            format!("{} {}{}", count, what, if count == 1 { "" } else { "s" })
+        };
+
+        match (self.errors, self.warnings) {
+            (0, 0) => None,
+            (0, warnings) => Some(Diagnostic::warning(format!(
+                "{} emitted",
+                plural(warnings, "warning")
+            ))),
+            (errors, 0) => Some(Diagnostic::error(format!(
+                "aborting due to {}",
+                plural(errors, "previous error")
+            ))),
+            (errors, warnings) => Some(Diagnostic::error(format!(
+                "aborting due to {}; {} emitted",
+                plural(errors, "previous error"),
+                plural(warnings, "warning")
+            ))),
+        }
+    }
+}
+
+#[test]
+fn counts_and_exit_code() {
+    let mut sink = DiagnosticSink::new();
+    assert_eq!(sink.exit_code(), 0);
+    assert_eq!(sink.summary(), None);
+
+    sink.emit(Diagnostic::warning("unused variable: `x`"));
+    sink.emit(Diagnostic::note("just so you know"));
+    assert_eq!((sink.errors(), sink.warnings()), (0, 1));
+    assert_eq!(sink.exit_code(), 0);
+    assert_eq!(
+        sink.summary().unwrap().message,
+        "1 warning emitted".to_owned()
+    );
+
+    sink.emit(Diagnostic::error("expected `;`"));
+    sink.emit(Diagnostic::error("expected `)`"));
+    assert_eq!((sink.errors(), sink.warnings()), (2, 1));
+    assert_eq!(sink.exit_code(), 1);
+    assert_eq!(
+        sink.summary().unwrap().message,
+        "aborting due to 2 previous errors; 1 warning emitted".to_owned()
+    );
+    assert_eq!(sink.diagnostics().len(), 4);
+}
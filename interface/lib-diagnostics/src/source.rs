@@ -0,0 +1,77 @@
+use lib_lexer_types::CodePoint;
+
+/// the index of a source in a `SourceMap`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SourceId(u32);
+
+impl SourceId {
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// the text of a file, and the name to show for it in diagnostics
+#[derive(Debug)]
+pub struct Source {
+    pub name: String,
+    pub text: String,
+    /// the byte offset of the start of every line
+    lines: Vec<usize>,
+}
+
+impl Source {
+    pub fn new(name: impl Into<String>, text: impl Into<String>) -> Self {
+        let text = text.into();
+        let lines = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        Self {
+            name: name.into(),
+            text,
+            lines,
+        }
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// the text of the 1-based line `row`, without the line ending
+    pub fn line(&self, row: u32) -> &str {
+        let row = row as usize;
+        let start = self.lines[row - 1];
+        let end = self.lines.get(row).copied().unwrap_or(self.text.len());
+
+        self.text[start..end].trim_end_matches(&['\n', '\r'][..])
+    }
+
+    /// the byte offset of `point`, clamped to the end of the text
+    pub fn offset(&self, point: CodePoint) -> usize {
+        let start = self.lines[point.row() as usize - 1];
+
+        (start + point.col() as usize - 1).min(self.text.len())
+    }
+}
+
+/// every source that a diagnostic may point into
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    sources: Vec<Source>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, name: impl Into<String>, text: impl Into<String>) -> SourceId {
+        let id = SourceId(self.sources.len() as u32);
+        self.sources.push(Source::new(name, text));
+        id
+    }
+
+    pub fn get(&self, id: SourceId) -> &Source {
+        &self.sources[id.index()]
+    }
+}
@@ -0,0 +1,288 @@
+use lib_lexer_types::{CodePoint, Span};
+
+use super::{Diagnostic, Source, SourceId, SourceMap};
+
+/// how sure a suggestion is that it fixes the code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Applicability {
+    /// the suggestion is definitely what the user meant, so it may be applied
+    /// without asking
+    MachineApplicable,
+    /// the suggestion may not be what the user meant
+    MaybeIncorrect,
+    /// the suggestion has placeholders like `(...)` that the user has to fill in
+    HasPlaceholders,
+    Unspecified,
+}
+
+impl Applicability {
+    pub fn name(self) -> &'static str {
+        match self {
+            Applicability::MachineApplicable => "MachineApplicable",
+            Applicability::MaybeIncorrect => "MaybeIncorrect",
+            Applicability::HasPlaceholders => "HasPlaceholders",
+            Applicability::Unspecified => "Unspecified",
+        }
+    }
+}
+
+/// a change to the source that fixes a diagnostic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    pub message: String,
+    pub source: SourceId,
+    /// the text from `start` up to `end` is replaced, an insertion starts and
+    /// ends at the same point
+    pub start: CodePoint,
+    pub end: CodePoint,
+    pub replacement: String,
+    pub applicability: Applicability,
+}
+
+impl Suggestion {
+    pub fn replace(
+        message: impl Into<String>,
+        source: SourceId,
+        span: Span,
+        replacement: impl Into<String>,
+        applicability: Applicability,
+    ) -> Self {
+        Self {
+            message: message.into(),
+            source,
+            start: span.start(),
+            end: span.end(),
+            replacement: replacement.into(),
+            applicability,
+        }
+    }
+
+    pub fn insert(
+        message: impl Into<String>,
+        source: SourceId,
+        at: CodePoint,
+        text: impl Into<String>,
+        applicability: Applicability,
+    ) -> Self {
+        Self {
+            message: message.into(),
+            source,
+            start: at,
+            end: at,
+            replacement: text.into(),
+            applicability,
+        }
+    }
+
+    /// removes the text from `start` up to `end`
+    pub fn remove(
+        message: impl Into<String>,
+        source: SourceId,
+        start: CodePoint,
+        end: CodePoint,
+        applicability: Applicability,
+    ) -> Self {
+        Self {
+            message: message.into(),
+            source,
+            start,
+            end,
+            replacement: String::new(),
+            applicability,
+        }
+    }
+}
+
+/// the text of a source after applying suggestions to it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fixed {
+    pub text: String,
+    pub applied: usize,
+    /// the suggestions that overlap a suggestion that was applied, which may
+    /// apply cleanly after checking the fixed text again
+    pub skipped: usize,
+}
+
+/// applies every machine-applicable suggestion for `id` in `diagnostics` to
+/// `source`
+///
+/// the suggestions are applied from the start of the text to the end, and a
+/// suggestion that overlaps one that was already applied is skipped. The same
+/// suggestion made by two diagnostics is only applied once.
+pub fn apply_fixes<'a>(
+    source: &Source,
+    id: SourceId,
+    diagnostics: impl IntoIterator<Item = &'a Diagnostic>,
+) -> Fixed {
+    let mut edits = diagnostics
+        .into_iter()
+        .flat_map(|diagnostic| &diagnostic.suggestions)
+        .filter(|suggestion| {
+            suggestion.source == id && suggestion.applicability == Applicability::MachineApplicable
+        })
+        .map(|suggestion| {
+            let range = source.offset(suggestion.start)..source.offset(suggestion.end);
+            (range, suggestion.replacement.as_str())
+        })
+        .collect::<Vec<_>>();
+
+    // stable, so that of two insertions at the same place the first one wins
+    edits.sort_by_key(|(range, _)| (range.start, range.end));
+
+    let mut fixed = Fixed {
+        text: String::with_capacity(source.text.len()),
+        applied: 0,
+        skipped: 0,
+    };
+    let mut last: Option<(std::ops::Range<usize>, &str)> = None;
+
+    for (range, replacement) in edits {
+        if let Some((last, last_replacement)) = &last {
+            if *last == range && *last_replacement == replacement {
+                continue;
+            }
+
+            let both_insert = last.is_empty() && range.is_empty();
+
+            if range.start < last.end || (both_insert && range.start == last.start) {
+                fixed.skipped += 1;
+                continue;
+            }
+        }
+
+        let copied = last.as_ref().map_or(0, |(last, _)| last.end);
+        fixed.text.push_str(&source.text[copied..range.start]);
+        fixed.text.push_str(replacement);
+        fixed.applied += 1;
+
+        last = Some((range, replacement));
+    }
+
+    let copied = last.as_ref().map_or(0, |(last, _)| last.end);
+    fixed.text.push_str(&source.text[copied..]);
+
+    fixed
+}
+
+/// applies the machine-applicable suggestions of `diagnostics` to every
+/// source that they change, for a `--fix` mode
+pub fn apply_all_fixes(sources: &SourceMap, diagnostics: &[Diagnostic]) -> Vec<(SourceId, Fixed)> {
+    let mut ids = diagnostics
+        .iter()
+        .flat_map(|diagnostic| &diagnostic.suggestions)
+        .filter(|suggestion| suggestion.applicability == Applicability::MachineApplicable)
+        .map(|suggestion| suggestion.source)
+        .collect::<Vec<_>>();
+    ids.sort_unstable();
+    ids.dedup();
+
+    ids.into_iter()
+        .map(|id| (id, apply_fixes(sources.get(id), id, diagnostics)))
+        .collect()
+}
+
+#[test]
+fn overlapping_fixes() {
+    let point = CodePoint::new_unchecked;
+
+    let mut sources = SourceMap::new();
+    let main = sources.add("main.lang", "let mut x = 1\nlet y = x\n");
+    let other = sources.add("other.lang", "let mut z = 0;\n");
+
+    let fix = |start, end, text: &str| Suggestion {
+        message: String::new(),
+        source: main,
+        start,
+        end,
+        replacement: text.to_owned(),
+        applicability: Applicability::MachineApplicable,
+    };
+
+    let diagnostics = [
+        Diagnostic::error("expected `;`")
+            .with_suggestion(fix(point(1, 14), point(1, 14), ";"))
+            // made twice, but only applied once
+            .with_suggestion(fix(point(1, 14), point(1, 14), ";")),
+        Diagnostic::warning("variable does not need to be mutable")
+            .with_suggestion(fix(point(1, 5), point(1, 9), ""))
+            // overlaps the removal of `mut `
+            .with_suggestion(fix(point(1, 8), point(1, 10), "_x")),
+        Diagnostic::error("expected `;`")
+            .with_suggestion(fix(point(2, 10), point(2, 10), ";"))
+            // a different insertion at the same place
+            .with_suggestion(fix(point(2, 10), point(2, 10), ","))
+            // not sure enough to apply
+            .with_suggestion(Suggestion::insert(
+                "",
+                main,
+                point(2, 1),
+                "pub ",
+                Applicability::MaybeIncorrect,
+            )),
+        Diagnostic::warning("variable does not need to be mutable").with_suggestion(
+            Suggestion::remove(
+                "remove this `mut`",
+                other,
+                point(1, 5),
+                point(1, 9),
+                Applicability::MachineApplicable,
+            ),
+        ),
+    ];
+
+    let fixed = apply_all_fixes(&sources, &diagnostics);
+
+    assert_eq!(
+        fixed,
+        [
+            (
+                main,
+                Fixed {
+                    text: "let x = 1;\nlet y = x;\n".to_owned(),
+                    applied: 3,
+                    skipped: 2,
+                }
+            ),
+            (
+                other,
+                Fixed {
+                    text: "let z = 0;\n".to_owned(),
+                    applied: 1,
+                    skipped: 0,
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn fix_missing_semicolon() {
+    use super::ToDiagnostic;
+
+    let text = "let x = 1\nlet y = (x + 2\n";
+    let ctx = lib_parser_types::context::LocalContext::default();
+    let mut sources = SourceMap::new();
+    let main = sources.add("main.lang", text);
+
+    let mut parser = lib_parser::Parser::new(lib_lexer::Lexer::new(text), ctx.as_ref());
+    let diagnostic = parser.parse().unwrap_err().to_diagnostic(main);
+    let fixed = apply_fixes(sources.get(main), main, Some(&diagnostic));
+    assert_eq!(fixed.text, "let x = 1;\nlet y = (x + 2\n");
+
+    // a missing `)` may belong in many places, so it isn't fixed
+    let fixed = sources.add("fixed.lang", fixed.text);
+    let text = &sources.get(fixed).text;
+    let mut parser = lib_parser::Parser::new(lib_lexer::Lexer::new(text), ctx.as_ref());
+    parser.parse().unwrap();
+    let diagnostic = parser.parse().unwrap_err().to_diagnostic(fixed);
+
+    assert_eq!(diagnostic.message, "expected `)`");
+    assert_eq!(
+        diagnostic.suggestions[0].applicability,
+        Applicability::MaybeIncorrect
+    );
+    assert_eq!(
+        apply_fixes(sources.get(fixed), fixed, Some(&diagnostic)).applied,
+        0
+    );
+}
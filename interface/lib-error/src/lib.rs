@@ -1,5 +1,9 @@
 use std::fmt;
 
+mod report;
+
+pub use report::{IntoReport, Report};
+
 pub trait WithContext<E> {
     type Output;
 
@@ -44,13 +48,13 @@ pub enum Initial {}
 
 impl<E, P> From<E> for Error<E, P> {
     fn from(err: E) -> Self {
-        Self { err, cause: None }
+        Self::new(err)
     }
 }
 
 impl<E> Error<E, Initial> {
     pub fn initial(err: E) -> Self {
-        Self { err, cause: None }
+        Self::new(err)
     }
 }
 
@@ -66,6 +70,15 @@ impl<E, P> Error<E, P> {
         }
     }
 
+    /// adds a layer that describes what was being done rather than what went
+    /// wrong, like `"while parsing let"`
+    pub fn context<M>(self, message: M) -> Context<M, Self> {
+        Context {
+            message,
+            cause: self,
+        }
+    }
+
     pub fn err(&self) -> &E {
         &self.err
     }
@@ -73,10 +86,321 @@ impl<E, P> Error<E, P> {
     pub fn cause(&self) -> Option<&P> {
         self.cause.as_ref()
     }
+
+    /// every layer of this error, from the outermost to the innermost
+    pub fn chain(&self) -> Chain<'_>
+    where
+        Self: ErrorChain,
+    {
+        Chain { next: Some(self) }
+    }
+
+    /// the innermost layer of this error
+    pub fn root_cause(&self) -> &dyn Frame
+    where
+        Self: ErrorChain,
+    {
+        self.chain()
+            .last()
+            .expect("an error has at least one layer")
+    }
+}
+
+/// one layer of an error chain, without its causes
+pub trait Frame: fmt::Display + fmt::Debug {}
+
+impl<T: fmt::Display + fmt::Debug + ?Sized> Frame for T {}
+
+/// an error chain that can be walked without knowing the types of its layers
+pub trait ErrorChain {
+    fn frame(&self) -> &dyn Frame;
+
+    fn next(&self) -> Option<&dyn ErrorChain>;
+}
+
+impl<E: Frame, P: ErrorChain> ErrorChain for Error<E, P> {
+    fn frame(&self) -> &dyn Frame {
+        &self.err
+    }
+
+    fn next(&self) -> Option<&dyn ErrorChain> {
+        self.cause.as_ref().map(|cause| cause as &dyn ErrorChain)
+    }
+}
+
+impl ErrorChain for Initial {
+    fn frame(&self) -> &dyn Frame {
+        match *self {}
+    }
+
+    fn next(&self) -> Option<&dyn ErrorChain> {
+        match *self {}
+    }
+}
+
+/// a layer of an error chain that describes what was being done when its
+/// cause happened, which is joined to the cause with `: ` instead of
+/// `, caused by: `
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Context<M, P> {
+    message: M,
+    cause: P,
+}
+
+impl<M, P> Context<M, P> {
+    pub fn message(&self) -> &M {
+        &self.message
+    }
+
+    pub fn cause(&self) -> &P {
+        &self.cause
+    }
+
+    /// adds another message, like `Error::context`
+    pub fn context<N>(self, message: N) -> Context<N, Self> {
+        Context {
+            message,
+            cause: self,
+        }
+    }
+
+    /// every layer of this error, from the outermost to the innermost
+    pub fn chain(&self) -> Chain<'_>
+    where
+        Self: ErrorChain,
+    {
+        Chain { next: Some(self) }
+    }
+
+    /// the innermost layer of this error
+    pub fn root_cause(&self) -> &dyn Frame
+    where
+        Self: ErrorChain,
+    {
+        self.chain()
+            .last()
+            .expect("an error has at least one layer")
+    }
+}
+
+impl<N, M, P> WithContext<N> for Context<M, P> {
+    type Output = Error<N, Self>;
+
+    #[inline]
+    fn with_context(self, err: N) -> Self::Output {
+        Error {
+            err,
+            cause: Some(self),
+        }
+    }
+}
+
+impl<M: Frame, P: ErrorChain> ErrorChain for Context<M, P> {
+    fn frame(&self) -> &dyn Frame {
+        &self.message
+    }
+
+    fn next(&self) -> Option<&dyn ErrorChain> {
+        Some(&self.cause)
+    }
+}
+
+/// the layers of an error, from the outermost to the innermost
+pub struct Chain<'a> {
+    next: Option<&'a dyn ErrorChain>,
+}
+
+impl<'a> Iterator for Chain<'a> {
+    type Item = &'a dyn Frame;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let layer = self.next?;
+        self.next = layer.next();
+        Some(layer.frame())
+    }
 }
 
-impl<E: fmt::Display> fmt::Display for Error<E, Initial> {
+/// `Initial` is never constructed, this is only so that the first error in a
+/// chain can be displayed the same way as the rest
+impl fmt::Display for Initial {
+    fn fmt(&self, _: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {}
+    }
+}
+
+impl std::error::Error for Initial {}
+
+/// the text between a layer and its cause
+fn separator(context: bool) -> &'static str {
+    if context {
+        ": "
+    } else {
+        ", caused by: "
+    }
+}
+
+/// writes the outermost layer, and with `{:#}` each of its causes too, so
+/// `"while parsing let"` around a parse error caused by a lexer error is
+/// `while parsing let: expected identifier, caused by: unknown character '$'`
+impl<E: fmt::Display, P: fmt::Display> fmt::Display for Error<E, P> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "")
+        write!(f, "{}", self.err)?;
+
+        match self.cause {
+            Some(ref cause) if f.alternate() => write!(f, "{}{:#}", separator(false), cause),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// writes the message, and with `{:#}` each of its causes too, like `Error`
+impl<M: fmt::Display, P: fmt::Display> fmt::Display for Context<M, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+
+        if f.alternate() {
+            write!(f, "{}{:#}", separator(true), self.cause)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// only `source` walks the causes, since `Display` only writes the outermost
+/// layer
+impl<E, P> std::error::Error for Error<E, P>
+where
+    E: fmt::Debug + fmt::Display,
+    P: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.cause
+            .as_ref()
+            .map(|cause| cause as &(dyn std::error::Error + 'static))
+    }
+}
+
+impl<M, P> std::error::Error for Context<M, P>
+where
+    M: fmt::Debug + fmt::Display,
+    P: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.cause)
     }
 }
+
+#[cfg(test)]
+#[derive(Debug, PartialEq)]
+enum TestError {
+    Parse,
+    Lex(char),
+}
+
+#[cfg(test)]
+impl fmt::Display for TestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TestError::Parse => f.write_str("expected identifier"),
+            TestError::Lex(c) => write!(f, "unknown character {:?}", c),
+        }
+    }
+}
+
+#[test]
+fn display_chain() {
+    let lex = Error::initial(TestError::Lex('$'));
+    assert_eq!(lex.to_string(), "unknown character '$'");
+    assert_eq!(format!("{:#}", lex), "unknown character '$'");
+
+    let parse = lex.with_context(TestError::Parse);
+    assert_eq!(parse.to_string(), "expected identifier");
+    assert_eq!(
+        format!("{:#}", parse),
+        "expected identifier, caused by: unknown character '$'"
+    );
+
+    let parse = Error::<_, Error<TestError>>::new(TestError::Parse);
+    assert_eq!(format!("{:#}", parse), "expected identifier");
+}
+
+#[test]
+fn display_context() {
+    let err = Error::initial(TestError::Lex('$'))
+        .with_context(TestError::Parse)
+        .context("while parsing let");
+    assert_eq!(err.to_string(), "while parsing let");
+    assert_eq!(
+        format!("{:#}", err),
+        "while parsing let: expected identifier, caused by: unknown character '$'"
+    );
+
+    let err = err.context(format!("in {}", "main.lang"));
+    assert_eq!(
+        format!("{:#}", err),
+        "in main.lang: while parsing let: expected identifier, caused by: unknown character '$'"
+    );
+
+    // a message added as an error is joined like one
+    let err = Error::initial(TestError::Parse).with_context("while parsing let");
+    assert_eq!(
+        format!("{:#}", err),
+        "while parsing let, caused by: expected identifier"
+    );
+}
+
+#[test]
+fn source_chain() {
+    use std::error::Error as _;
+
+    let err = Error::initial(TestError::Lex('$')).with_context(TestError::Parse);
+    let source = err.source().unwrap();
+
+    assert_eq!(source.to_string(), "unknown character '$'");
+    assert!(source.source().is_none());
+
+    // `Display` only writes the outermost layer, so walking the sources
+    // writes each layer once
+    let err = err.context("while parsing let");
+    let mut layers = vec![err.to_string()];
+    let mut source = err.source();
+    while let Some(err) = source {
+        layers.push(err.to_string());
+        source = err.source();
+    }
+
+    assert_eq!(
+        layers,
+        [
+            "while parsing let",
+            "expected identifier",
+            "unknown character '$'"
+        ]
+    );
+}
+
+#[test]
+fn walk_chain() {
+    let err = Error::initial(TestError::Lex('$'))
+        .with_context(TestError::Parse)
+        .context("while parsing let");
+
+    let frames = err
+        .chain()
+        .map(|frame| frame.to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        frames,
+        [
+            "while parsing let",
+            "expected identifier",
+            "unknown character '$'"
+        ]
+    );
+    assert_eq!(format!("{:?}", err.root_cause()), "Lex('$')");
+
+    let err = Error::initial(TestError::Parse);
+    assert_eq!(err.chain().count(), 1);
+    assert_eq!(err.root_cause().to_string(), "expected identifier");
+}
@@ -0,0 +1,389 @@
+//! a type-erased error chain
+//!
+//! every layer added to an `Error` changes its type, so a stage that calls
+//! into several others ends up with a different error type for each of them.
+//! A `Report` holds the same layers behind trait objects, so every stage can
+//! return `Result<T, Report>`, and the concrete layers can still be found again
+//! with `downcast_ref`.
+
+use std::any::Any;
+use std::fmt;
+
+use super::{Context, Error, Frame, Initial, WithContext};
+
+/// a layer of a `Report`
+trait ErasedFrame: Frame + Send + Sync + 'static {
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_frame(&self) -> &dyn Frame;
+}
+
+impl<T: Frame + Send + Sync + 'static> ErasedFrame for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_frame(&self) -> &dyn Frame {
+        self
+    }
+}
+
+/// extra data attached to a layer of a `Report`, like the span of the error
+trait Attachment: fmt::Debug + Send + Sync + 'static {
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T: fmt::Debug + Send + Sync + 'static> Attachment for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// `Box<dyn ErasedFrame>` is an `ErasedFrame` too, so the methods of these
+/// traits are always called on the unboxed value
+struct Layer {
+    frame: Box<dyn ErasedFrame>,
+    attachments: Vec<Box<dyn Attachment>>,
+    /// whether `frame` was added by `Report::context`
+    context: bool,
+    /// the next layer in, which is the `source` of this one
+    cause: Option<Box<Layer>>,
+}
+
+impl Layer {
+    /// this layer and every layer in it, from the outermost to the innermost
+    fn chain(&self) -> impl Iterator<Item = &Layer> + '_ {
+        std::iter::successors(Some(self), |layer| layer.cause.as_deref())
+    }
+}
+
+/// an error chain whose layers can be of any type
+pub struct Report {
+    /// the outermost layer
+    layer: Layer,
+    /// errors that were found after this one, while recovering from it
+    related: Vec<Report>,
+}
+
+impl Report {
+    pub fn new<C: Frame + Send + Sync + 'static>(err: C) -> Self {
+        Self {
+            layer: Layer {
+                frame: Box::new(err),
+                attachments: Vec::new(),
+                context: false,
+                cause: None,
+            },
+            related: Vec::new(),
+        }
+    }
+
+    /// adds an outer layer that describes what was being done rather than
+    /// what went wrong, like `Error::context`
+    pub fn context<M: Frame + Send + Sync + 'static>(self, message: M) -> Self {
+        self.layer(message, true)
+    }
+
+    fn layer<C: Frame + Send + Sync + 'static>(self, frame: C, context: bool) -> Self {
+        Self {
+            layer: Layer {
+                frame: Box::new(frame),
+                attachments: Vec::new(),
+                context,
+                cause: Some(Box::new(self.layer)),
+            },
+            related: self.related,
+        }
+    }
+
+    /// attaches `value` to the outermost layer
+    pub fn attach<A: fmt::Debug + Send + Sync + 'static>(mut self, value: A) -> Self {
+        self.layer.attachments.push(Box::new(value));
+        self
+    }
+
+    /// the outermost layer
+    pub fn frame(&self) -> &dyn Frame {
+        (*self.layer.frame).as_frame()
+    }
+
+    /// every layer, from the outermost to the innermost
+    pub fn frames(&self) -> impl Iterator<Item = &dyn Frame> + '_ {
+        self.layer.chain().map(|layer| (*layer.frame).as_frame())
+    }
+
+    /// the innermost layer
+    pub fn root_cause(&self) -> &dyn Frame {
+        self.frames()
+            .last()
+            .expect("a report has at least one layer")
+    }
+
+    /// the outermost layer of type `T`
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.layer
+            .chain()
+            .find_map(|layer| (*layer.frame).as_any().downcast_ref())
+    }
+
+    /// whether any layer is of type `T`
+    pub fn contains<T: Any>(&self) -> bool {
+        self.downcast_ref::<T>().is_some()
+    }
+
+    /// every attachment of type `A`, from the outermost layer to the innermost
+    pub fn attachments<A: Any>(&self) -> impl Iterator<Item = &A> + '_ {
+        self.layer
+            .chain()
+            .flat_map(|layer| &layer.attachments)
+            .filter_map(|attachment| (**attachment).as_any().downcast_ref())
+    }
+
+    /// the attachments of type `A` on the outermost layer of type `T`
+    pub fn attachments_of<T: Any, A: Any>(&self) -> impl Iterator<Item = &A> + '_ {
+        self.layer
+            .chain()
+            .find(|layer| (*layer.frame).as_any().is::<T>())
+            .into_iter()
+            .flat_map(|layer| &layer.attachments)
+            .filter_map(|attachment| (**attachment).as_any().downcast_ref())
+    }
+
+    /// adds an error that was found after this one, so that a stage that
+    /// recovers from errors can still return all of them at once
+    pub fn push(&mut self, related: impl Into<Report>) {
+        self.related.push(related.into());
+    }
+
+    /// the errors added by `push`, in the order that they were added
+    pub fn related(&self) -> &[Report] {
+        &self.related
+    }
+
+    /// this report and every related report
+    pub fn iter(&self) -> impl Iterator<Item = &Report> + '_ {
+        std::iter::once(self).chain(&self.related)
+    }
+
+    /// collects `reports` into one, or `None` if there are none
+    pub fn collect<I>(reports: I) -> Option<Self>
+    where
+        I: IntoIterator,
+        I::Item: Into<Report>,
+    {
+        let mut reports = reports.into_iter().map(Into::into);
+        let mut first = reports.next()?;
+        first.related.extend(reports);
+        Some(first)
+    }
+}
+
+/// an error chain that can be turned into a `Report`, keeping all of its layers
+pub trait IntoReport {
+    fn into_report(self) -> Report;
+}
+
+impl IntoReport for Initial {
+    fn into_report(self) -> Report {
+        match self {}
+    }
+}
+
+impl<E: Frame + Send + Sync + 'static, P: IntoReport> IntoReport for Error<E, P> {
+    fn into_report(self) -> Report {
+        match self.cause {
+            Some(cause) => cause.into_report().layer(self.err, false),
+            None => Report::new(self.err),
+        }
+    }
+}
+
+impl<M: Frame + Send + Sync + 'static, P: IntoReport> IntoReport for Context<M, P> {
+    fn into_report(self) -> Report {
+        let (message, cause) = (self.message, self.cause);
+        cause.into_report().context(message)
+    }
+}
+
+impl<M, P> From<Context<M, P>> for Report
+where
+    Context<M, P>: IntoReport,
+{
+    fn from(err: Context<M, P>) -> Self {
+        err.into_report()
+    }
+}
+
+impl<E, P> From<Error<E, P>> for Report
+where
+    Error<E, P>: IntoReport,
+{
+    fn from(err: Error<E, P>) -> Self {
+        err.into_report()
+    }
+}
+
+impl<N: Frame + Send + Sync + 'static> WithContext<N> for Report {
+    type Output = Report;
+
+    #[inline]
+    fn with_context(self, err: N) -> Self::Output {
+        self.layer(err, false)
+    }
+}
+
+impl<T, N: Frame + Send + Sync + 'static> WithContext<N> for Result<T, Report> {
+    type Output = Result<T, Report>;
+
+    #[inline]
+    fn with_context(self, err: N) -> Self::Output {
+        self.map_err(|report| report.with_context(err))
+    }
+}
+
+/// writes the layer the same way as `Error`, so only the frame unless `{:#}`
+/// is used
+impl fmt::Display for Layer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", (*self.frame).as_frame())?;
+
+        match self.cause {
+            Some(ref cause) if f.alternate() => {
+                write!(f, "{}{:#}", super::separator(self.context), cause)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// only the frame and attachments of this layer, without its causes
+impl fmt::Debug for Layer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut layer = f.debug_tuple("");
+        layer.field(&(*self.frame).as_frame());
+
+        for attachment in &self.attachments {
+            layer.field(attachment);
+        }
+
+        layer.finish()
+    }
+}
+
+impl std::error::Error for Layer {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.cause
+            .as_deref()
+            .map(|cause| cause as &(dyn std::error::Error + 'static))
+    }
+}
+
+/// writes the outermost layer, or every layer with `{:#}`, but never the
+/// related reports
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.layer, f)
+    }
+}
+
+impl fmt::Debug for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Report")
+            .field("layers", &self.layer.chain().collect::<Vec<_>>())
+            .field("related", &self.related)
+            .finish()
+    }
+}
+
+impl std::error::Error for Report {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        std::error::Error::source(&self.layer)
+    }
+}
+
+#[cfg(test)]
+use super::TestError;
+
+#[test]
+fn from_error_chain() {
+    let err = Error::initial(TestError::Lex('$'))
+        .with_context(TestError::Parse)
+        .context("while parsing let");
+    let display = format!("{:#}", err);
+
+    let report = Report::from(err);
+
+    assert_eq!(format!("{:#}", report), display);
+    assert_eq!(report.to_string(), "while parsing let");
+    assert_eq!(report.frame().to_string(), "while parsing let");
+    assert_eq!(format!("{:?}", report.root_cause()), "Lex('$')");
+    assert_eq!(report.frames().count(), 3);
+
+    // every layer is the source of the one around it
+    let sources = std::iter::successors(std::error::Error::source(&report), |err| err.source())
+        .map(|err| err.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(sources, ["expected identifier", "unknown character '$'"]);
+
+    assert_eq!(report.downcast_ref::<TestError>(), Some(&TestError::Parse));
+    assert_eq!(report.downcast_ref::<&str>(), Some(&"while parsing let"));
+    assert!(!report.contains::<Initial>());
+
+    // `?` turns any chain into a report
+    let parse = || -> Result<(), Report> {
+        Err(Error::initial(TestError::Lex('@')))?;
+        Ok(())
+    };
+    let report = parse()
+        .with_context(TestError::Parse)
+        .map_err(|report| report.context("while parsing let"))
+        .unwrap_err();
+    assert_eq!(
+        format!("{:#}", report),
+        "while parsing let: expected identifier, caused by: unknown character '@'"
+    );
+}
+
+#[test]
+fn attachments_and_related() {
+    let mut report = Report::new(TestError::Lex('$'))
+        .attach((1_usize, 5_usize))
+        .with_context(TestError::Parse)
+        .attach((1_usize, 4_usize))
+        .attach("help: remove the `$`");
+
+    assert_eq!(
+        report.attachments::<(usize, usize)>().collect::<Vec<_>>(),
+        [&(1, 4), &(1, 5)]
+    );
+    assert_eq!(
+        report
+            .attachments_of::<TestError, &str>()
+            .collect::<Vec<_>>(),
+        [&"help: remove the `$`"]
+    );
+    assert_eq!(report.attachments::<u32>().count(), 0);
+
+    report.push(Error::initial(TestError::Lex('@')));
+    assert_eq!(report.related().len(), 1);
+    assert_eq!(report.iter().count(), 2);
+    // related reports aren't part of the chain
+    assert_eq!(
+        format!("{:#}", report),
+        "expected identifier, caused by: unknown character '$'"
+    );
+
+    let collected = Report::collect(vec![
+        Error::initial(TestError::Parse),
+        Error::initial(TestError::Lex('$')),
+    ])
+    .unwrap();
+    assert_eq!(
+        collected
+            .iter()
+            .map(|report| report.to_string())
+            .collect::<Vec<_>>(),
+        ["expected identifier", "unknown character '$'"]
+    );
+    assert!(Report::collect(Vec::<Report>::new()).is_none());
+}
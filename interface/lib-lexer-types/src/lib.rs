@@ -46,6 +46,11 @@ impl<'input, L: Lexer<'input>> Peekable<'input, L> {
         Ok(self.tokens.last().copied())
     }
 
+    /// puts `token` back, so that it is the next token
+    pub fn push(&mut self, token: Token<'input>) {
+        self.tokens.push(token)
+    }
+
     // fn peek_n(&mut self, n: usize) -> Option<&[Token<'input>]> {
     //     if self.tokens.len() < n {
     //         self.tokens.extend(self.inner.parse()?)
@@ -78,11 +83,33 @@ pub enum ErrorType {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Token<'input> {
     pub data: TokenData<'input>, // 24 bytes
     pub span: Span,              // 16 bytes
 }
 
+/// a token or a piece of trivia, which the parser never sees
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Lexeme<'input> {
+    pub kind: LexemeKind<'input>,
+    pub text: &'input str,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LexemeKind<'input> {
+    Trivia(TriviaKind),
+    Token(TokenData<'input>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TriviaKind {
+    Whitespace,
+    /// `//` up to the end of the line
+    Comment,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Real(f64);
 
@@ -100,6 +127,13 @@ impl Hash for Real {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for Real {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.0)
+    }
+}
+
 impl Real {
     pub fn new(value: f64) -> Option<Self> {
         if value.is_finite() {
@@ -115,6 +149,7 @@ impl Real {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum TokenData<'input> {
     Symbol(Symbol),
     Keyword(Keyword),
@@ -124,6 +159,33 @@ pub enum TokenData<'input> {
     StringLiteral(&'input str),
 }
 
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at {}", self.err, self.span.start())
+    }
+}
+
+impl ErrorType {
+    /// the stable code of this error, which `lib_diagnostics::explain` describes
+    pub fn code(&self) -> &'static str {
+        match self {
+            ErrorType::UnknownCharacter(_) => "E0001",
+            ErrorType::InvalidFloat(_) => "E0002",
+            ErrorType::InvalidInt(_) => "E0003",
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ErrorType::UnknownCharacter(c) => write!(f, "unknown character {:?}", c),
+            ErrorType::InvalidFloat(_) => f.write_str("invalid float literal"),
+            ErrorType::InvalidInt(err) => write!(f, "invalid integer literal: {}", err),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TokenType {
     Symbol(Symbol),
@@ -147,11 +209,63 @@ impl TokenData<'_> {
     }
 }
 
+/// the kind of token, as it would be described in an error
+impl std::fmt::Display for TokenType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            TokenType::Symbol(sym) => write!(f, "`{}`", sym.as_str()),
+            TokenType::Keyword(kw) => write!(f, "`{}`", kw.as_str()),
+            TokenType::Identifier => f.write_str("identifier"),
+            TokenType::Integer => f.write_str("integer"),
+            TokenType::Float => f.write_str("float"),
+            TokenType::StringLiteral => f.write_str("string"),
+        }
+    }
+}
+
+/// writes the token as source text that lexes back to the same token
+impl std::fmt::Display for TokenData<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            TokenData::Symbol(sym) => f.write_str(sym.as_str()),
+            TokenData::Keyword(kw) => f.write_str(kw.as_str()),
+            TokenData::Identifier(ident) => f.write_str(ident.to_str()),
+            TokenData::Integer(int) => write!(f, "{}", int),
+            TokenData::Float(real) => {
+                // floats are only lexed with a `.`, and `Display` never uses an exponent
+                let text = real.get().to_string();
+
+                if text.contains('.') {
+                    f.write_str(&text)
+                } else {
+                    write!(f, "{}.0", text)
+                }
+            }
+            TokenData::StringLiteral(string) => write!(f, "{:?}", string),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Symbol {
     Assign,
     Dot,
     Semicolon,
+    Comma,
+    FatArrow,
+    Or,
+    Underscore,
+    Colon,
+    PathSep,
+    Arrow,
+    Pound,
+    OpenBrace,
+    CloseBrace,
+    OpenParen,
+    CloseParen,
+    OpenBracket,
+    CloseBracket,
     Add,
     Sub,
     Mul,
@@ -163,9 +277,51 @@ pub enum Symbol {
     GreaterThan,
     LessEqual,
     GreaterEqual,
+    LogicalAnd,
+    LogicalOr,
+    Not,
+}
+
+impl Symbol {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Symbol::Assign => "=",
+            Symbol::Dot => ".",
+            Symbol::Semicolon => ";",
+            Symbol::Comma => ",",
+            Symbol::FatArrow => "=>",
+            Symbol::Or => "|",
+            Symbol::Underscore => "_",
+            Symbol::Colon => ":",
+            Symbol::PathSep => "::",
+            Symbol::Arrow => "->",
+            Symbol::Pound => "#",
+            Symbol::OpenBrace => "{",
+            Symbol::CloseBrace => "}",
+            Symbol::OpenParen => "(",
+            Symbol::CloseParen => ")",
+            Symbol::OpenBracket => "[",
+            Symbol::CloseBracket => "]",
+            Symbol::Add => "+",
+            Symbol::Sub => "-",
+            Symbol::Mul => "*",
+            Symbol::Div => "/",
+            Symbol::Rem => "%",
+            Symbol::Equal => "==",
+            Symbol::NotEqual => "!=",
+            Symbol::LessThan => "<",
+            Symbol::GreaterThan => ">",
+            Symbol::LessEqual => "<=",
+            Symbol::GreaterEqual => ">=",
+            Symbol::LogicalAnd => "&&",
+            Symbol::LogicalOr => "||",
+            Symbol::Not => "!",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Keyword {
     Let,
     Mut,
@@ -175,4 +331,37 @@ pub enum Keyword {
     Continue,
     Return,
     Type,
+    Fn,
+    If,
+    Else,
+    True,
+    False,
+    Struct,
+    Enum,
+    Mod,
+    Use,
+}
+
+impl Keyword {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Keyword::Let => "let",
+            Keyword::Mut => "mut",
+            Keyword::Match => "match",
+            Keyword::Loop => "loop",
+            Keyword::Break => "break",
+            Keyword::Continue => "continue",
+            Keyword::Return => "return",
+            Keyword::Type => "type",
+            Keyword::Fn => "fn",
+            Keyword::If => "if",
+            Keyword::Else => "else",
+            Keyword::True => "true",
+            Keyword::False => "false",
+            Keyword::Struct => "struct",
+            Keyword::Enum => "enum",
+            Keyword::Mod => "mod",
+            Keyword::Use => "use",
+        }
+    }
 }
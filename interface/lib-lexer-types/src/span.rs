@@ -1,12 +1,15 @@
 use std::cmp::Ordering;
+use std::fmt;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Span {
     start: CodePoint,
     end: CodePoint,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct CodePoint {
     row: u32,
     col: u32,
@@ -34,6 +37,13 @@ impl CodePoint {
     }
 }
 
+/// `row:col`
+impl fmt::Display for CodePoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.row, self.col)
+    }
+}
+
 impl Span {
     pub fn merge(self, other: Self) -> Self {
         Self {
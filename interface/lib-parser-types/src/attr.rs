@@ -0,0 +1,24 @@
+use super::{Path, Punctuated};
+use lib_lexer_types::Token;
+
+/// `#[name]` or `#[name(arg, ...)]`, which annotates the item that follows it
+///
+/// attributes may only precede `let` statements, functions and type items
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Attribute<'input> {
+    pub sym_pound: Token<'input>,
+    pub sym_open: Token<'input>,
+    pub name: Path<'input>,
+    pub args: Option<AttrArgs<'input>>,
+    pub sym_close: Token<'input>,
+}
+
+/// `(arg, ...)`, where each argument is an identifier or a literal token
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AttrArgs<'input> {
+    pub sym_open: Token<'input>,
+    pub args: Punctuated<'input, Token<'input>>,
+    pub sym_close: Token<'input>,
+}
@@ -1,17 +1,47 @@
-use super::{Assign, Expr, HAst, Let};
+use super::{Assign, Enum, Expr, Function, HAst, Let, Mod, Struct, TypeAlias, TypeExpr, Use};
 use lib_arena::{local::LocalArena, sync::SyncArena, Arena};
 
-pub type LocalContext<A, B, C, D> =
-    Context<LocalArena<A>, LocalArena<B>, LocalArena<C>, LocalArena<D>>;
+pub type LocalContext<A, B, C, D, E, F, G, H, I, J, K> = Context<
+    LocalArena<A>,
+    LocalArena<B>,
+    LocalArena<C>,
+    LocalArena<D>,
+    LocalArena<E>,
+    LocalArena<F>,
+    LocalArena<G>,
+    LocalArena<H>,
+    LocalArena<I>,
+    LocalArena<J>,
+    LocalArena<K>,
+>;
 
-pub type SyncContext<A, B, C, D> = Context<SyncArena<A>, SyncArena<B>, SyncArena<C>, SyncArena<D>>;
+pub type SyncContext<A, B, C, D, E, F, G, H, I, J, K> = Context<
+    SyncArena<A>,
+    SyncArena<B>,
+    SyncArena<C>,
+    SyncArena<D>,
+    SyncArena<E>,
+    SyncArena<F>,
+    SyncArena<G>,
+    SyncArena<H>,
+    SyncArena<I>,
+    SyncArena<J>,
+    SyncArena<K>,
+>;
 
 #[derive(Default)]
-pub struct Context<A, B, C, D> {
+pub struct Context<A, B, C, D, E, F, G, H, I, J, K> {
     pub high_ast: A,
     pub node_let: B,
     pub node_assign: C,
     pub expr: D,
+    pub node_type_alias: E,
+    pub ty: F,
+    pub node_function: G,
+    pub node_struct: H,
+    pub node_enum: I,
+    pub node_mod: J,
+    pub node_use: K,
 }
 
 #[derive(Clone, Copy)]
@@ -20,6 +50,13 @@ pub struct ContextRef<'input, 'hacx> {
     node_let: &'hacx dyn Arena<Value = Let<'input, 'hacx>>,
     node_assign: &'hacx dyn Arena<Value = Assign<'input, 'hacx>>,
     expr: &'hacx dyn Arena<Value = Expr<'input, 'hacx>>,
+    node_type_alias: &'hacx dyn Arena<Value = TypeAlias<'input, 'hacx>>,
+    ty: &'hacx dyn Arena<Value = TypeExpr<'input, 'hacx>>,
+    node_function: &'hacx dyn Arena<Value = Function<'input, 'hacx>>,
+    node_struct: &'hacx dyn Arena<Value = Struct<'input, 'hacx>>,
+    node_enum: &'hacx dyn Arena<Value = Enum<'input, 'hacx>>,
+    node_mod: &'hacx dyn Arena<Value = Mod<'input>>,
+    node_use: &'hacx dyn Arena<Value = Use<'input>>,
 }
 
 pub trait ContextOverload<'ctx, A> {
@@ -27,19 +64,33 @@ pub trait ContextOverload<'ctx, A> {
     fn alloc(self, value: A) -> &'ctx mut A;
 }
 
-impl<A, B, C, D> Context<A, B, C, D> {
+impl<A, B, C, D, E, F, G, H, I, J, K> Context<A, B, C, D, E, F, G, H, I, J, K> {
     pub fn as_ref<'input, 'hacx>(&'hacx self) -> ContextRef<'input, 'hacx>
     where
         A: Arena<Value = HAst<'input, 'hacx>>,
         B: Arena<Value = Let<'input, 'hacx>>,
         C: Arena<Value = Assign<'input, 'hacx>>,
         D: Arena<Value = Expr<'input, 'hacx>>,
+        E: Arena<Value = TypeAlias<'input, 'hacx>>,
+        F: Arena<Value = TypeExpr<'input, 'hacx>>,
+        G: Arena<Value = Function<'input, 'hacx>>,
+        H: Arena<Value = Struct<'input, 'hacx>>,
+        I: Arena<Value = Enum<'input, 'hacx>>,
+        J: Arena<Value = Mod<'input>>,
+        K: Arena<Value = Use<'input>>,
     {
         ContextRef {
             high_ast: &self.high_ast,
             node_let: &self.node_let,
             node_assign: &self.node_assign,
             expr: &self.expr,
+            node_type_alias: &self.node_type_alias,
+            ty: &self.ty,
+            node_function: &self.node_function,
+            node_struct: &self.node_struct,
+            node_enum: &self.node_enum,
+            node_mod: &self.node_mod,
+            node_use: &self.node_use,
         }
     }
 }
@@ -56,11 +107,11 @@ impl<'input, 'hacx> ContextRef<'input, 'hacx> {
 }
 
 macro_rules! overload_set {
-    ($($field:ident => $type:ident),* $(,)?) => {$(
-        impl<'input, 'hacx> ContextOverload<'hacx, $type<'input, 'hacx>> for ContextRef<'input, 'hacx> {
+    ($($field:ident => $type:ty),* $(,)?) => {$(
+        impl<'input, 'hacx> ContextOverload<'hacx, $type> for ContextRef<'input, 'hacx> {
             #[inline]
             #[allow(clippy::mut_from_ref)]
-            fn alloc(self, value: $type<'input, 'hacx>) -> &'hacx mut $type<'input, 'hacx> {
+            fn alloc(self, value: $type) -> &'hacx mut $type {
                 self.$field.alloc(value)
             }
         }
@@ -68,8 +119,15 @@ macro_rules! overload_set {
 }
 
 overload_set! {
-    high_ast => HAst,
-    node_let => Let,
-    node_assign => Assign,
-    expr => Expr,
+    high_ast => HAst<'input, 'hacx>,
+    node_let => Let<'input, 'hacx>,
+    node_assign => Assign<'input, 'hacx>,
+    expr => Expr<'input, 'hacx>,
+    node_type_alias => TypeAlias<'input, 'hacx>,
+    ty => TypeExpr<'input, 'hacx>,
+    node_function => Function<'input, 'hacx>,
+    node_struct => Struct<'input, 'hacx>,
+    node_enum => Enum<'input, 'hacx>,
+    node_mod => Mod<'input>,
+    node_use => Use<'input>,
 }
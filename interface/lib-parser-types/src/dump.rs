@@ -0,0 +1,428 @@
+//! dumps syntax trees for debugging and golden tests
+//!
+//! `sexpr` writes one node per line as an indented S-expression, where each
+//! node is `(kind atoms... children...)`. Atoms are the source text of the
+//! names, operators and literals of the node, and spans are left out so that
+//! dumps don't change when only the formatting of the source changes.
+//!
+//! `json` writes the full tree, including spans and every token, and is only
+//! available with the `serde` feature.
+
+use std::fmt::Write as _;
+
+use super::{
+    Attribute, Block, Expr, Fields, GenericArgs, GenericParams, HAst, If, Literal, Path, Pattern,
+    ReturnType, TypeAnnotation, TypeExpr,
+};
+use lib_lexer_types::Token;
+
+/// dumps a sequence of statements as S-expressions, one top level node per statement
+pub fn sexpr(items: &[HAst<'_, '_>]) -> String {
+    let mut dumper = SExpr::default();
+
+    for item in items {
+        dumper.hast(item);
+        dumper.out.push('\n');
+    }
+
+    dumper.out
+}
+
+/// dumps a single expression as an S-expression
+pub fn sexpr_expr(expr: &Expr<'_, '_>) -> String {
+    let mut dumper = SExpr::default();
+    dumper.expr(expr);
+    dumper.out
+}
+
+/// dumps a sequence of statements as pretty printed JSON
+#[cfg(feature = "serde")]
+pub fn json(items: &[HAst<'_, '_>]) -> String {
+    serde_json::to_string_pretty(items).expect("syntax trees can always be serialized")
+}
+
+#[derive(Default)]
+struct SExpr {
+    out: String,
+    depth: usize,
+}
+
+impl SExpr {
+    /// writes a node, `f` writes the atoms and then the children of the node
+    fn node(&mut self, kind: &str, f: impl FnOnce(&mut Self)) {
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+
+            for _ in 0..self.depth {
+                self.out.push_str("  ");
+            }
+        }
+
+        self.out.push('(');
+        self.out.push_str(kind);
+        self.depth += 1;
+        f(self);
+        self.depth -= 1;
+        self.out.push(')');
+    }
+
+    fn atom(&mut self, text: &str) {
+        self.out.push(' ');
+        self.out.push_str(text);
+    }
+
+    fn token(&mut self, token: Token<'_>) {
+        write!(self.out, " {}", token.data).expect("writing to a `String` can't fail");
+    }
+
+    fn path(&mut self, path: &Path<'_>) {
+        self.out.push(' ');
+
+        for (i, &segment) in path.segments.iter().enumerate() {
+            if i != 0 {
+                self.out.push_str("::");
+            }
+
+            write!(self.out, "{}", segment.data).expect("writing to a `String` can't fail");
+        }
+    }
+
+    fn attrs(&mut self, attrs: &[Attribute<'_>]) {
+        for attr in attrs {
+            self.node("attr", |this| {
+                this.path(&attr.name);
+
+                for &arg in attr.args.iter().flat_map(|args| &args.args) {
+                    this.token(arg);
+                }
+            });
+        }
+    }
+
+    fn generic_params(&mut self, generics: &Option<GenericParams<'_>>) {
+        if let Some(ref generics) = generics {
+            self.node("generics", |this| {
+                for &param in &generics.params {
+                    this.token(param);
+                }
+            });
+        }
+    }
+
+    fn generic_args(&mut self, generics: &GenericArgs<'_, '_>) {
+        self.node("generics", |this| {
+            for arg in &generics.args {
+                this.ty(arg);
+            }
+        });
+    }
+
+    fn return_type(&mut self, ret: &Option<ReturnType<'_, '_>>) {
+        if let Some(ref ret) = ret {
+            self.node("ret", |this| this.ty(ret.ty));
+        }
+    }
+
+    fn type_annotation(&mut self, ty: &Option<TypeAnnotation<'_, '_>>) {
+        if let Some(ref ty) = ty {
+            self.ty(&ty.ty);
+        }
+    }
+
+    fn fields(&mut self, fields: &Fields<'_, '_>) {
+        match fields {
+            Fields::Named(fields) => self.node("named-fields", |this| {
+                for field in &fields.fields {
+                    this.node("field", |this| {
+                        this.token(field.ident);
+                        this.ty(&field.ty);
+                    });
+                }
+            }),
+            Fields::Tuple(fields) => self.node("tuple-fields", |this| {
+                for field in &fields.fields {
+                    this.ty(field);
+                }
+            }),
+            Fields::Unit => (),
+        }
+    }
+
+    fn hast(&mut self, ast: &HAst<'_, '_>) {
+        match ast {
+            HAst::Let(ast_let) => self.node("let", |this| {
+                this.attrs(&ast_let.attrs);
+                this.pattern(&ast_let.pattern);
+                this.type_annotation(&ast_let.ty);
+                this.expr(&ast_let.value);
+            }),
+            HAst::Assign(assign) => self.node("assign", |this| {
+                this.token(assign.ident);
+                this.expr(&assign.value);
+            }),
+            HAst::TypeAlias(type_alias) => self.node("type-alias", |this| {
+                this.token(type_alias.ident);
+                this.attrs(&type_alias.attrs);
+                this.generic_params(&type_alias.generics);
+                this.ty(&type_alias.ty);
+            }),
+            HAst::Function(function) => self.node("fn", |this| {
+                this.token(function.ident);
+                this.attrs(&function.attrs);
+                this.generic_params(&function.generics);
+
+                for param in &function.params {
+                    this.node("param", |this| {
+                        this.token(param.ident);
+                        this.ty(&param.ty);
+                    });
+                }
+
+                this.return_type(&function.ret);
+                this.block(&function.body);
+            }),
+            HAst::Struct(ast_struct) => self.node("struct", |this| {
+                this.token(ast_struct.ident);
+                this.attrs(&ast_struct.attrs);
+                this.generic_params(&ast_struct.generics);
+                this.fields(&ast_struct.fields);
+            }),
+            HAst::Enum(ast_enum) => self.node("enum", |this| {
+                this.token(ast_enum.ident);
+                this.attrs(&ast_enum.attrs);
+                this.generic_params(&ast_enum.generics);
+
+                for variant in &ast_enum.variants {
+                    this.node("variant", |this| {
+                        this.token(variant.ident);
+                        this.fields(&variant.fields);
+                    });
+                }
+            }),
+            HAst::Mod(ast_mod) => self.node("mod", |this| this.token(ast_mod.ident)),
+            HAst::Use(ast_use) => self.node("use", |this| this.path(&ast_use.path)),
+            HAst::Expr(stmt) => {
+                // block-like expression statements don't need a semicolon
+                let kind = if stmt.sym_semi.is_some() {
+                    "semi"
+                } else {
+                    "expr"
+                };
+
+                self.node(kind, |this| this.expr(stmt.expr))
+            }
+        }
+    }
+
+    fn block(&mut self, block: &Block<'_, '_>) {
+        self.node("block", |this| {
+            for stmt in &block.stmts {
+                this.hast(stmt);
+            }
+
+            if let Some(ref tail) = block.tail {
+                this.node("tail", |this| this.expr(tail));
+            }
+        })
+    }
+
+    fn literal(&mut self, literal: &Literal<'_>) {
+        let (kind, token) = match *literal {
+            Literal::Integer(token) => ("int", token),
+            Literal::Float(token) => ("float", token),
+            Literal::String(token) => ("string", token),
+            Literal::Bool(token) => ("bool", token),
+        };
+
+        self.node(kind, |this| this.token(token))
+    }
+
+    fn ast_if(&mut self, ast_if: &If<'_, '_>) {
+        self.node("if", |this| {
+            this.expr(ast_if.condition);
+            this.block(&ast_if.then_branch);
+
+            if let Some(ref else_branch) = ast_if.else_branch {
+                this.node("else", |this| this.expr(else_branch.value));
+            }
+        })
+    }
+
+    fn expr(&mut self, expr: &Expr<'_, '_>) {
+        match expr {
+            Expr::Literal(literal) => self.literal(literal),
+            Expr::Identifier(ident) => self.node("ident", |this| this.token(*ident)),
+            Expr::Path(path) => self.node("path", |this| this.path(path)),
+            Expr::Prefix(op, operand) => self.node("prefix", |this| {
+                this.token(*op);
+                this.expr(operand);
+            }),
+            Expr::Postfix(operand, op) => self.node("postfix", |this| {
+                this.token(*op);
+                this.expr(operand);
+            }),
+            Expr::Binary(left, op, right) => self.node("binary", |this| {
+                this.token(*op);
+                this.expr(left);
+                this.expr(right);
+            }),
+            Expr::Logical(left, op, right) => self.node("logical", |this| {
+                this.token(*op);
+                this.expr(left);
+                this.expr(right);
+            }),
+            Expr::Match(ast_match) => self.node("match", |this| {
+                this.expr(ast_match.scrutinee);
+
+                for arm in &ast_match.arms {
+                    this.node("arm", |this| {
+                        this.pattern(&arm.pattern);
+                        this.expr(&arm.value);
+                    });
+                }
+            }),
+            Expr::If(ast_if) => self.ast_if(ast_if),
+            Expr::Block(block) => self.block(block),
+            Expr::Call(call) => self.node("call", |this| {
+                this.expr(call.callee);
+
+                for arg in &call.args {
+                    this.expr(arg);
+                }
+            }),
+            Expr::Field(field) => self.node("field", |this| {
+                this.token(field.ident);
+                this.expr(field.base);
+            }),
+            Expr::TupleIndex(tuple_index) => self.node("tuple-index", |this| {
+                this.token(tuple_index.index);
+                this.expr(tuple_index.base);
+            }),
+            Expr::MethodCall(method_call) => self.node("method-call", |this| {
+                this.token(method_call.method);
+                this.expr(method_call.receiver);
+
+                if let Some(ref generics) = method_call.generics {
+                    this.generic_args(generics);
+                }
+
+                for arg in &method_call.args {
+                    this.expr(arg);
+                }
+            }),
+            Expr::Paren(paren) => self.node("paren", |this| this.expr(paren.expr)),
+            Expr::Tuple(tuple) => self.node("tuple", |this| {
+                for item in &tuple.items {
+                    this.expr(item);
+                }
+            }),
+            Expr::Array(array) => self.node("array", |this| {
+                for item in &array.items {
+                    this.expr(item);
+                }
+            }),
+            Expr::Struct(ast_struct) => self.node("struct-expr", |this| {
+                this.path(&ast_struct.name);
+
+                for field in &ast_struct.fields {
+                    this.node("field-init", |this| {
+                        this.token(field.ident);
+
+                        if let Some((_, ref value)) = field.value {
+                            this.expr(value);
+                        }
+                    });
+                }
+            }),
+            Expr::Closure(closure) => self.node("closure", |this| {
+                for param in &closure.params {
+                    this.node("param", |this| {
+                        this.pattern(&param.pattern);
+                        this.type_annotation(&param.ty);
+                    });
+                }
+
+                this.return_type(&closure.ret);
+                this.expr(closure.body);
+            }),
+            Expr::Turbofish(turbofish) => self.node("turbofish", |this| {
+                this.expr(turbofish.base);
+                this.generic_args(&turbofish.args);
+            }),
+        }
+    }
+
+    fn pattern(&mut self, pattern: &Pattern<'_>) {
+        match pattern {
+            Pattern::Literal(literal) => self.literal(literal),
+            Pattern::Binding(binding) => self.node("binding", |this| {
+                if binding.kw_mut.is_some() {
+                    this.atom("mut");
+                }
+
+                this.token(binding.ident);
+            }),
+            Pattern::Path(path) => self.node("path-pattern", |this| this.path(path)),
+            Pattern::Wildcard(_) => self.node("wildcard", |_| ()),
+            Pattern::Or(patterns) => self.node("or", |this| {
+                for pattern in patterns {
+                    this.pattern(pattern);
+                }
+            }),
+            Pattern::Tuple(tuple) => self.node("tuple-pattern", |this| {
+                for item in &tuple.items {
+                    this.pattern(item);
+                }
+            }),
+            Pattern::Array(array) => self.node("array-pattern", |this| {
+                for item in &array.items {
+                    this.pattern(item);
+                }
+            }),
+            Pattern::Struct(ast_struct) => self.node("struct-pattern", |this| {
+                this.path(&ast_struct.name);
+
+                for field in &ast_struct.fields {
+                    this.node("field-pattern", |this| {
+                        this.token(field.ident);
+
+                        if let Some((_, ref pattern)) = field.pattern {
+                            this.pattern(pattern);
+                        }
+                    });
+                }
+            }),
+            Pattern::TupleStruct(tuple_struct) => self.node("tuple-struct-pattern", |this| {
+                this.path(&tuple_struct.name);
+
+                for item in &tuple_struct.items {
+                    this.pattern(item);
+                }
+            }),
+        }
+    }
+
+    fn ty(&mut self, ty: &TypeExpr<'_, '_>) {
+        match ty {
+            TypeExpr::Named(named) => self.node("type", |this| {
+                this.path(&named.path);
+
+                if let Some(ref args) = named.args {
+                    this.generic_args(args);
+                }
+            }),
+            TypeExpr::Tuple(tuple) => self.node("tuple-type", |this| {
+                for item in &tuple.items {
+                    this.ty(item);
+                }
+            }),
+            TypeExpr::Function(function) => self.node("fn-type", |this| {
+                for param in &function.params {
+                    this.ty(param);
+                }
+
+                this.return_type(&function.ret);
+            }),
+        }
+    }
+}
@@ -0,0 +1,1028 @@
+//! rebuilding the AST into a new context
+
+use lib_lexer_types::Token;
+
+use super::context::ContextRef;
+use super::{
+    ArrayExpr, ArrayPattern, Assign, AttrArgs, Attribute, Binding, Block, Call, Closure,
+    ClosureParam, Else, Enum, Expr, ExprStmt, Field, FieldDef, FieldInit, FieldPattern, Fields,
+    Function, FunctionType, GenericArgs, GenericParams, HAst, If, Let, Literal, Match, MatchArm,
+    MethodCall, Mod, NamedFields, NamedType, Param, ParenExpr, Path, Pattern, Punctuated,
+    ReturnType, Struct, StructExpr, StructPattern, TupleExpr, TupleFields, TupleIndex,
+    TuplePattern, TupleStructPattern, TupleType, Turbofish, TypeAlias, TypeAnnotation, TypeExpr,
+    Use, Variant,
+};
+
+/// builds a copy of a tree in the context given by `Fold::context`
+///
+/// every method defaults to the `walk_*` function of the same name, which
+/// folds the children of the node and rebuilds the node around them, so an
+/// override can replace a node outright or call that function and then
+/// change the result
+pub trait Fold<'input, 'new> {
+    fn context(&self) -> ContextRef<'input, 'new>;
+
+    fn fold_hast(&mut self, ast: &HAst<'input, '_>) -> HAst<'input, 'new> {
+        walk_hast(self, ast)
+    }
+
+    fn fold_attribute(&mut self, attr: &Attribute<'input>) -> Attribute<'input> {
+        walk_attribute(self, attr)
+    }
+
+    fn fold_let(&mut self, ast_let: &Let<'input, '_>) -> Let<'input, 'new> {
+        walk_let(self, ast_let)
+    }
+
+    fn fold_assign(&mut self, assign: &Assign<'input, '_>) -> Assign<'input, 'new> {
+        walk_assign(self, assign)
+    }
+
+    fn fold_type_alias(&mut self, type_alias: &TypeAlias<'input, '_>) -> TypeAlias<'input, 'new> {
+        walk_type_alias(self, type_alias)
+    }
+
+    fn fold_function(&mut self, function: &Function<'input, '_>) -> Function<'input, 'new> {
+        walk_function(self, function)
+    }
+
+    fn fold_param(&mut self, param: &Param<'input, '_>) -> Param<'input, 'new> {
+        walk_param(self, param)
+    }
+
+    fn fold_struct(&mut self, ast_struct: &Struct<'input, '_>) -> Struct<'input, 'new> {
+        walk_struct(self, ast_struct)
+    }
+
+    fn fold_enum(&mut self, ast_enum: &Enum<'input, '_>) -> Enum<'input, 'new> {
+        walk_enum(self, ast_enum)
+    }
+
+    fn fold_variant(&mut self, variant: &Variant<'input, '_>) -> Variant<'input, 'new> {
+        walk_variant(self, variant)
+    }
+
+    fn fold_fields(&mut self, fields: &Fields<'input, '_>) -> Fields<'input, 'new> {
+        walk_fields(self, fields)
+    }
+
+    fn fold_field_def(&mut self, field: &FieldDef<'input, '_>) -> FieldDef<'input, 'new> {
+        walk_field_def(self, field)
+    }
+
+    fn fold_mod(&mut self, ast_mod: &Mod<'input>) -> Mod<'input> {
+        walk_mod(self, ast_mod)
+    }
+
+    fn fold_use(&mut self, ast_use: &Use<'input>) -> Use<'input> {
+        walk_use(self, ast_use)
+    }
+
+    fn fold_path(&mut self, path: &Path<'input>) -> Path<'input> {
+        walk_path(self, path)
+    }
+
+    fn fold_expr_stmt(&mut self, stmt: &ExprStmt<'input, '_>) -> ExprStmt<'input, 'new> {
+        walk_expr_stmt(self, stmt)
+    }
+
+    fn fold_expr(&mut self, expr: &Expr<'input, '_>) -> Expr<'input, 'new> {
+        walk_expr(self, expr)
+    }
+
+    fn fold_literal(&mut self, literal: &Literal<'input>) -> Literal<'input> {
+        walk_literal(self, literal)
+    }
+
+    /// a name used as an expression
+    fn fold_identifier(&mut self, ident: Token<'input>) -> Token<'input> {
+        self.fold_token(ident)
+    }
+
+    fn fold_match(&mut self, ast_match: &Match<'input, '_>) -> Match<'input, 'new> {
+        walk_match(self, ast_match)
+    }
+
+    fn fold_match_arm(&mut self, arm: &MatchArm<'input, '_>) -> MatchArm<'input, 'new> {
+        walk_match_arm(self, arm)
+    }
+
+    fn fold_if(&mut self, ast_if: &If<'input, '_>) -> If<'input, 'new> {
+        walk_if(self, ast_if)
+    }
+
+    fn fold_block(&mut self, block: &Block<'input, '_>) -> Block<'input, 'new> {
+        walk_block(self, block)
+    }
+
+    fn fold_call(&mut self, call: &Call<'input, '_>) -> Call<'input, 'new> {
+        walk_call(self, call)
+    }
+
+    fn fold_field(&mut self, field: &Field<'input, '_>) -> Field<'input, 'new> {
+        walk_field(self, field)
+    }
+
+    fn fold_tuple_index(
+        &mut self,
+        tuple_index: &TupleIndex<'input, '_>,
+    ) -> TupleIndex<'input, 'new> {
+        walk_tuple_index(self, tuple_index)
+    }
+
+    fn fold_method_call(
+        &mut self,
+        method_call: &MethodCall<'input, '_>,
+    ) -> MethodCall<'input, 'new> {
+        walk_method_call(self, method_call)
+    }
+
+    fn fold_paren_expr(&mut self, paren: &ParenExpr<'input, '_>) -> ParenExpr<'input, 'new> {
+        walk_paren_expr(self, paren)
+    }
+
+    fn fold_tuple_expr(&mut self, tuple: &TupleExpr<'input, '_>) -> TupleExpr<'input, 'new> {
+        walk_tuple_expr(self, tuple)
+    }
+
+    fn fold_array_expr(&mut self, array: &ArrayExpr<'input, '_>) -> ArrayExpr<'input, 'new> {
+        walk_array_expr(self, array)
+    }
+
+    fn fold_struct_expr(
+        &mut self,
+        ast_struct: &StructExpr<'input, '_>,
+    ) -> StructExpr<'input, 'new> {
+        walk_struct_expr(self, ast_struct)
+    }
+
+    fn fold_field_init(&mut self, field: &FieldInit<'input, '_>) -> FieldInit<'input, 'new> {
+        walk_field_init(self, field)
+    }
+
+    fn fold_closure(&mut self, closure: &Closure<'input, '_>) -> Closure<'input, 'new> {
+        walk_closure(self, closure)
+    }
+
+    fn fold_closure_param(
+        &mut self,
+        param: &ClosureParam<'input, '_>,
+    ) -> ClosureParam<'input, 'new> {
+        walk_closure_param(self, param)
+    }
+
+    fn fold_turbofish(&mut self, turbofish: &Turbofish<'input, '_>) -> Turbofish<'input, 'new> {
+        walk_turbofish(self, turbofish)
+    }
+
+    fn fold_pattern(&mut self, pattern: &Pattern<'input>) -> Pattern<'input> {
+        walk_pattern(self, pattern)
+    }
+
+    /// a name bound by a pattern
+    fn fold_binding(&mut self, binding: &Binding<'input>) -> Binding<'input> {
+        walk_binding(self, binding)
+    }
+
+    fn fold_tuple_pattern(&mut self, tuple: &TuplePattern<'input>) -> TuplePattern<'input> {
+        walk_tuple_pattern(self, tuple)
+    }
+
+    fn fold_array_pattern(&mut self, array: &ArrayPattern<'input>) -> ArrayPattern<'input> {
+        walk_array_pattern(self, array)
+    }
+
+    fn fold_struct_pattern(&mut self, ast_struct: &StructPattern<'input>) -> StructPattern<'input> {
+        walk_struct_pattern(self, ast_struct)
+    }
+
+    fn fold_field_pattern(&mut self, field: &FieldPattern<'input>) -> FieldPattern<'input> {
+        walk_field_pattern(self, field)
+    }
+
+    fn fold_tuple_struct_pattern(
+        &mut self,
+        tuple_struct: &TupleStructPattern<'input>,
+    ) -> TupleStructPattern<'input> {
+        walk_tuple_struct_pattern(self, tuple_struct)
+    }
+
+    fn fold_type(&mut self, ty: &TypeExpr<'input, '_>) -> TypeExpr<'input, 'new> {
+        walk_type(self, ty)
+    }
+
+    fn fold_named_type(&mut self, named: &NamedType<'input, '_>) -> NamedType<'input, 'new> {
+        walk_named_type(self, named)
+    }
+
+    fn fold_tuple_type(&mut self, tuple: &TupleType<'input, '_>) -> TupleType<'input, 'new> {
+        walk_tuple_type(self, tuple)
+    }
+
+    fn fold_function_type(
+        &mut self,
+        function: &FunctionType<'input, '_>,
+    ) -> FunctionType<'input, 'new> {
+        walk_function_type(self, function)
+    }
+
+    fn fold_return_type(&mut self, ret: &ReturnType<'input, '_>) -> ReturnType<'input, 'new> {
+        walk_return_type(self, ret)
+    }
+
+    fn fold_type_annotation(
+        &mut self,
+        ty: &TypeAnnotation<'input, '_>,
+    ) -> TypeAnnotation<'input, 'new> {
+        walk_type_annotation(self, ty)
+    }
+
+    fn fold_generic_params(&mut self, generics: &GenericParams<'input>) -> GenericParams<'input> {
+        walk_generic_params(self, generics)
+    }
+
+    fn fold_generic_args(&mut self, args: &GenericArgs<'input, '_>) -> GenericArgs<'input, 'new> {
+        walk_generic_args(self, args)
+    }
+
+    /// every token of the tree goes through this, so overriding it can move
+    /// or rewrite tokens without overriding every node that contains them
+    fn fold_token(&mut self, token: Token<'input>) -> Token<'input> {
+        token
+    }
+}
+
+fn fold_optional_token<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    token: Option<Token<'input>>,
+) -> Option<Token<'input>> {
+    token.map(|token| folder.fold_token(token))
+}
+
+fn fold_punctuated<'input, 'new, F: Fold<'input, 'new> + ?Sized, T, U>(
+    folder: &mut F,
+    punctuated: &Punctuated<'input, T>,
+    mut f: impl FnMut(&mut F, &T) -> U,
+) -> Punctuated<'input, U> {
+    let items = punctuated.iter().map(|item| f(folder, item)).collect();
+    let separators = punctuated
+        .separators
+        .iter()
+        .map(|&separator| folder.fold_token(separator))
+        .collect();
+
+    Punctuated { items, separators }
+}
+
+pub fn walk_hast<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    ast: &HAst<'input, '_>,
+) -> HAst<'input, 'new> {
+    let ctx = folder.context();
+
+    match ast {
+        HAst::Let(ast_let) => HAst::Let(ctx.alloc(folder.fold_let(ast_let))),
+        HAst::Assign(assign) => HAst::Assign(ctx.alloc(folder.fold_assign(assign))),
+        HAst::TypeAlias(type_alias) => {
+            HAst::TypeAlias(ctx.alloc(folder.fold_type_alias(type_alias)))
+        }
+        HAst::Function(function) => HAst::Function(ctx.alloc(folder.fold_function(function))),
+        HAst::Struct(ast_struct) => HAst::Struct(ctx.alloc(folder.fold_struct(ast_struct))),
+        HAst::Enum(ast_enum) => HAst::Enum(ctx.alloc(folder.fold_enum(ast_enum))),
+        HAst::Mod(ast_mod) => HAst::Mod(ctx.alloc(folder.fold_mod(ast_mod))),
+        HAst::Use(ast_use) => HAst::Use(ctx.alloc(folder.fold_use(ast_use))),
+        HAst::Expr(stmt) => HAst::Expr(folder.fold_expr_stmt(stmt)),
+    }
+}
+
+pub fn walk_attribute<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    attr: &Attribute<'input>,
+) -> Attribute<'input> {
+    Attribute {
+        sym_pound: folder.fold_token(attr.sym_pound),
+        sym_open: folder.fold_token(attr.sym_open),
+        name: folder.fold_path(&attr.name),
+        args: attr.args.as_ref().map(|args| AttrArgs {
+            sym_open: folder.fold_token(args.sym_open),
+            args: fold_punctuated(folder, &args.args, |folder, arg| folder.fold_token(*arg)),
+            sym_close: folder.fold_token(args.sym_close),
+        }),
+        sym_close: folder.fold_token(attr.sym_close),
+    }
+}
+
+fn fold_attrs<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    attrs: &[Attribute<'input>],
+) -> Vec<Attribute<'input>> {
+    attrs
+        .iter()
+        .map(|attr| folder.fold_attribute(attr))
+        .collect()
+}
+
+pub fn walk_let<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    ast_let: &Let<'input, '_>,
+) -> Let<'input, 'new> {
+    Let {
+        attrs: fold_attrs(folder, &ast_let.attrs),
+        kw_let: folder.fold_token(ast_let.kw_let),
+        pattern: folder.fold_pattern(&ast_let.pattern),
+        ty: ast_let
+            .ty
+            .as_ref()
+            .map(|ty| folder.fold_type_annotation(ty)),
+        sym_assign: folder.fold_token(ast_let.sym_assign),
+        value: folder.fold_expr(&ast_let.value),
+        sym_semi: folder.fold_token(ast_let.sym_semi),
+    }
+}
+
+pub fn walk_assign<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    assign: &Assign<'input, '_>,
+) -> Assign<'input, 'new> {
+    Assign {
+        ident: folder.fold_token(assign.ident),
+        sym_assign: folder.fold_token(assign.sym_assign),
+        value: folder.fold_expr(&assign.value),
+        sym_semi: folder.fold_token(assign.sym_semi),
+    }
+}
+
+pub fn walk_type_alias<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    type_alias: &TypeAlias<'input, '_>,
+) -> TypeAlias<'input, 'new> {
+    TypeAlias {
+        attrs: fold_attrs(folder, &type_alias.attrs),
+        kw_type: folder.fold_token(type_alias.kw_type),
+        ident: folder.fold_token(type_alias.ident),
+        generics: type_alias
+            .generics
+            .as_ref()
+            .map(|generics| folder.fold_generic_params(generics)),
+        sym_assign: folder.fold_token(type_alias.sym_assign),
+        ty: folder.fold_type(&type_alias.ty),
+        sym_semi: folder.fold_token(type_alias.sym_semi),
+    }
+}
+
+pub fn walk_function<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    function: &Function<'input, '_>,
+) -> Function<'input, 'new> {
+    Function {
+        attrs: fold_attrs(folder, &function.attrs),
+        kw_fn: folder.fold_token(function.kw_fn),
+        ident: folder.fold_token(function.ident),
+        generics: function
+            .generics
+            .as_ref()
+            .map(|generics| folder.fold_generic_params(generics)),
+        sym_open: folder.fold_token(function.sym_open),
+        params: fold_punctuated(folder, &function.params, |folder, param| {
+            folder.fold_param(param)
+        }),
+        sym_close: folder.fold_token(function.sym_close),
+        ret: function
+            .ret
+            .as_ref()
+            .map(|ret| folder.fold_return_type(ret)),
+        body: folder.fold_block(&function.body),
+    }
+}
+
+pub fn walk_param<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    param: &Param<'input, '_>,
+) -> Param<'input, 'new> {
+    Param {
+        ident: folder.fold_token(param.ident),
+        sym_colon: folder.fold_token(param.sym_colon),
+        ty: folder.fold_type(&param.ty),
+    }
+}
+
+pub fn walk_struct<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    ast_struct: &Struct<'input, '_>,
+) -> Struct<'input, 'new> {
+    Struct {
+        attrs: fold_attrs(folder, &ast_struct.attrs),
+        kw_struct: folder.fold_token(ast_struct.kw_struct),
+        ident: folder.fold_token(ast_struct.ident),
+        generics: ast_struct
+            .generics
+            .as_ref()
+            .map(|generics| folder.fold_generic_params(generics)),
+        fields: folder.fold_fields(&ast_struct.fields),
+        sym_semi: fold_optional_token(folder, ast_struct.sym_semi),
+    }
+}
+
+pub fn walk_enum<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    ast_enum: &Enum<'input, '_>,
+) -> Enum<'input, 'new> {
+    Enum {
+        attrs: fold_attrs(folder, &ast_enum.attrs),
+        kw_enum: folder.fold_token(ast_enum.kw_enum),
+        ident: folder.fold_token(ast_enum.ident),
+        generics: ast_enum
+            .generics
+            .as_ref()
+            .map(|generics| folder.fold_generic_params(generics)),
+        sym_open: folder.fold_token(ast_enum.sym_open),
+        variants: fold_punctuated(folder, &ast_enum.variants, |folder, variant| {
+            folder.fold_variant(variant)
+        }),
+        sym_close: folder.fold_token(ast_enum.sym_close),
+    }
+}
+
+pub fn walk_variant<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    variant: &Variant<'input, '_>,
+) -> Variant<'input, 'new> {
+    Variant {
+        ident: folder.fold_token(variant.ident),
+        fields: folder.fold_fields(&variant.fields),
+    }
+}
+
+pub fn walk_fields<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    fields: &Fields<'input, '_>,
+) -> Fields<'input, 'new> {
+    match fields {
+        Fields::Named(named) => Fields::Named(NamedFields {
+            sym_open: folder.fold_token(named.sym_open),
+            fields: fold_punctuated(folder, &named.fields, |folder, field| {
+                folder.fold_field_def(field)
+            }),
+            sym_close: folder.fold_token(named.sym_close),
+        }),
+        Fields::Tuple(tuple) => Fields::Tuple(TupleFields {
+            sym_open: folder.fold_token(tuple.sym_open),
+            fields: fold_punctuated(folder, &tuple.fields, |folder, ty| folder.fold_type(ty)),
+            sym_close: folder.fold_token(tuple.sym_close),
+        }),
+        Fields::Unit => Fields::Unit,
+    }
+}
+
+pub fn walk_field_def<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    field: &FieldDef<'input, '_>,
+) -> FieldDef<'input, 'new> {
+    FieldDef {
+        ident: folder.fold_token(field.ident),
+        sym_colon: folder.fold_token(field.sym_colon),
+        ty: folder.fold_type(&field.ty),
+    }
+}
+
+pub fn walk_mod<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    ast_mod: &Mod<'input>,
+) -> Mod<'input> {
+    Mod {
+        kw_mod: folder.fold_token(ast_mod.kw_mod),
+        ident: folder.fold_token(ast_mod.ident),
+        sym_semi: folder.fold_token(ast_mod.sym_semi),
+    }
+}
+
+pub fn walk_use<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    ast_use: &Use<'input>,
+) -> Use<'input> {
+    Use {
+        kw_use: folder.fold_token(ast_use.kw_use),
+        path: folder.fold_path(&ast_use.path),
+        sym_semi: folder.fold_token(ast_use.sym_semi),
+    }
+}
+
+pub fn walk_path<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    path: &Path<'input>,
+) -> Path<'input> {
+    Path {
+        segments: fold_punctuated(folder, &path.segments, |folder, segment| {
+            folder.fold_token(*segment)
+        }),
+    }
+}
+
+pub fn walk_expr_stmt<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    stmt: &ExprStmt<'input, '_>,
+) -> ExprStmt<'input, 'new> {
+    let expr = folder.fold_expr(stmt.expr);
+
+    ExprStmt {
+        expr: folder.context().alloc(expr),
+        sym_semi: fold_optional_token(folder, stmt.sym_semi),
+    }
+}
+
+pub fn walk_expr<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    expr: &Expr<'input, '_>,
+) -> Expr<'input, 'new> {
+    let ctx = folder.context();
+
+    match expr {
+        Expr::Literal(literal) => Expr::Literal(folder.fold_literal(literal)),
+        Expr::Identifier(ident) => Expr::Identifier(folder.fold_identifier(*ident)),
+        Expr::Path(path) => Expr::Path(folder.fold_path(path)),
+        Expr::Prefix(op, expr) => {
+            let op = folder.fold_token(*op);
+
+            Expr::Prefix(op, ctx.alloc(folder.fold_expr(expr)))
+        }
+        Expr::Postfix(expr, op) => {
+            let expr = ctx.alloc(folder.fold_expr(expr));
+
+            Expr::Postfix(expr, folder.fold_token(*op))
+        }
+        Expr::Binary(left, op, right) => {
+            let left = ctx.alloc(folder.fold_expr(left));
+            let op = folder.fold_token(*op);
+            let right = ctx.alloc(folder.fold_expr(right));
+
+            Expr::Binary(left, op, right)
+        }
+        Expr::Logical(left, op, right) => {
+            let left = ctx.alloc(folder.fold_expr(left));
+            let op = folder.fold_token(*op);
+            let right = ctx.alloc(folder.fold_expr(right));
+
+            Expr::Logical(left, op, right)
+        }
+        Expr::Match(ast_match) => Expr::Match(folder.fold_match(ast_match)),
+        Expr::If(ast_if) => Expr::If(folder.fold_if(ast_if)),
+        Expr::Block(block) => Expr::Block(folder.fold_block(block)),
+        Expr::Call(call) => Expr::Call(folder.fold_call(call)),
+        Expr::Field(field) => Expr::Field(folder.fold_field(field)),
+        Expr::TupleIndex(tuple_index) => Expr::TupleIndex(folder.fold_tuple_index(tuple_index)),
+        Expr::MethodCall(method_call) => Expr::MethodCall(folder.fold_method_call(method_call)),
+        Expr::Paren(paren) => Expr::Paren(folder.fold_paren_expr(paren)),
+        Expr::Tuple(tuple) => Expr::Tuple(folder.fold_tuple_expr(tuple)),
+        Expr::Array(array) => Expr::Array(folder.fold_array_expr(array)),
+        Expr::Struct(ast_struct) => Expr::Struct(folder.fold_struct_expr(ast_struct)),
+        Expr::Closure(closure) => Expr::Closure(folder.fold_closure(closure)),
+        Expr::Turbofish(turbofish) => Expr::Turbofish(folder.fold_turbofish(turbofish)),
+    }
+}
+
+pub fn walk_literal<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    literal: &Literal<'input>,
+) -> Literal<'input> {
+    match *literal {
+        Literal::Integer(token) => Literal::Integer(folder.fold_token(token)),
+        Literal::Float(token) => Literal::Float(folder.fold_token(token)),
+        Literal::String(token) => Literal::String(folder.fold_token(token)),
+        Literal::Bool(token) => Literal::Bool(folder.fold_token(token)),
+    }
+}
+
+pub fn walk_match<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    ast_match: &Match<'input, '_>,
+) -> Match<'input, 'new> {
+    let scrutinee = folder.fold_expr(ast_match.scrutinee);
+
+    Match {
+        kw_match: folder.fold_token(ast_match.kw_match),
+        scrutinee: folder.context().alloc(scrutinee),
+        sym_open: folder.fold_token(ast_match.sym_open),
+        arms: ast_match
+            .arms
+            .iter()
+            .map(|arm| folder.fold_match_arm(arm))
+            .collect(),
+        sym_close: folder.fold_token(ast_match.sym_close),
+    }
+}
+
+pub fn walk_match_arm<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    arm: &MatchArm<'input, '_>,
+) -> MatchArm<'input, 'new> {
+    MatchArm {
+        pattern: folder.fold_pattern(&arm.pattern),
+        sym_fat_arrow: folder.fold_token(arm.sym_fat_arrow),
+        value: folder.fold_expr(&arm.value),
+        sym_comma: fold_optional_token(folder, arm.sym_comma),
+    }
+}
+
+pub fn walk_if<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    ast_if: &If<'input, '_>,
+) -> If<'input, 'new> {
+    let ctx = folder.context();
+    let condition = folder.fold_expr(ast_if.condition);
+
+    If {
+        kw_if: folder.fold_token(ast_if.kw_if),
+        condition: ctx.alloc(condition),
+        then_branch: folder.fold_block(&ast_if.then_branch),
+        else_branch: ast_if.else_branch.as_ref().map(|else_branch| Else {
+            kw_else: folder.fold_token(else_branch.kw_else),
+            value: ctx.alloc(folder.fold_expr(else_branch.value)),
+        }),
+    }
+}
+
+pub fn walk_block<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    block: &Block<'input, '_>,
+) -> Block<'input, 'new> {
+    let ctx = folder.context();
+
+    Block {
+        sym_open: folder.fold_token(block.sym_open),
+        stmts: block
+            .stmts
+            .iter()
+            .map(|stmt| folder.fold_hast(stmt))
+            .collect(),
+        tail: block
+            .tail
+            .as_ref()
+            .map(|tail| ctx.alloc(folder.fold_expr(tail))),
+        sym_close: folder.fold_token(block.sym_close),
+    }
+}
+
+pub fn walk_call<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    call: &Call<'input, '_>,
+) -> Call<'input, 'new> {
+    let callee = folder.fold_expr(call.callee);
+
+    Call {
+        callee: folder.context().alloc(callee),
+        sym_open: folder.fold_token(call.sym_open),
+        args: fold_punctuated(folder, &call.args, |folder, arg| folder.fold_expr(arg)),
+        sym_close: folder.fold_token(call.sym_close),
+    }
+}
+
+pub fn walk_field<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    field: &Field<'input, '_>,
+) -> Field<'input, 'new> {
+    let base = folder.fold_expr(field.base);
+
+    Field {
+        base: folder.context().alloc(base),
+        sym_dot: folder.fold_token(field.sym_dot),
+        ident: folder.fold_token(field.ident),
+    }
+}
+
+pub fn walk_tuple_index<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    tuple_index: &TupleIndex<'input, '_>,
+) -> TupleIndex<'input, 'new> {
+    let base = folder.fold_expr(tuple_index.base);
+
+    TupleIndex {
+        base: folder.context().alloc(base),
+        sym_dot: folder.fold_token(tuple_index.sym_dot),
+        index: folder.fold_token(tuple_index.index),
+    }
+}
+
+pub fn walk_method_call<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    method_call: &MethodCall<'input, '_>,
+) -> MethodCall<'input, 'new> {
+    let receiver = folder.fold_expr(method_call.receiver);
+
+    MethodCall {
+        receiver: folder.context().alloc(receiver),
+        sym_dot: folder.fold_token(method_call.sym_dot),
+        method: folder.fold_token(method_call.method),
+        generics: method_call
+            .generics
+            .as_ref()
+            .map(|generics| folder.fold_generic_args(generics)),
+        sym_open: folder.fold_token(method_call.sym_open),
+        args: fold_punctuated(folder, &method_call.args, |folder, arg| {
+            folder.fold_expr(arg)
+        }),
+        sym_close: folder.fold_token(method_call.sym_close),
+    }
+}
+
+pub fn walk_paren_expr<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    paren: &ParenExpr<'input, '_>,
+) -> ParenExpr<'input, 'new> {
+    let sym_open = folder.fold_token(paren.sym_open);
+    let expr = folder.fold_expr(paren.expr);
+
+    ParenExpr {
+        sym_open,
+        expr: folder.context().alloc(expr),
+        sym_close: folder.fold_token(paren.sym_close),
+    }
+}
+
+pub fn walk_tuple_expr<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    tuple: &TupleExpr<'input, '_>,
+) -> TupleExpr<'input, 'new> {
+    TupleExpr {
+        sym_open: folder.fold_token(tuple.sym_open),
+        items: fold_punctuated(folder, &tuple.items, |folder, item| folder.fold_expr(item)),
+        sym_close: folder.fold_token(tuple.sym_close),
+    }
+}
+
+pub fn walk_array_expr<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    array: &ArrayExpr<'input, '_>,
+) -> ArrayExpr<'input, 'new> {
+    ArrayExpr {
+        sym_open: folder.fold_token(array.sym_open),
+        items: fold_punctuated(folder, &array.items, |folder, item| folder.fold_expr(item)),
+        sym_close: folder.fold_token(array.sym_close),
+    }
+}
+
+pub fn walk_struct_expr<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    ast_struct: &StructExpr<'input, '_>,
+) -> StructExpr<'input, 'new> {
+    StructExpr {
+        name: folder.fold_path(&ast_struct.name),
+        sym_open: folder.fold_token(ast_struct.sym_open),
+        fields: fold_punctuated(folder, &ast_struct.fields, |folder, field| {
+            folder.fold_field_init(field)
+        }),
+        sym_close: folder.fold_token(ast_struct.sym_close),
+    }
+}
+
+pub fn walk_field_init<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    field: &FieldInit<'input, '_>,
+) -> FieldInit<'input, 'new> {
+    FieldInit {
+        ident: folder.fold_token(field.ident),
+        value: field
+            .value
+            .as_ref()
+            .map(|(sym_colon, value)| (folder.fold_token(*sym_colon), folder.fold_expr(value))),
+    }
+}
+
+pub fn walk_closure<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    closure: &Closure<'input, '_>,
+) -> Closure<'input, 'new> {
+    let params = fold_punctuated(folder, &closure.params, |folder, param| {
+        folder.fold_closure_param(param)
+    });
+    let ret = closure.ret.as_ref().map(|ret| folder.fold_return_type(ret));
+    let body = folder.fold_expr(closure.body);
+
+    Closure {
+        sym_open: folder.fold_token(closure.sym_open),
+        params,
+        sym_close: fold_optional_token(folder, closure.sym_close),
+        ret,
+        body: folder.context().alloc(body),
+    }
+}
+
+pub fn walk_closure_param<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    param: &ClosureParam<'input, '_>,
+) -> ClosureParam<'input, 'new> {
+    ClosureParam {
+        pattern: folder.fold_pattern(&param.pattern),
+        ty: param.ty.as_ref().map(|ty| folder.fold_type_annotation(ty)),
+    }
+}
+
+pub fn walk_turbofish<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    turbofish: &Turbofish<'input, '_>,
+) -> Turbofish<'input, 'new> {
+    let base = folder.fold_expr(turbofish.base);
+
+    Turbofish {
+        base: folder.context().alloc(base),
+        args: folder.fold_generic_args(&turbofish.args),
+    }
+}
+
+pub fn walk_pattern<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    pattern: &Pattern<'input>,
+) -> Pattern<'input> {
+    match pattern {
+        Pattern::Literal(literal) => Pattern::Literal(folder.fold_literal(literal)),
+        Pattern::Binding(binding) => Pattern::Binding(folder.fold_binding(binding)),
+        Pattern::Path(path) => Pattern::Path(folder.fold_path(path)),
+        Pattern::Wildcard(token) => Pattern::Wildcard(folder.fold_token(*token)),
+        Pattern::Or(patterns) => {
+            Pattern::Or(fold_punctuated(folder, patterns, |folder, pattern| {
+                folder.fold_pattern(pattern)
+            }))
+        }
+        Pattern::Tuple(tuple) => Pattern::Tuple(folder.fold_tuple_pattern(tuple)),
+        Pattern::Array(array) => Pattern::Array(folder.fold_array_pattern(array)),
+        Pattern::Struct(ast_struct) => Pattern::Struct(folder.fold_struct_pattern(ast_struct)),
+        Pattern::TupleStruct(tuple_struct) => {
+            Pattern::TupleStruct(folder.fold_tuple_struct_pattern(tuple_struct))
+        }
+    }
+}
+
+pub fn walk_binding<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    binding: &Binding<'input>,
+) -> Binding<'input> {
+    Binding {
+        kw_mut: fold_optional_token(folder, binding.kw_mut),
+        ident: folder.fold_token(binding.ident),
+    }
+}
+
+pub fn walk_tuple_pattern<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    tuple: &TuplePattern<'input>,
+) -> TuplePattern<'input> {
+    TuplePattern {
+        sym_open: folder.fold_token(tuple.sym_open),
+        items: fold_punctuated(folder, &tuple.items, |folder, item| {
+            folder.fold_pattern(item)
+        }),
+        sym_close: folder.fold_token(tuple.sym_close),
+    }
+}
+
+pub fn walk_array_pattern<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    array: &ArrayPattern<'input>,
+) -> ArrayPattern<'input> {
+    ArrayPattern {
+        sym_open: folder.fold_token(array.sym_open),
+        items: fold_punctuated(folder, &array.items, |folder, item| {
+            folder.fold_pattern(item)
+        }),
+        sym_close: folder.fold_token(array.sym_close),
+    }
+}
+
+pub fn walk_struct_pattern<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    ast_struct: &StructPattern<'input>,
+) -> StructPattern<'input> {
+    StructPattern {
+        name: folder.fold_path(&ast_struct.name),
+        sym_open: folder.fold_token(ast_struct.sym_open),
+        fields: fold_punctuated(folder, &ast_struct.fields, |folder, field| {
+            folder.fold_field_pattern(field)
+        }),
+        sym_close: folder.fold_token(ast_struct.sym_close),
+    }
+}
+
+pub fn walk_field_pattern<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    field: &FieldPattern<'input>,
+) -> FieldPattern<'input> {
+    FieldPattern {
+        ident: folder.fold_token(field.ident),
+        pattern: field.pattern.as_ref().map(|(sym_colon, pattern)| {
+            (folder.fold_token(*sym_colon), folder.fold_pattern(pattern))
+        }),
+    }
+}
+
+pub fn walk_tuple_struct_pattern<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    tuple_struct: &TupleStructPattern<'input>,
+) -> TupleStructPattern<'input> {
+    TupleStructPattern {
+        name: folder.fold_path(&tuple_struct.name),
+        sym_open: folder.fold_token(tuple_struct.sym_open),
+        items: fold_punctuated(folder, &tuple_struct.items, |folder, item| {
+            folder.fold_pattern(item)
+        }),
+        sym_close: folder.fold_token(tuple_struct.sym_close),
+    }
+}
+
+pub fn walk_type<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    ty: &TypeExpr<'input, '_>,
+) -> TypeExpr<'input, 'new> {
+    match ty {
+        TypeExpr::Named(named) => TypeExpr::Named(folder.fold_named_type(named)),
+        TypeExpr::Tuple(tuple) => TypeExpr::Tuple(folder.fold_tuple_type(tuple)),
+        TypeExpr::Function(function) => TypeExpr::Function(folder.fold_function_type(function)),
+    }
+}
+
+pub fn walk_named_type<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    named: &NamedType<'input, '_>,
+) -> NamedType<'input, 'new> {
+    NamedType {
+        path: folder.fold_path(&named.path),
+        args: named
+            .args
+            .as_ref()
+            .map(|args| folder.fold_generic_args(args)),
+    }
+}
+
+pub fn walk_tuple_type<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    tuple: &TupleType<'input, '_>,
+) -> TupleType<'input, 'new> {
+    TupleType {
+        sym_open: folder.fold_token(tuple.sym_open),
+        items: fold_punctuated(folder, &tuple.items, |folder, item| folder.fold_type(item)),
+        sym_close: folder.fold_token(tuple.sym_close),
+    }
+}
+
+pub fn walk_function_type<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    function: &FunctionType<'input, '_>,
+) -> FunctionType<'input, 'new> {
+    FunctionType {
+        kw_fn: folder.fold_token(function.kw_fn),
+        sym_open: folder.fold_token(function.sym_open),
+        params: fold_punctuated(folder, &function.params, |folder, param| {
+            folder.fold_type(param)
+        }),
+        sym_close: folder.fold_token(function.sym_close),
+        ret: function
+            .ret
+            .as_ref()
+            .map(|ret| folder.fold_return_type(ret)),
+    }
+}
+
+pub fn walk_return_type<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    ret: &ReturnType<'input, '_>,
+) -> ReturnType<'input, 'new> {
+    let ty = folder.fold_type(ret.ty);
+
+    ReturnType {
+        sym_arrow: folder.fold_token(ret.sym_arrow),
+        ty: folder.context().alloc(ty),
+    }
+}
+
+pub fn walk_type_annotation<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    ty: &TypeAnnotation<'input, '_>,
+) -> TypeAnnotation<'input, 'new> {
+    TypeAnnotation {
+        sym_colon: folder.fold_token(ty.sym_colon),
+        ty: folder.fold_type(&ty.ty),
+    }
+}
+
+pub fn walk_generic_params<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    generics: &GenericParams<'input>,
+) -> GenericParams<'input> {
+    GenericParams {
+        sym_open: folder.fold_token(generics.sym_open),
+        params: fold_punctuated(folder, &generics.params, |folder, param| {
+            folder.fold_token(*param)
+        }),
+        sym_close: folder.fold_token(generics.sym_close),
+    }
+}
+
+pub fn walk_generic_args<'input, 'new, F: Fold<'input, 'new> + ?Sized>(
+    folder: &mut F,
+    args: &GenericArgs<'input, '_>,
+) -> GenericArgs<'input, 'new> {
+    GenericArgs {
+        sym_path_sep: fold_optional_token(folder, args.sym_path_sep),
+        sym_open: folder.fold_token(args.sym_open),
+        args: fold_punctuated(folder, &args.args, |folder, arg| folder.fold_type(arg)),
+        sym_close: folder.fold_token(args.sym_close),
+    }
+}
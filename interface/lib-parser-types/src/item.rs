@@ -0,0 +1,86 @@
+use super::{Attribute, GenericParams, Path, Punctuated, TypeExpr};
+use lib_lexer_types::Token;
+
+/// `struct Name { field: T, ... }`, `struct Name(T, ...);` or `struct Name;`
+///
+/// the semicolon is only present for tuple and unit structs
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Struct<'input, 'hacx> {
+    pub attrs: Vec<Attribute<'input>>,
+    pub kw_struct: Token<'input>,
+    pub ident: Token<'input>,
+    pub generics: Option<GenericParams<'input>>,
+    pub fields: Fields<'input, 'hacx>,
+    pub sym_semi: Option<Token<'input>>,
+}
+
+/// `enum Name { Variant, Variant(T, ...), Variant { field: T, ... }, ... }`
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Enum<'input, 'hacx> {
+    pub attrs: Vec<Attribute<'input>>,
+    pub kw_enum: Token<'input>,
+    pub ident: Token<'input>,
+    pub generics: Option<GenericParams<'input>>,
+    pub sym_open: Token<'input>,
+    pub variants: Punctuated<'input, Variant<'input, 'hacx>>,
+    pub sym_close: Token<'input>,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Variant<'input, 'hacx> {
+    pub ident: Token<'input>,
+    pub fields: Fields<'input, 'hacx>,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Fields<'input, 'hacx> {
+    Named(NamedFields<'input, 'hacx>),
+    Tuple(TupleFields<'input, 'hacx>),
+    Unit,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct NamedFields<'input, 'hacx> {
+    pub sym_open: Token<'input>,
+    pub fields: Punctuated<'input, FieldDef<'input, 'hacx>>,
+    pub sym_close: Token<'input>,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TupleFields<'input, 'hacx> {
+    pub sym_open: Token<'input>,
+    pub fields: Punctuated<'input, TypeExpr<'input, 'hacx>>,
+    pub sym_close: Token<'input>,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FieldDef<'input, 'hacx> {
+    pub ident: Token<'input>,
+    pub sym_colon: Token<'input>,
+    pub ty: TypeExpr<'input, 'hacx>,
+}
+
+/// `mod name;`, which declares a module whose source is in another file
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Mod<'input> {
+    pub kw_mod: Token<'input>,
+    pub ident: Token<'input>,
+    pub sym_semi: Token<'input>,
+}
+
+/// `use path::to::item;`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Use<'input> {
+    pub kw_use: Token<'input>,
+    pub path: Path<'input>,
+    pub sym_semi: Token<'input>,
+}
@@ -1,8 +1,38 @@
-use lib_lexer_types::{Symbol, Token, TokenType};
+use lib_lexer_types::{CodePoint, LexError, Span, Token, TokenType};
+
+#[macro_use]
+mod visitor;
 
 pub mod context;
+pub mod dump;
+pub mod fold;
+pub mod print;
+pub mod visit;
+pub mod visit_mut;
+
+mod attr;
+mod item;
+mod path;
+mod pattern;
+mod precedence;
+mod punctuated;
+mod spanned;
+mod ty;
+pub use attr::{AttrArgs, Attribute};
+pub use item::{Enum, FieldDef, Fields, Mod, NamedFields, Struct, TupleFields, Use, Variant};
+pub use path::Path;
+pub use pattern::{
+    ArrayPattern, Binding, FieldPattern, Pattern, StructPattern, TuplePattern, TupleStructPattern,
+};
+pub use precedence::Precedence;
+pub use punctuated::Punctuated;
+pub use spanned::Spanned;
+pub use ty::{
+    FunctionType, GenericArgs, GenericParams, NamedType, ReturnType, TupleType, TypeAnnotation,
+    TypeExpr, TypeExprPtr,
+};
 
-type ParseError = lib_error::Error<Error, lib_lexer_types::LexError>;
+pub type ParseError = lib_error::Error<Error, LexError>;
 pub type Result<T, E = ParseError> = std::result::Result<T, E>;
 
 pub trait Parser<'input, 'hacx> {
@@ -22,51 +52,392 @@ impl<'input, 'hacx, P: Parser<'input, 'hacx> + ?Sized> Parser<'input, 'hacx> for
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub enum Error {
+pub struct Error {
+    pub err: ErrorType,
+    /// the token where the error was found, or the character after the last
+    /// token if the input ended too early
+    pub span: Span,
+    /// the end of the token before the error, which is where a missing token
+    /// would go
+    pub prev_end: Option<CodePoint>,
+}
+
+impl Error {
+    pub fn new(err: ErrorType, span: Span) -> Self {
+        Self {
+            err,
+            span,
+            prev_end: None,
+        }
+    }
+
+    pub fn with_prev_end(self, prev_end: Option<CodePoint>) -> Self {
+        Self { prev_end, ..self }
+    }
+}
+
+/// wraps an error from the lexer in a parse error at the same place
+pub fn lex_error(err: LexError) -> ParseError {
+    let span = err.err().span;
+
+    err.with_context(Error::new(ErrorType::LexError, span))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorType {
     LexError,
     Expected(TokenType),
     ExpectedOneOf(Vec<TokenType>),
+    InvalidAssignTarget,
+    /// attributes were given to a statement that can't have attributes
+    InvalidAttributeTarget,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at {}", self.err, self.span.start())
+    }
+}
+
+impl ErrorType {
+    /// the stable code of this error, which `lib_diagnostics::explain` describes
+    ///
+    /// the lexer's errors are `E00xx`, and the parser's are `E01xx`
+    pub fn code(&self) -> &'static str {
+        match self {
+            ErrorType::LexError => "E0100",
+            ErrorType::Expected(_) => "E0101",
+            ErrorType::ExpectedOneOf(_) => "E0102",
+            ErrorType::InvalidAssignTarget => "E0103",
+            ErrorType::InvalidAttributeTarget => "E0104",
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ErrorType::LexError => f.write_str("couldn't read the next token"),
+            ErrorType::Expected(tok_type) => write!(f, "expected {}", tok_type),
+            ErrorType::ExpectedOneOf(tok_types) => {
+                f.write_str("expected one of ")?;
+
+                for (i, tok_type) in tok_types.iter().enumerate() {
+                    if i != 0 {
+                        f.write_str(", ")?;
+                    }
+
+                    write!(f, "{}", tok_type)?;
+                }
+
+                Ok(())
+            }
+            ErrorType::InvalidAssignTarget => f.write_str("invalid left side of an assignment"),
+            ErrorType::InvalidAttributeTarget => {
+                f.write_str("attributes aren't allowed on this statement")
+            }
+        }
+    }
 }
 
 pub type HAstPtr<'input, 'hacx> = &'hacx mut HAst<'input, 'hacx>;
 pub type ExprPtr<'input, 'hacx> = &'hacx mut Expr<'input, 'hacx>;
 
 #[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum HAst<'input, 'hacx> {
     Let(&'hacx mut Let<'input, 'hacx>),
     Assign(&'hacx mut Assign<'input, 'hacx>),
+    TypeAlias(&'hacx mut TypeAlias<'input, 'hacx>),
+    Function(&'hacx mut Function<'input, 'hacx>),
+    Struct(&'hacx mut Struct<'input, 'hacx>),
+    Enum(&'hacx mut Enum<'input, 'hacx>),
+    Mod(&'hacx mut Mod<'input>),
+    Use(&'hacx mut Use<'input>),
+    Expr(ExprStmt<'input, 'hacx>),
 }
 
+/// an expression used as a statement
+///
+/// the semicolon may only be omitted after block-like expressions
 #[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ExprStmt<'input, 'hacx> {
+    pub expr: ExprPtr<'input, 'hacx>,
+    pub sym_semi: Option<Token<'input>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Expr<'input, 'hacx> {
     Literal(Literal<'input>),
     Identifier(Token<'input>),
-    Prefix(Symbol, ExprPtr<'input, 'hacx>),
-    Postfix(ExprPtr<'input, 'hacx>, Symbol),
-    Binary(ExprPtr<'input, 'hacx>, Symbol, ExprPtr<'input, 'hacx>),
+    /// a path with at least two segments, single identifiers are `Expr::Identifier`
+    Path(Path<'input>),
+    /// the operators are `Symbol` tokens
+    Prefix(Token<'input>, ExprPtr<'input, 'hacx>),
+    Postfix(ExprPtr<'input, 'hacx>, Token<'input>),
+    Binary(
+        ExprPtr<'input, 'hacx>,
+        Token<'input>,
+        ExprPtr<'input, 'hacx>,
+    ),
+    /// `&&` and `||`, the right operand is only evaluated if the left operand
+    /// doesn't decide the result
+    Logical(
+        ExprPtr<'input, 'hacx>,
+        Token<'input>,
+        ExprPtr<'input, 'hacx>,
+    ),
+    Match(Match<'input, 'hacx>),
+    If(If<'input, 'hacx>),
+    Block(Block<'input, 'hacx>),
+    Call(Call<'input, 'hacx>),
+    Field(Field<'input, 'hacx>),
+    TupleIndex(TupleIndex<'input, 'hacx>),
+    MethodCall(MethodCall<'input, 'hacx>),
+    Paren(ParenExpr<'input, 'hacx>),
+    Tuple(TupleExpr<'input, 'hacx>),
+    Array(ArrayExpr<'input, 'hacx>),
+    Struct(StructExpr<'input, 'hacx>),
+    Closure(Closure<'input, 'hacx>),
+    Turbofish(Turbofish<'input, 'hacx>),
+}
+
+impl Expr<'_, '_> {
+    /// block-like expressions end a statement without needing a semicolon
+    pub fn is_block_like(&self) -> bool {
+        matches!(self, Expr::Match(_) | Expr::If(_) | Expr::Block(_))
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Literal<'input> {
     Integer(Token<'input>),
     Float(Token<'input>),
     String(Token<'input>),
+    Bool(Token<'input>),
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Match<'input, 'hacx> {
+    pub kw_match: Token<'input>,
+    pub scrutinee: ExprPtr<'input, 'hacx>,
+    pub sym_open: Token<'input>,
+    pub arms: Vec<MatchArm<'input, 'hacx>>,
+    pub sym_close: Token<'input>,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct MatchArm<'input, 'hacx> {
+    pub pattern: Pattern<'input>,
+    pub sym_fat_arrow: Token<'input>,
+    pub value: Expr<'input, 'hacx>,
+    pub sym_comma: Option<Token<'input>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Call<'input, 'hacx> {
+    pub callee: ExprPtr<'input, 'hacx>,
+    pub sym_open: Token<'input>,
+    pub args: Punctuated<'input, Expr<'input, 'hacx>>,
+    pub sym_close: Token<'input>,
+}
+
+/// `base.ident`
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Field<'input, 'hacx> {
+    pub base: ExprPtr<'input, 'hacx>,
+    pub sym_dot: Token<'input>,
+    pub ident: Token<'input>,
+}
+
+/// `base.0`, where `index` is an integer token
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TupleIndex<'input, 'hacx> {
+    pub base: ExprPtr<'input, 'hacx>,
+    pub sym_dot: Token<'input>,
+    pub index: Token<'input>,
+}
+
+/// `receiver.method(args...)` or `receiver.method::<T, ...>(args...)`
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct MethodCall<'input, 'hacx> {
+    pub receiver: ExprPtr<'input, 'hacx>,
+    pub sym_dot: Token<'input>,
+    pub method: Token<'input>,
+    pub generics: Option<GenericArgs<'input, 'hacx>>,
+    pub sym_open: Token<'input>,
+    pub args: Punctuated<'input, Expr<'input, 'hacx>>,
+    pub sym_close: Token<'input>,
+}
+
+/// `base::<T, ...>`, explicit generic arguments for `base`
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Turbofish<'input, 'hacx> {
+    pub base: ExprPtr<'input, 'hacx>,
+    pub args: GenericArgs<'input, 'hacx>,
+}
+
+/// `(expr)`
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ParenExpr<'input, 'hacx> {
+    pub sym_open: Token<'input>,
+    pub expr: ExprPtr<'input, 'hacx>,
+    pub sym_close: Token<'input>,
+}
+
+/// `(a, b, ...)`, where `()` is the unit value
+///
+/// a single expression in parentheses without a trailing comma is not a tuple,
+/// and is parsed as a `ParenExpr`
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TupleExpr<'input, 'hacx> {
+    pub sym_open: Token<'input>,
+    pub items: Punctuated<'input, Expr<'input, 'hacx>>,
+    pub sym_close: Token<'input>,
+}
+
+/// `[a, b, ...]`
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ArrayExpr<'input, 'hacx> {
+    pub sym_open: Token<'input>,
+    pub items: Punctuated<'input, Expr<'input, 'hacx>>,
+    pub sym_close: Token<'input>,
+}
+
+/// `Name { field: value, field, ... }`
+///
+/// struct literals are not allowed in the condition of an `if` or the scrutinee
+/// of a `match`, unless they are wrapped in parentheses
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct StructExpr<'input, 'hacx> {
+    pub name: Path<'input>,
+    pub sym_open: Token<'input>,
+    pub fields: Punctuated<'input, FieldInit<'input, 'hacx>>,
+    pub sym_close: Token<'input>,
+}
+
+/// `field: value`, or just `field` which takes the value of the variable of the same name
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FieldInit<'input, 'hacx> {
+    pub ident: Token<'input>,
+    pub value: Option<(Token<'input>, Expr<'input, 'hacx>)>,
+}
+
+/// `|params...| body` or `|params...| -> R { ... }`
+///
+/// a closure without parameters is written `||`, which is lexed as a single
+/// `Symbol::LogicalOr` token, in that case `sym_open` is that token and
+/// `sym_close` is `None`
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Closure<'input, 'hacx> {
+    pub sym_open: Token<'input>,
+    pub params: Punctuated<'input, ClosureParam<'input, 'hacx>>,
+    pub sym_close: Option<Token<'input>>,
+    pub ret: Option<ReturnType<'input, 'hacx>>,
+    /// if there is a return type, then this is always an `Expr::Block`
+    pub body: ExprPtr<'input, 'hacx>,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ClosureParam<'input, 'hacx> {
+    pub pattern: Pattern<'input>,
+    pub ty: Option<TypeAnnotation<'input, 'hacx>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct If<'input, 'hacx> {
+    pub kw_if: Token<'input>,
+    pub condition: ExprPtr<'input, 'hacx>,
+    pub then_branch: Block<'input, 'hacx>,
+    pub else_branch: Option<Else<'input, 'hacx>>,
+}
+
+/// the `else` of an `if`, where `value` is either another `Expr::If` or an `Expr::Block`
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Else<'input, 'hacx> {
+    pub kw_else: Token<'input>,
+    pub value: ExprPtr<'input, 'hacx>,
 }
 
 #[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Block<'input, 'hacx> {
+    pub sym_open: Token<'input>,
+    pub stmts: Vec<HAst<'input, 'hacx>>,
+    pub tail: Option<ExprPtr<'input, 'hacx>>,
+    pub sym_close: Token<'input>,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Let<'input, 'hacx> {
+    pub attrs: Vec<Attribute<'input>>,
     pub kw_let: Token<'input>,
-    pub kw_mut: Option<Token<'input>>,
-    pub ident: Token<'input>,
+    pub pattern: Pattern<'input>,
+    pub ty: Option<TypeAnnotation<'input, 'hacx>>,
     pub sym_assign: Token<'input>,
     pub value: Expr<'input, 'hacx>,
     pub sym_semi: Token<'input>,
 }
 
 #[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Assign<'input, 'hacx> {
     pub ident: Token<'input>,
     pub sym_assign: Token<'input>,
     pub value: Expr<'input, 'hacx>,
     pub sym_semi: Token<'input>,
 }
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TypeAlias<'input, 'hacx> {
+    pub attrs: Vec<Attribute<'input>>,
+    pub kw_type: Token<'input>,
+    pub ident: Token<'input>,
+    pub generics: Option<GenericParams<'input>>,
+    pub sym_assign: Token<'input>,
+    pub ty: TypeExpr<'input, 'hacx>,
+    pub sym_semi: Token<'input>,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Function<'input, 'hacx> {
+    pub attrs: Vec<Attribute<'input>>,
+    pub kw_fn: Token<'input>,
+    pub ident: Token<'input>,
+    pub generics: Option<GenericParams<'input>>,
+    pub sym_open: Token<'input>,
+    pub params: Punctuated<'input, Param<'input, 'hacx>>,
+    pub sym_close: Token<'input>,
+    pub ret: Option<ReturnType<'input, 'hacx>>,
+    pub body: Block<'input, 'hacx>,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Param<'input, 'hacx> {
+    pub ident: Token<'input>,
+    pub sym_colon: Token<'input>,
+    pub ty: TypeExpr<'input, 'hacx>,
+}
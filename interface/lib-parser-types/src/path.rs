@@ -0,0 +1,27 @@
+use super::Punctuated;
+use lib_lexer_types::Token;
+
+/// `a::b::c`, the separators are `Symbol::PathSep` tokens
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Path<'input> {
+    pub segments: Punctuated<'input, Token<'input>>,
+}
+
+impl<'input> Path<'input> {
+    pub fn first(&self) -> Token<'input> {
+        self.segments.items[0]
+    }
+
+    pub fn last(&self) -> Token<'input> {
+        *self.segments.items.last().unwrap()
+    }
+
+    /// returns the identifier if this path only has one segment
+    pub fn as_ident(&self) -> Option<Token<'input>> {
+        match self.segments.items[..] {
+            [ident] => Some(ident),
+            _ => None,
+        }
+    }
+}
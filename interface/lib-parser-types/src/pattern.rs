@@ -0,0 +1,75 @@
+use super::{Literal, Path, Punctuated};
+use lib_lexer_types::Token;
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Pattern<'input> {
+    Literal(Literal<'input>),
+    Binding(Binding<'input>),
+    /// a path with at least two segments, such as a unit enum variant,
+    /// single identifiers are `Pattern::Binding`
+    Path(Path<'input>),
+    Wildcard(Token<'input>),
+    Or(Punctuated<'input, Pattern<'input>>),
+    Tuple(TuplePattern<'input>),
+    Array(ArrayPattern<'input>),
+    Struct(StructPattern<'input>),
+    TupleStruct(TupleStructPattern<'input>),
+}
+
+/// `ident` or `mut ident`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Binding<'input> {
+    pub kw_mut: Option<Token<'input>>,
+    pub ident: Token<'input>,
+}
+
+/// `(a, b, ...)`
+///
+/// a single pattern in parentheses without a trailing comma is not a tuple,
+/// and is parsed as the inner pattern
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TuplePattern<'input> {
+    pub sym_open: Token<'input>,
+    pub items: Punctuated<'input, Pattern<'input>>,
+    pub sym_close: Token<'input>,
+}
+
+/// `[a, b, ...]`
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ArrayPattern<'input> {
+    pub sym_open: Token<'input>,
+    pub items: Punctuated<'input, Pattern<'input>>,
+    pub sym_close: Token<'input>,
+}
+
+/// `Name { field, field: pattern, ... }`
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct StructPattern<'input> {
+    pub name: Path<'input>,
+    pub sym_open: Token<'input>,
+    pub fields: Punctuated<'input, FieldPattern<'input>>,
+    pub sym_close: Token<'input>,
+}
+
+/// `field: pattern`, or just `field` which binds the field to a variable of the same name
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FieldPattern<'input> {
+    pub ident: Token<'input>,
+    pub pattern: Option<(Token<'input>, Pattern<'input>)>,
+}
+
+/// `Name(a, b, ...)`
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TupleStructPattern<'input> {
+    pub name: Path<'input>,
+    pub sym_open: Token<'input>,
+    pub items: Punctuated<'input, Pattern<'input>>,
+    pub sym_close: Token<'input>,
+}
@@ -0,0 +1,52 @@
+use lib_lexer_types::Symbol;
+
+/// Binding power of operators, from loosest to tightest
+///
+/// All binary operators are left associative
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Precedence {
+    /// closures extend as far to the right as possible
+    Closure,
+    LogicalOr,
+    LogicalAnd,
+    Compare,
+    Sum,
+    Product,
+    Prefix,
+    Primary,
+}
+
+impl Precedence {
+    pub fn of_binary(symbol: Symbol) -> Option<Self> {
+        match symbol {
+            Symbol::LogicalOr => Some(Precedence::LogicalOr),
+            Symbol::LogicalAnd => Some(Precedence::LogicalAnd),
+            Symbol::Equal
+            | Symbol::NotEqual
+            | Symbol::LessThan
+            | Symbol::GreaterThan
+            | Symbol::LessEqual
+            | Symbol::GreaterEqual => Some(Precedence::Compare),
+            Symbol::Add | Symbol::Sub => Some(Precedence::Sum),
+            Symbol::Mul | Symbol::Div | Symbol::Rem => Some(Precedence::Product),
+            _ => None,
+        }
+    }
+
+    pub fn is_prefix(symbol: Symbol) -> bool {
+        matches!(symbol, Symbol::Sub | Symbol::Not)
+    }
+
+    /// the next tighter precedence level
+    pub fn next(self) -> Self {
+        match self {
+            Precedence::Closure => Precedence::LogicalOr,
+            Precedence::LogicalOr => Precedence::LogicalAnd,
+            Precedence::LogicalAnd => Precedence::Compare,
+            Precedence::Compare => Precedence::Sum,
+            Precedence::Sum => Precedence::Product,
+            Precedence::Product => Precedence::Prefix,
+            Precedence::Prefix | Precedence::Primary => Precedence::Primary,
+        }
+    }
+}
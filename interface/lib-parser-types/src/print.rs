@@ -0,0 +1,686 @@
+//! turns syntax trees back into source text
+//!
+//! The output is canonical: spacing, line breaks and indentation are fixed,
+//! and parentheses are only added where the precedence of operators, or the
+//! grammar of statements and `if`/`match` heads, require them. Optional
+//! punctuation that is stored in the tree, such as parenthesized
+//! expressions, trailing commas and semicolons after block-like statements,
+//! is kept, so parsing the printed source gives back the same tree, except
+//! for spans.
+
+use std::fmt::Write as _;
+
+use super::{
+    Attribute, Block, Closure, Enum, Expr, Fields, Function, GenericArgs, GenericParams, HAst, If,
+    Literal, Match, Path, Pattern, Precedence, Punctuated, ReturnType, Struct, TypeAlias,
+    TypeAnnotation, TypeExpr,
+};
+use lib_lexer_types::{Token, TokenData};
+
+const INDENT: &str = "    ";
+
+/// prints a sequence of statements, one per line
+pub fn to_source(items: &[HAst<'_, '_>]) -> String {
+    let mut printer = Printer::new();
+
+    for item in items {
+        printer.print_hast(item);
+        printer.out.push('\n');
+    }
+
+    printer.finish()
+}
+
+#[derive(Debug)]
+pub struct Printer {
+    out: String,
+    indent: usize,
+    // mirrors the parser, struct literals are ambiguous with the block after
+    // an `if` condition or `match` scrutinee
+    struct_literals: bool,
+}
+
+impl Default for Printer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// the precedence of an expression, as seen by the operator around it
+fn precedence(expr: &Expr<'_, '_>) -> Precedence {
+    match expr {
+        Expr::Binary(_, op, _) | Expr::Logical(_, op, _) => match op.data {
+            TokenData::Symbol(symbol) => {
+                Precedence::of_binary(symbol).unwrap_or(Precedence::Primary)
+            }
+            _ => Precedence::Primary,
+        },
+        Expr::Prefix(..) => Precedence::Prefix,
+        // the body of a closure without a return type extends as far right as possible
+        Expr::Closure(closure) if closure.ret.is_none() => Precedence::Closure,
+        _ => Precedence::Primary,
+    }
+}
+
+/// if the leftmost primary expression of `expr` is block-like
+///
+/// a block-like expression at the start of a statement ends the statement,
+/// so such an expression statement must be wrapped in parentheses
+fn starts_with_block(expr: &Expr<'_, '_>) -> bool {
+    match expr {
+        Expr::Match(_) | Expr::If(_) | Expr::Block(_) => true,
+        Expr::Binary(left, ..) | Expr::Logical(left, ..) | Expr::Postfix(left, _) => {
+            starts_with_block(left)
+        }
+        Expr::Call(call) => starts_with_block(call.callee),
+        Expr::Field(field) => starts_with_block(field.base),
+        Expr::TupleIndex(tuple_index) => starts_with_block(tuple_index.base),
+        Expr::MethodCall(method_call) => starts_with_block(method_call.receiver),
+        Expr::Turbofish(turbofish) => starts_with_block(turbofish.base),
+        _ => false,
+    }
+}
+
+/// if the printed pattern starts with `(`, which would continue a block-like
+/// match arm without a comma as a call
+fn starts_with_paren(pattern: &Pattern<'_>) -> bool {
+    match pattern {
+        Pattern::Tuple(_) => true,
+        Pattern::Or(patterns) => {
+            matches!(patterns.items.first(), Some(first) if starts_with_paren(first))
+        }
+        _ => false,
+    }
+}
+
+impl Printer {
+    pub fn new() -> Self {
+        Self {
+            out: String::new(),
+            indent: 0,
+            struct_literals: true,
+        }
+    }
+
+    pub fn finish(self) -> String {
+        self.out
+    }
+
+    fn token(&mut self, token: Token<'_>) {
+        write!(self.out, "{}", token.data).expect("writing to a `String` can't fail");
+    }
+
+    fn line(&mut self) {
+        self.out.push('\n');
+
+        for _ in 0..self.indent {
+            self.out.push_str(INDENT);
+        }
+    }
+
+    fn punctuated<T>(
+        &mut self,
+        items: &Punctuated<'_, T>,
+        mut print_item: impl FnMut(&mut Self, &T),
+    ) {
+        for (i, item) in items.iter().enumerate() {
+            if i != 0 {
+                self.out.push_str(", ");
+            }
+
+            print_item(self, item);
+        }
+
+        if items.has_trailing() {
+            self.out.push(',');
+        }
+    }
+
+    /// prints the items of a tuple, a single item always needs a trailing comma
+    fn tuple<T>(&mut self, items: &Punctuated<'_, T>, print_item: impl FnMut(&mut Self, &T)) {
+        self.out.push('(');
+        self.punctuated(items, print_item);
+
+        if items.len() == 1 && !items.has_trailing() {
+            self.out.push(',');
+        }
+
+        self.out.push(')');
+    }
+
+    /// prints one item per line, used for the fields of structs and the variants of enums
+    fn lines<T>(&mut self, items: &Punctuated<'_, T>, mut print_item: impl FnMut(&mut Self, &T)) {
+        if items.is_empty() {
+            self.out.push_str("{}");
+            return;
+        }
+
+        self.out.push('{');
+        self.indent += 1;
+
+        for (i, item) in items.iter().enumerate() {
+            self.line();
+            print_item(self, item);
+
+            if i + 1 < items.len() || items.has_trailing() {
+                self.out.push(',');
+            }
+        }
+
+        self.indent -= 1;
+        self.line();
+        self.out.push('}');
+    }
+
+    fn with_struct_literals(&mut self, allowed: bool, f: impl FnOnce(&mut Self)) {
+        let old = std::mem::replace(&mut self.struct_literals, allowed);
+        f(self);
+        self.struct_literals = old;
+    }
+
+    pub fn print_hast(&mut self, ast: &HAst<'_, '_>) {
+        match ast {
+            HAst::Let(ast_let) => {
+                self.attrs(&ast_let.attrs);
+                self.out.push_str("let ");
+                self.print_pattern(&ast_let.pattern);
+                self.type_annotation(&ast_let.ty);
+                self.out.push_str(" = ");
+                self.print_expr(&ast_let.value);
+                self.out.push(';');
+            }
+            HAst::Assign(assign) => {
+                self.token(assign.ident);
+                self.out.push_str(" = ");
+                self.print_expr(&assign.value);
+                self.out.push(';');
+            }
+            HAst::TypeAlias(type_alias) => self.type_alias(type_alias),
+            HAst::Function(function) => self.function(function),
+            HAst::Struct(ast_struct) => self.ast_struct(ast_struct),
+            HAst::Enum(ast_enum) => self.ast_enum(ast_enum),
+            HAst::Mod(ast_mod) => {
+                self.out.push_str("mod ");
+                self.token(ast_mod.ident);
+                self.out.push(';');
+            }
+            HAst::Use(ast_use) => {
+                self.out.push_str("use ");
+                self.path(&ast_use.path);
+                self.out.push(';');
+            }
+            HAst::Expr(stmt) => {
+                self.stmt_expr(stmt.expr);
+
+                if stmt.sym_semi.is_some() {
+                    self.out.push(';');
+                }
+            }
+        }
+    }
+
+    /// prints an expression at the start of a statement
+    fn stmt_expr(&mut self, expr: &Expr<'_, '_>) {
+        if !expr.is_block_like() && starts_with_block(expr) {
+            self.parens(expr);
+        } else {
+            self.print_expr(expr);
+        }
+    }
+
+    fn attrs(&mut self, attrs: &[Attribute<'_>]) {
+        for attr in attrs {
+            self.out.push_str("#[");
+            self.path(&attr.name);
+
+            if let Some(ref args) = attr.args {
+                self.out.push('(');
+                self.punctuated(&args.args, |this, &arg| this.token(arg));
+                self.out.push(')');
+            }
+
+            self.out.push(']');
+            self.line();
+        }
+    }
+
+    fn type_alias(&mut self, type_alias: &TypeAlias<'_, '_>) {
+        self.attrs(&type_alias.attrs);
+        self.out.push_str("type ");
+        self.token(type_alias.ident);
+        self.generic_params(&type_alias.generics);
+        self.out.push_str(" = ");
+        self.print_type(&type_alias.ty);
+        self.out.push(';');
+    }
+
+    fn function(&mut self, function: &Function<'_, '_>) {
+        self.attrs(&function.attrs);
+        self.out.push_str("fn ");
+        self.token(function.ident);
+        self.generic_params(&function.generics);
+        self.out.push('(');
+        self.punctuated(&function.params, |this, param| {
+            this.token(param.ident);
+            this.out.push_str(": ");
+            this.print_type(&param.ty);
+        });
+        self.out.push(')');
+        self.return_type(&function.ret);
+        self.out.push(' ');
+        self.block(&function.body);
+    }
+
+    fn ast_struct(&mut self, ast_struct: &Struct<'_, '_>) {
+        self.attrs(&ast_struct.attrs);
+        self.out.push_str("struct ");
+        self.token(ast_struct.ident);
+        self.generic_params(&ast_struct.generics);
+
+        match ast_struct.fields {
+            Fields::Named(ref fields) => {
+                self.out.push(' ');
+                self.lines(&fields.fields, |this, field| {
+                    this.token(field.ident);
+                    this.out.push_str(": ");
+                    this.print_type(&field.ty);
+                });
+            }
+            Fields::Tuple(ref fields) => {
+                self.out.push('(');
+                self.punctuated(&fields.fields, Self::print_type);
+                self.out.push_str(");");
+            }
+            Fields::Unit => self.out.push(';'),
+        }
+    }
+
+    fn ast_enum(&mut self, ast_enum: &Enum<'_, '_>) {
+        self.attrs(&ast_enum.attrs);
+        self.out.push_str("enum ");
+        self.token(ast_enum.ident);
+        self.generic_params(&ast_enum.generics);
+        self.out.push(' ');
+        self.lines(&ast_enum.variants, |this, variant| {
+            this.token(variant.ident);
+
+            match variant.fields {
+                Fields::Named(ref fields) => {
+                    this.out.push_str(" { ");
+                    this.punctuated(&fields.fields, |this, field| {
+                        this.token(field.ident);
+                        this.out.push_str(": ");
+                        this.print_type(&field.ty);
+                    });
+                    this.out.push_str(" }");
+                }
+                Fields::Tuple(ref fields) => {
+                    this.out.push('(');
+                    this.punctuated(&fields.fields, Self::print_type);
+                    this.out.push(')');
+                }
+                Fields::Unit => (),
+            }
+        });
+    }
+
+    fn generic_params(&mut self, generics: &Option<GenericParams<'_>>) {
+        if let Some(ref generics) = generics {
+            self.out.push('<');
+            self.punctuated(&generics.params, |this, &param| this.token(param));
+            self.out.push('>');
+        }
+    }
+
+    fn generic_args(&mut self, generics: &GenericArgs<'_, '_>) {
+        if generics.sym_path_sep.is_some() {
+            self.out.push_str("::");
+        }
+
+        self.out.push('<');
+        self.punctuated(&generics.args, Self::print_type);
+        self.out.push('>');
+    }
+
+    fn return_type(&mut self, ret: &Option<ReturnType<'_, '_>>) {
+        if let Some(ref ret) = ret {
+            self.out.push_str(" -> ");
+            self.print_type(ret.ty);
+        }
+    }
+
+    fn type_annotation(&mut self, ty: &Option<TypeAnnotation<'_, '_>>) {
+        if let Some(ref ty) = ty {
+            self.out.push_str(": ");
+            self.print_type(&ty.ty);
+        }
+    }
+
+    fn path(&mut self, path: &Path<'_>) {
+        for (i, &segment) in path.segments.iter().enumerate() {
+            if i != 0 {
+                self.out.push_str("::");
+            }
+
+            self.token(segment);
+        }
+    }
+
+    fn literal(&mut self, literal: &Literal<'_>) {
+        match *literal {
+            Literal::Integer(token)
+            | Literal::Float(token)
+            | Literal::String(token)
+            | Literal::Bool(token) => self.token(token),
+        }
+    }
+
+    fn block(&mut self, block: &Block<'_, '_>) {
+        if block.stmts.is_empty() && block.tail.is_none() {
+            self.out.push_str("{}");
+            return;
+        }
+
+        self.out.push('{');
+        self.indent += 1;
+
+        for stmt in &block.stmts {
+            self.line();
+            self.print_hast(stmt);
+        }
+
+        if let Some(ref tail) = block.tail {
+            self.line();
+            self.stmt_expr(tail);
+        }
+
+        self.indent -= 1;
+        self.line();
+        self.out.push('}');
+    }
+
+    pub fn print_expr(&mut self, expr: &Expr<'_, '_>) {
+        self.expr(expr, Precedence::Closure)
+    }
+
+    /// prints an expression in a position that binds at least as tightly as `min_prec`
+    fn expr(&mut self, expr: &Expr<'_, '_>, min_prec: Precedence) {
+        let is_struct = matches!(expr, Expr::Struct(_));
+
+        if precedence(expr) < min_prec || (is_struct && !self.struct_literals) {
+            self.parens(expr);
+        } else {
+            self.expr_unparenthesized(expr);
+        }
+    }
+
+    fn parens(&mut self, expr: &Expr<'_, '_>) {
+        self.out.push('(');
+        self.with_struct_literals(true, |this| this.expr_unparenthesized(expr));
+        self.out.push(')');
+    }
+
+    fn expr_unparenthesized(&mut self, expr: &Expr<'_, '_>) {
+        match expr {
+            Expr::Literal(literal) => self.literal(literal),
+            Expr::Identifier(ident) => self.token(*ident),
+            Expr::Path(path) => self.path(path),
+            Expr::Prefix(op, operand) => {
+                self.token(*op);
+                self.expr(operand, Precedence::Prefix);
+            }
+            Expr::Postfix(operand, op) => {
+                self.expr(operand, Precedence::Primary);
+                self.token(*op);
+            }
+            Expr::Binary(left, op, right) | Expr::Logical(left, op, right) => {
+                let prec = precedence(expr);
+
+                self.expr(left, prec);
+                self.out.push(' ');
+                self.token(*op);
+                self.out.push(' ');
+                self.expr(right, prec.next());
+            }
+            Expr::Match(ast_match) => self.ast_match(ast_match),
+            Expr::If(ast_if) => self.ast_if(ast_if),
+            Expr::Block(block) => self.with_struct_literals(true, |this| this.block(block)),
+            Expr::Call(call) => {
+                self.expr(call.callee, Precedence::Primary);
+                self.args(&call.args);
+            }
+            Expr::Field(field) => {
+                self.expr(field.base, Precedence::Primary);
+                self.out.push('.');
+                self.token(field.ident);
+            }
+            Expr::TupleIndex(tuple_index) => {
+                // `1.0` would be lexed as a float
+                if let Expr::Literal(Literal::Integer(_)) = tuple_index.base {
+                    self.parens(tuple_index.base);
+                } else {
+                    self.expr(tuple_index.base, Precedence::Primary);
+                }
+
+                self.out.push('.');
+                self.token(tuple_index.index);
+            }
+            Expr::MethodCall(method_call) => {
+                self.expr(method_call.receiver, Precedence::Primary);
+                self.out.push('.');
+                self.token(method_call.method);
+
+                if let Some(ref generics) = method_call.generics {
+                    self.generic_args(generics);
+                }
+
+                self.args(&method_call.args);
+            }
+            Expr::Paren(paren) => self.parens(paren.expr),
+            Expr::Tuple(tuple) => {
+                self.with_struct_literals(true, |this| this.tuple(&tuple.items, Self::print_expr))
+            }
+            Expr::Array(array) => self.with_struct_literals(true, |this| {
+                this.out.push('[');
+                this.punctuated(&array.items, Self::print_expr);
+                this.out.push(']');
+            }),
+            Expr::Struct(ast_struct) => {
+                self.path(&ast_struct.name);
+
+                if ast_struct.fields.is_empty() {
+                    self.out.push_str(" {}");
+                    return;
+                }
+
+                self.out.push_str(" { ");
+                self.punctuated(&ast_struct.fields, |this, field| {
+                    this.token(field.ident);
+
+                    if let Some((_, ref value)) = field.value {
+                        this.out.push_str(": ");
+                        this.print_expr(value);
+                    }
+                });
+                self.out.push_str(" }");
+            }
+            Expr::Closure(closure) => self.closure(closure),
+            Expr::Turbofish(turbofish) => {
+                self.expr(turbofish.base, Precedence::Primary);
+                self.generic_args(&turbofish.args);
+            }
+        }
+    }
+
+    fn args(&mut self, args: &Punctuated<'_, Expr<'_, '_>>) {
+        self.out.push('(');
+        self.with_struct_literals(true, |this| this.punctuated(args, Self::print_expr));
+        self.out.push(')');
+    }
+
+    fn closure(&mut self, closure: &Closure<'_, '_>) {
+        match closure.sym_close {
+            // `||` would be lexed as a single token, which is a closure without `sym_close`
+            Some(_) if closure.params.is_empty() => self.out.push_str("| |"),
+            Some(_) => {
+                self.out.push('|');
+                self.punctuated(&closure.params, |this, param| {
+                    // `|` closes the parameter list, so or-patterns need parentheses
+                    if let Pattern::Or(_) = param.pattern {
+                        this.out.push('(');
+                        this.print_pattern(&param.pattern);
+                        this.out.push(')');
+                    } else {
+                        this.print_pattern(&param.pattern);
+                    }
+
+                    this.type_annotation(&param.ty);
+                });
+                self.out.push('|');
+            }
+            None => self.out.push_str("||"),
+        }
+
+        self.return_type(&closure.ret);
+        self.out.push(' ');
+
+        match (&closure.ret, &*closure.body) {
+            (Some(_), Expr::Block(block)) => {
+                self.with_struct_literals(true, |this| this.block(block))
+            }
+            _ => self.expr(closure.body, Precedence::Closure),
+        }
+    }
+
+    fn ast_match(&mut self, ast_match: &Match<'_, '_>) {
+        self.out.push_str("match ");
+        self.with_struct_literals(false, |this| this.print_expr(ast_match.scrutinee));
+        self.out.push(' ');
+
+        if ast_match.arms.is_empty() {
+            self.out.push_str("{}");
+            return;
+        }
+
+        self.out.push('{');
+        self.indent += 1;
+
+        for (i, arm) in ast_match.arms.iter().enumerate() {
+            self.line();
+            self.print_pattern(&arm.pattern);
+            self.out.push_str(" => ");
+            self.with_struct_literals(true, |this| this.print_expr(&arm.value));
+
+            let needs_comma = match ast_match.arms.get(i + 1) {
+                Some(next) => !arm.value.is_block_like() || starts_with_paren(&next.pattern),
+                None => false,
+            };
+
+            if arm.sym_comma.is_some() || needs_comma {
+                self.out.push(',');
+            }
+        }
+
+        self.indent -= 1;
+        self.line();
+        self.out.push('}');
+    }
+
+    fn ast_if(&mut self, ast_if: &If<'_, '_>) {
+        self.out.push_str("if ");
+        self.with_struct_literals(false, |this| this.print_expr(ast_if.condition));
+        self.out.push(' ');
+        self.with_struct_literals(true, |this| this.block(&ast_if.then_branch));
+
+        if let Some(ref else_branch) = ast_if.else_branch {
+            self.out.push_str(" else ");
+
+            self.with_struct_literals(true, |this| match else_branch.value {
+                Expr::If(ref ast_if) => this.ast_if(ast_if),
+                Expr::Block(ref block) => this.block(block),
+                ref value => this.print_expr(value),
+            });
+        }
+    }
+
+    pub fn print_pattern(&mut self, pattern: &Pattern<'_>) {
+        match pattern {
+            Pattern::Literal(literal) => self.literal(literal),
+            Pattern::Binding(binding) => {
+                if binding.kw_mut.is_some() {
+                    self.out.push_str("mut ");
+                }
+
+                self.token(binding.ident);
+            }
+            Pattern::Path(path) => self.path(path),
+            Pattern::Wildcard(_) => self.out.push('_'),
+            Pattern::Or(patterns) => {
+                for (i, pattern) in patterns.iter().enumerate() {
+                    if i != 0 {
+                        self.out.push_str(" | ");
+                    }
+
+                    // or-patterns are flattened when parsed
+                    if let Pattern::Or(_) = pattern {
+                        self.out.push('(');
+                        self.print_pattern(pattern);
+                        self.out.push(')');
+                    } else {
+                        self.print_pattern(pattern);
+                    }
+                }
+            }
+            Pattern::Tuple(tuple) => self.tuple(&tuple.items, Self::print_pattern),
+            Pattern::Array(array) => {
+                self.out.push('[');
+                self.punctuated(&array.items, Self::print_pattern);
+                self.out.push(']');
+            }
+            Pattern::Struct(ast_struct) => {
+                self.path(&ast_struct.name);
+
+                if ast_struct.fields.is_empty() {
+                    self.out.push_str(" {}");
+                    return;
+                }
+
+                self.out.push_str(" { ");
+                self.punctuated(&ast_struct.fields, |this, field| {
+                    this.token(field.ident);
+
+                    if let Some((_, ref pattern)) = field.pattern {
+                        this.out.push_str(": ");
+                        this.print_pattern(pattern);
+                    }
+                });
+                self.out.push_str(" }");
+            }
+            Pattern::TupleStruct(tuple_struct) => {
+                self.path(&tuple_struct.name);
+                self.out.push('(');
+                self.punctuated(&tuple_struct.items, Self::print_pattern);
+                self.out.push(')');
+            }
+        }
+    }
+
+    pub fn print_type(&mut self, ty: &TypeExpr<'_, '_>) {
+        match ty {
+            TypeExpr::Named(named) => {
+                self.path(&named.path);
+
+                if let Some(ref args) = named.args {
+                    self.generic_args(args);
+                }
+            }
+            TypeExpr::Tuple(tuple) => self.tuple(&tuple.items, Self::print_type),
+            TypeExpr::Function(function) => {
+                self.out.push_str("fn(");
+                self.punctuated(&function.params, Self::print_type);
+                self.out.push(')');
+                self.return_type(&function.ret);
+            }
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use lib_lexer_types::Token;
+
+/// A sequence of `T` separated by punctuation tokens
+///
+/// There is either one separator between each pair of items, or one separator
+/// after every item if the sequence has a trailing separator
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Punctuated<'input, T> {
+    pub items: Vec<T>,
+    pub separators: Vec<Token<'input>>,
+}
+
+impl<T> Default for Punctuated<'_, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'input, T> Punctuated<'input, T> {
+    pub fn new() -> Self {
+        Self {
+            items: Vec::new(),
+            separators: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn has_trailing(&self) -> bool {
+        !self.items.is_empty() && self.items.len() == self.separators.len()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.items.iter()
+    }
+
+    /// maps every item, keeping the separators
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Punctuated<'input, U> {
+        Punctuated {
+            items: self.items.iter().map(f).collect(),
+            separators: self.separators.clone(),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.items.iter_mut()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Punctuated<'_, T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut Punctuated<'_, T> {
+    type Item = &'a mut T;
+    type IntoIter = std::slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
@@ -0,0 +1,231 @@
+use super::{
+    Assign, Attribute, Block, Enum, Expr, ExprStmt, Fields, Function, HAst, If, Let, Literal, Mod,
+    NamedType, Path, Pattern, Struct, TypeAlias, TypeExpr, Use,
+};
+use lib_lexer_types::{Span, Token};
+
+/// a node that covers a contiguous region of the source
+///
+/// the span of a node runs from the start of its first token to the end of
+/// its last token
+pub trait Spanned {
+    fn span(&self) -> Span;
+}
+
+impl Spanned for Token<'_> {
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
+/// extends `span` to cover the attributes before an item
+fn with_attrs(attrs: &[Attribute<'_>], span: Span) -> Span {
+    match attrs.first() {
+        Some(attr) => attr.span().merge(span),
+        None => span,
+    }
+}
+
+impl Spanned for HAst<'_, '_> {
+    fn span(&self) -> Span {
+        match self {
+            HAst::Let(ast_let) => ast_let.span(),
+            HAst::Assign(assign) => assign.span(),
+            HAst::TypeAlias(type_alias) => type_alias.span(),
+            HAst::Function(function) => function.span(),
+            HAst::Struct(ast_struct) => ast_struct.span(),
+            HAst::Enum(ast_enum) => ast_enum.span(),
+            HAst::Mod(ast_mod) => ast_mod.span(),
+            HAst::Use(ast_use) => ast_use.span(),
+            HAst::Expr(stmt) => stmt.span(),
+        }
+    }
+}
+
+impl Spanned for Attribute<'_> {
+    fn span(&self) -> Span {
+        self.sym_pound.span.merge(self.sym_close.span)
+    }
+}
+
+impl Spanned for Let<'_, '_> {
+    fn span(&self) -> Span {
+        with_attrs(&self.attrs, self.kw_let.span.merge(self.sym_semi.span))
+    }
+}
+
+impl Spanned for Assign<'_, '_> {
+    fn span(&self) -> Span {
+        self.ident.span.merge(self.sym_semi.span)
+    }
+}
+
+impl Spanned for TypeAlias<'_, '_> {
+    fn span(&self) -> Span {
+        with_attrs(&self.attrs, self.kw_type.span.merge(self.sym_semi.span))
+    }
+}
+
+impl Spanned for Function<'_, '_> {
+    fn span(&self) -> Span {
+        with_attrs(&self.attrs, self.kw_fn.span.merge(self.body.span()))
+    }
+}
+
+impl Spanned for Struct<'_, '_> {
+    fn span(&self) -> Span {
+        let end = match (&self.fields, self.sym_semi) {
+            (_, Some(sym_semi)) => sym_semi.span,
+            (Fields::Named(fields), None) => fields.sym_close.span,
+            (Fields::Tuple(fields), None) => fields.sym_close.span,
+            (Fields::Unit, None) => self.ident.span,
+        };
+
+        with_attrs(&self.attrs, self.kw_struct.span.merge(end))
+    }
+}
+
+impl Spanned for Enum<'_, '_> {
+    fn span(&self) -> Span {
+        with_attrs(&self.attrs, self.kw_enum.span.merge(self.sym_close.span))
+    }
+}
+
+impl Spanned for Mod<'_> {
+    fn span(&self) -> Span {
+        self.kw_mod.span.merge(self.sym_semi.span)
+    }
+}
+
+impl Spanned for Use<'_> {
+    fn span(&self) -> Span {
+        self.kw_use.span.merge(self.sym_semi.span)
+    }
+}
+
+impl Spanned for Path<'_> {
+    fn span(&self) -> Span {
+        self.first().span.merge(self.last().span)
+    }
+}
+
+impl Spanned for ExprStmt<'_, '_> {
+    fn span(&self) -> Span {
+        match self.sym_semi {
+            Some(sym_semi) => self.expr.span().merge(sym_semi.span),
+            None => self.expr.span(),
+        }
+    }
+}
+
+impl Spanned for Literal<'_> {
+    fn span(&self) -> Span {
+        match self {
+            Literal::Integer(token)
+            | Literal::Float(token)
+            | Literal::String(token)
+            | Literal::Bool(token) => token.span,
+        }
+    }
+}
+
+impl Spanned for Block<'_, '_> {
+    fn span(&self) -> Span {
+        self.sym_open.span.merge(self.sym_close.span)
+    }
+}
+
+impl Spanned for If<'_, '_> {
+    fn span(&self) -> Span {
+        let end = match self.else_branch {
+            Some(ref else_branch) => else_branch.value.span(),
+            None => self.then_branch.span(),
+        };
+
+        self.kw_if.span.merge(end)
+    }
+}
+
+impl Spanned for Expr<'_, '_> {
+    fn span(&self) -> Span {
+        match self {
+            Expr::Literal(literal) => literal.span(),
+            Expr::Identifier(ident) => ident.span,
+            Expr::Path(path) => path.span(),
+            Expr::Prefix(op, expr) => op.span.merge(expr.span()),
+            Expr::Postfix(expr, op) => expr.span().merge(op.span),
+            Expr::Binary(left, _, right) | Expr::Logical(left, _, right) => {
+                left.span().merge(right.span())
+            }
+            Expr::Match(ast_match) => ast_match.kw_match.span.merge(ast_match.sym_close.span),
+            Expr::If(ast_if) => ast_if.span(),
+            Expr::Block(block) => block.span(),
+            Expr::Call(call) => call.callee.span().merge(call.sym_close.span),
+            Expr::Field(field) => field.base.span().merge(field.ident.span),
+            Expr::TupleIndex(tuple_index) => tuple_index.base.span().merge(tuple_index.index.span),
+            Expr::MethodCall(method_call) => method_call
+                .receiver
+                .span()
+                .merge(method_call.sym_close.span),
+            Expr::Paren(paren) => paren.sym_open.span.merge(paren.sym_close.span),
+            Expr::Tuple(tuple) => tuple.sym_open.span.merge(tuple.sym_close.span),
+            Expr::Array(array) => array.sym_open.span.merge(array.sym_close.span),
+            Expr::Struct(ast_struct) => ast_struct.name.span().merge(ast_struct.sym_close.span),
+            Expr::Closure(closure) => closure.sym_open.span.merge(closure.body.span()),
+            Expr::Turbofish(turbofish) => {
+                turbofish.base.span().merge(turbofish.args.sym_close.span)
+            }
+        }
+    }
+}
+
+impl Spanned for Pattern<'_> {
+    fn span(&self) -> Span {
+        match self {
+            Pattern::Literal(literal) => literal.span(),
+            Pattern::Binding(binding) => match binding.kw_mut {
+                Some(kw_mut) => kw_mut.span.merge(binding.ident.span),
+                None => binding.ident.span,
+            },
+            Pattern::Path(path) => path.span(),
+            Pattern::Wildcard(token) => token.span,
+            Pattern::Or(patterns) => {
+                let first = patterns.items.first().map(Pattern::span);
+                let last = patterns.items.last().map(Pattern::span);
+
+                match (first, last) {
+                    (Some(first), Some(last)) => first.merge(last),
+                    _ => unreachable!("or-patterns have at least two patterns"),
+                }
+            }
+            Pattern::Tuple(tuple) => tuple.sym_open.span.merge(tuple.sym_close.span),
+            Pattern::Array(array) => array.sym_open.span.merge(array.sym_close.span),
+            Pattern::Struct(ast_struct) => ast_struct.name.span().merge(ast_struct.sym_close.span),
+            Pattern::TupleStruct(tuple_struct) => {
+                tuple_struct.name.span().merge(tuple_struct.sym_close.span)
+            }
+        }
+    }
+}
+
+impl Spanned for NamedType<'_, '_> {
+    fn span(&self) -> Span {
+        match self.args {
+            Some(ref args) => self.path.span().merge(args.sym_close.span),
+            None => self.path.span(),
+        }
+    }
+}
+
+impl Spanned for TypeExpr<'_, '_> {
+    fn span(&self) -> Span {
+        match self {
+            TypeExpr::Named(named) => named.span(),
+            TypeExpr::Tuple(tuple) => tuple.sym_open.span.merge(tuple.sym_close.span),
+            TypeExpr::Function(function) => match function.ret {
+                Some(ref ret) => function.kw_fn.span.merge(ret.ty.span()),
+                None => function.kw_fn.span.merge(function.sym_close.span),
+            },
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use super::{Path, Punctuated};
+use lib_lexer_types::Token;
+
+pub type TypeExprPtr<'input, 'hacx> = &'hacx mut TypeExpr<'input, 'hacx>;
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum TypeExpr<'input, 'hacx> {
+    Named(NamedType<'input, 'hacx>),
+    Tuple(TupleType<'input, 'hacx>),
+    Function(FunctionType<'input, 'hacx>),
+}
+
+/// `path::to::Name` or `path::to::Name<A, B, ...>`
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct NamedType<'input, 'hacx> {
+    pub path: Path<'input>,
+    pub args: Option<GenericArgs<'input, 'hacx>>,
+}
+
+/// `(A, B, ...)`, where `()` is the unit type
+///
+/// a single type in parentheses without a trailing comma is not a tuple,
+/// and is parsed as the inner type
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TupleType<'input, 'hacx> {
+    pub sym_open: Token<'input>,
+    pub items: Punctuated<'input, TypeExpr<'input, 'hacx>>,
+    pub sym_close: Token<'input>,
+}
+
+/// `fn(A, B, ...) -> R`, where the return type defaults to `()`
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FunctionType<'input, 'hacx> {
+    pub kw_fn: Token<'input>,
+    pub sym_open: Token<'input>,
+    pub params: Punctuated<'input, TypeExpr<'input, 'hacx>>,
+    pub sym_close: Token<'input>,
+    pub ret: Option<ReturnType<'input, 'hacx>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ReturnType<'input, 'hacx> {
+    pub sym_arrow: Token<'input>,
+    pub ty: TypeExprPtr<'input, 'hacx>,
+}
+
+/// `: T`, as found after the binding in a `let`
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TypeAnnotation<'input, 'hacx> {
+    pub sym_colon: Token<'input>,
+    pub ty: TypeExpr<'input, 'hacx>,
+}
+
+/// `<T, U, ...>`, as found after the name of a generic item
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct GenericParams<'input> {
+    pub sym_open: Token<'input>,
+    pub params: Punctuated<'input, Token<'input>>,
+    pub sym_close: Token<'input>,
+}
+
+/// `<A, B, ...>` in types, or `::<A, B, ...>` in expressions
+///
+/// In types a `<` always starts generic arguments, but in expressions `<` is
+/// always the less than operator, so `f<T>(x)` is parsed as `(f < T) > (x)`.
+/// Generic arguments in expressions must be written with a leading `::`
+/// (the turbofish), which is unambiguous, and `sym_path_sep` is that `::`.
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct GenericArgs<'input, 'hacx> {
+    pub sym_path_sep: Option<Token<'input>>,
+    pub sym_open: Token<'input>,
+    pub args: Punctuated<'input, TypeExpr<'input, 'hacx>>,
+    pub sym_close: Token<'input>,
+}
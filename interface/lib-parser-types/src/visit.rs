@@ -0,0 +1,3 @@
+//! traversal of the AST by shared reference
+
+visitor! { Visitor }
@@ -0,0 +1,3 @@
+//! traversal of the AST by unique reference, which allows rewriting nodes in place
+
+visitor! { VisitorMut, mut }
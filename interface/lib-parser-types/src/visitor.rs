@@ -0,0 +1,715 @@
+/// generates a visitor trait and its `walk_*` functions, which take the
+/// nodes by shared reference, or by unique reference if `mut` is given
+macro_rules! visitor {
+    ($Visitor:ident $(, $mut:tt)?) => {
+        use crate::{
+            ArrayExpr, ArrayPattern, Assign, Attribute, Binding, Block, Call, Closure, ClosureParam,
+            Enum, Expr, ExprStmt, Field, FieldDef, FieldInit, FieldPattern, Fields, Function,
+            FunctionType, GenericArgs, GenericParams, HAst, If, Let, Literal, Match, MatchArm,
+            MethodCall, Mod, NamedType, Param, ParenExpr, Path, Pattern, ReturnType, Struct,
+            StructExpr, StructPattern, TupleExpr, TupleIndex, TuplePattern, TupleStructPattern,
+            TupleType, Turbofish, TypeAlias, TypeAnnotation, TypeExpr, Use, Variant,
+        };
+
+        /// every method defaults to the `walk_*` function of the same name, which
+        /// visits the children of the node, so an override that still wants to
+        /// visit the children must call that function itself
+        pub trait $Visitor<'input, 'hacx> {
+            fn visit_hast(&mut self, ast: &$($mut)? HAst<'input, 'hacx>) {
+                walk_hast(self, ast)
+            }
+
+            fn visit_attribute(&mut self, attr: &$($mut)? Attribute<'input>) {
+                walk_attribute(self, attr)
+            }
+
+            fn visit_let(&mut self, ast_let: &$($mut)? Let<'input, 'hacx>) {
+                walk_let(self, ast_let)
+            }
+
+            fn visit_assign(&mut self, assign: &$($mut)? Assign<'input, 'hacx>) {
+                walk_assign(self, assign)
+            }
+
+            fn visit_type_alias(&mut self, type_alias: &$($mut)? TypeAlias<'input, 'hacx>) {
+                walk_type_alias(self, type_alias)
+            }
+
+            fn visit_function(&mut self, function: &$($mut)? Function<'input, 'hacx>) {
+                walk_function(self, function)
+            }
+
+            fn visit_param(&mut self, param: &$($mut)? Param<'input, 'hacx>) {
+                walk_param(self, param)
+            }
+
+            fn visit_struct(&mut self, ast_struct: &$($mut)? Struct<'input, 'hacx>) {
+                walk_struct(self, ast_struct)
+            }
+
+            fn visit_enum(&mut self, ast_enum: &$($mut)? Enum<'input, 'hacx>) {
+                walk_enum(self, ast_enum)
+            }
+
+            fn visit_variant(&mut self, variant: &$($mut)? Variant<'input, 'hacx>) {
+                walk_variant(self, variant)
+            }
+
+            fn visit_fields(&mut self, fields: &$($mut)? Fields<'input, 'hacx>) {
+                walk_fields(self, fields)
+            }
+
+            fn visit_field_def(&mut self, field: &$($mut)? FieldDef<'input, 'hacx>) {
+                walk_field_def(self, field)
+            }
+
+            fn visit_mod(&mut self, _ast_mod: &$($mut)? Mod<'input>) {}
+
+            fn visit_use(&mut self, ast_use: &$($mut)? Use<'input>) {
+                walk_use(self, ast_use)
+            }
+
+            fn visit_path(&mut self, _path: &$($mut)? Path<'input>) {}
+
+            fn visit_expr_stmt(&mut self, stmt: &$($mut)? ExprStmt<'input, 'hacx>) {
+                walk_expr_stmt(self, stmt)
+            }
+
+            fn visit_expr(&mut self, expr: &$($mut)? Expr<'input, 'hacx>) {
+                walk_expr(self, expr)
+            }
+
+            fn visit_literal(&mut self, _literal: &$($mut)? Literal<'input>) {}
+
+            /// a name used as an expression
+            fn visit_identifier(&mut self, _ident: &$($mut)? lib_lexer_types::Token<'input>) {}
+
+            fn visit_match(&mut self, ast_match: &$($mut)? Match<'input, 'hacx>) {
+                walk_match(self, ast_match)
+            }
+
+            fn visit_match_arm(&mut self, arm: &$($mut)? MatchArm<'input, 'hacx>) {
+                walk_match_arm(self, arm)
+            }
+
+            fn visit_if(&mut self, ast_if: &$($mut)? If<'input, 'hacx>) {
+                walk_if(self, ast_if)
+            }
+
+            fn visit_block(&mut self, block: &$($mut)? Block<'input, 'hacx>) {
+                walk_block(self, block)
+            }
+
+            fn visit_call(&mut self, call: &$($mut)? Call<'input, 'hacx>) {
+                walk_call(self, call)
+            }
+
+            fn visit_field(&mut self, field: &$($mut)? Field<'input, 'hacx>) {
+                walk_field(self, field)
+            }
+
+            fn visit_tuple_index(&mut self, tuple_index: &$($mut)? TupleIndex<'input, 'hacx>) {
+                walk_tuple_index(self, tuple_index)
+            }
+
+            fn visit_method_call(&mut self, method_call: &$($mut)? MethodCall<'input, 'hacx>) {
+                walk_method_call(self, method_call)
+            }
+
+            fn visit_paren_expr(&mut self, paren: &$($mut)? ParenExpr<'input, 'hacx>) {
+                walk_paren_expr(self, paren)
+            }
+
+            fn visit_tuple_expr(&mut self, tuple: &$($mut)? TupleExpr<'input, 'hacx>) {
+                walk_tuple_expr(self, tuple)
+            }
+
+            fn visit_array_expr(&mut self, array: &$($mut)? ArrayExpr<'input, 'hacx>) {
+                walk_array_expr(self, array)
+            }
+
+            fn visit_struct_expr(&mut self, ast_struct: &$($mut)? StructExpr<'input, 'hacx>) {
+                walk_struct_expr(self, ast_struct)
+            }
+
+            fn visit_field_init(&mut self, field: &$($mut)? FieldInit<'input, 'hacx>) {
+                walk_field_init(self, field)
+            }
+
+            fn visit_closure(&mut self, closure: &$($mut)? Closure<'input, 'hacx>) {
+                walk_closure(self, closure)
+            }
+
+            fn visit_closure_param(&mut self, param: &$($mut)? ClosureParam<'input, 'hacx>) {
+                walk_closure_param(self, param)
+            }
+
+            fn visit_turbofish(&mut self, turbofish: &$($mut)? Turbofish<'input, 'hacx>) {
+                walk_turbofish(self, turbofish)
+            }
+
+            fn visit_pattern(&mut self, pattern: &$($mut)? Pattern<'input>) {
+                walk_pattern(self, pattern)
+            }
+
+            /// a name bound by a pattern
+            fn visit_binding(&mut self, _binding: &$($mut)? Binding<'input>) {}
+
+            fn visit_tuple_pattern(&mut self, tuple: &$($mut)? TuplePattern<'input>) {
+                walk_tuple_pattern(self, tuple)
+            }
+
+            fn visit_array_pattern(&mut self, array: &$($mut)? ArrayPattern<'input>) {
+                walk_array_pattern(self, array)
+            }
+
+            fn visit_struct_pattern(&mut self, ast_struct: &$($mut)? StructPattern<'input>) {
+                walk_struct_pattern(self, ast_struct)
+            }
+
+            fn visit_field_pattern(&mut self, field: &$($mut)? FieldPattern<'input>) {
+                walk_field_pattern(self, field)
+            }
+
+            fn visit_tuple_struct_pattern(
+                &mut self,
+                tuple_struct: &$($mut)? TupleStructPattern<'input>,
+            ) {
+                walk_tuple_struct_pattern(self, tuple_struct)
+            }
+
+            fn visit_type(&mut self, ty: &$($mut)? TypeExpr<'input, 'hacx>) {
+                walk_type(self, ty)
+            }
+
+            fn visit_named_type(&mut self, named: &$($mut)? NamedType<'input, 'hacx>) {
+                walk_named_type(self, named)
+            }
+
+            fn visit_tuple_type(&mut self, tuple: &$($mut)? TupleType<'input, 'hacx>) {
+                walk_tuple_type(self, tuple)
+            }
+
+            fn visit_function_type(&mut self, function: &$($mut)? FunctionType<'input, 'hacx>) {
+                walk_function_type(self, function)
+            }
+
+            fn visit_return_type(&mut self, ret: &$($mut)? ReturnType<'input, 'hacx>) {
+                walk_return_type(self, ret)
+            }
+
+            fn visit_type_annotation(&mut self, ty: &$($mut)? TypeAnnotation<'input, 'hacx>) {
+                walk_type_annotation(self, ty)
+            }
+
+            fn visit_generic_params(&mut self, _generics: &$($mut)? GenericParams<'input>) {}
+
+            fn visit_generic_args(&mut self, args: &$($mut)? GenericArgs<'input, 'hacx>) {
+                walk_generic_args(self, args)
+            }
+        }
+
+        pub fn walk_hast<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            ast: &$($mut)? HAst<'input, 'hacx>,
+        ) {
+            match ast {
+                HAst::Let(ast_let) => visitor.visit_let(ast_let),
+                HAst::Assign(assign) => visitor.visit_assign(assign),
+                HAst::TypeAlias(type_alias) => visitor.visit_type_alias(type_alias),
+                HAst::Function(function) => visitor.visit_function(function),
+                HAst::Struct(ast_struct) => visitor.visit_struct(ast_struct),
+                HAst::Enum(ast_enum) => visitor.visit_enum(ast_enum),
+                HAst::Mod(ast_mod) => visitor.visit_mod(ast_mod),
+                HAst::Use(ast_use) => visitor.visit_use(ast_use),
+                HAst::Expr(stmt) => visitor.visit_expr_stmt(stmt),
+            }
+        }
+
+        pub fn walk_attribute<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            attr: &$($mut)? Attribute<'input>,
+        ) {
+            visitor.visit_path(&$($mut)? attr.name);
+        }
+
+        pub fn walk_let<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            ast_let: &$($mut)? Let<'input, 'hacx>,
+        ) {
+            for attr in &$($mut)? ast_let.attrs {
+                visitor.visit_attribute(attr);
+            }
+
+            visitor.visit_pattern(&$($mut)? ast_let.pattern);
+
+            if let Some(ty) = &$($mut)? ast_let.ty {
+                visitor.visit_type_annotation(ty);
+            }
+
+            visitor.visit_expr(&$($mut)? ast_let.value);
+        }
+
+        pub fn walk_assign<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            assign: &$($mut)? Assign<'input, 'hacx>,
+        ) {
+            visitor.visit_expr(&$($mut)? assign.value);
+        }
+
+        pub fn walk_type_alias<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            type_alias: &$($mut)? TypeAlias<'input, 'hacx>,
+        ) {
+            for attr in &$($mut)? type_alias.attrs {
+                visitor.visit_attribute(attr);
+            }
+
+            if let Some(generics) = &$($mut)? type_alias.generics {
+                visitor.visit_generic_params(generics);
+            }
+
+            visitor.visit_type(&$($mut)? type_alias.ty);
+        }
+
+        pub fn walk_function<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            function: &$($mut)? Function<'input, 'hacx>,
+        ) {
+            for attr in &$($mut)? function.attrs {
+                visitor.visit_attribute(attr);
+            }
+
+            if let Some(generics) = &$($mut)? function.generics {
+                visitor.visit_generic_params(generics);
+            }
+
+            for param in &$($mut)? function.params {
+                visitor.visit_param(param);
+            }
+
+            if let Some(ret) = &$($mut)? function.ret {
+                visitor.visit_return_type(ret);
+            }
+
+            visitor.visit_block(&$($mut)? function.body);
+        }
+
+        pub fn walk_param<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            param: &$($mut)? Param<'input, 'hacx>,
+        ) {
+            visitor.visit_type(&$($mut)? param.ty);
+        }
+
+        pub fn walk_struct<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            ast_struct: &$($mut)? Struct<'input, 'hacx>,
+        ) {
+            for attr in &$($mut)? ast_struct.attrs {
+                visitor.visit_attribute(attr);
+            }
+
+            if let Some(generics) = &$($mut)? ast_struct.generics {
+                visitor.visit_generic_params(generics);
+            }
+
+            visitor.visit_fields(&$($mut)? ast_struct.fields);
+        }
+
+        pub fn walk_enum<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            ast_enum: &$($mut)? Enum<'input, 'hacx>,
+        ) {
+            for attr in &$($mut)? ast_enum.attrs {
+                visitor.visit_attribute(attr);
+            }
+
+            if let Some(generics) = &$($mut)? ast_enum.generics {
+                visitor.visit_generic_params(generics);
+            }
+
+            for variant in &$($mut)? ast_enum.variants {
+                visitor.visit_variant(variant);
+            }
+        }
+
+        pub fn walk_variant<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            variant: &$($mut)? Variant<'input, 'hacx>,
+        ) {
+            visitor.visit_fields(&$($mut)? variant.fields);
+        }
+
+        pub fn walk_fields<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            fields: &$($mut)? Fields<'input, 'hacx>,
+        ) {
+            match fields {
+                Fields::Named(named) => {
+                    for field in &$($mut)? named.fields {
+                        visitor.visit_field_def(field);
+                    }
+                }
+                Fields::Tuple(tuple) => {
+                    for ty in &$($mut)? tuple.fields {
+                        visitor.visit_type(ty);
+                    }
+                }
+                Fields::Unit => (),
+            }
+        }
+
+        pub fn walk_field_def<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            field: &$($mut)? FieldDef<'input, 'hacx>,
+        ) {
+            visitor.visit_type(&$($mut)? field.ty);
+        }
+
+        pub fn walk_use<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            ast_use: &$($mut)? Use<'input>,
+        ) {
+            visitor.visit_path(&$($mut)? ast_use.path);
+        }
+
+        pub fn walk_expr_stmt<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            stmt: &$($mut)? ExprStmt<'input, 'hacx>,
+        ) {
+            visitor.visit_expr(&$($mut)? stmt.expr);
+        }
+
+        pub fn walk_expr<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            expr: &$($mut)? Expr<'input, 'hacx>,
+        ) {
+            match expr {
+                Expr::Literal(literal) => visitor.visit_literal(literal),
+                Expr::Identifier(ident) => visitor.visit_identifier(ident),
+                Expr::Path(path) => visitor.visit_path(path),
+                Expr::Prefix(_, expr) | Expr::Postfix(expr, _) => visitor.visit_expr(expr),
+                Expr::Binary(left, _, right) | Expr::Logical(left, _, right) => {
+                    visitor.visit_expr(left);
+                    visitor.visit_expr(right);
+                }
+                Expr::Match(ast_match) => visitor.visit_match(ast_match),
+                Expr::If(ast_if) => visitor.visit_if(ast_if),
+                Expr::Block(block) => visitor.visit_block(block),
+                Expr::Call(call) => visitor.visit_call(call),
+                Expr::Field(field) => visitor.visit_field(field),
+                Expr::TupleIndex(tuple_index) => visitor.visit_tuple_index(tuple_index),
+                Expr::MethodCall(method_call) => visitor.visit_method_call(method_call),
+                Expr::Paren(paren) => visitor.visit_paren_expr(paren),
+                Expr::Tuple(tuple) => visitor.visit_tuple_expr(tuple),
+                Expr::Array(array) => visitor.visit_array_expr(array),
+                Expr::Struct(ast_struct) => visitor.visit_struct_expr(ast_struct),
+                Expr::Closure(closure) => visitor.visit_closure(closure),
+                Expr::Turbofish(turbofish) => visitor.visit_turbofish(turbofish),
+            }
+        }
+
+        pub fn walk_match<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            ast_match: &$($mut)? Match<'input, 'hacx>,
+        ) {
+            visitor.visit_expr(&$($mut)? ast_match.scrutinee);
+
+            for arm in &$($mut)? ast_match.arms {
+                visitor.visit_match_arm(arm);
+            }
+        }
+
+        pub fn walk_match_arm<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            arm: &$($mut)? MatchArm<'input, 'hacx>,
+        ) {
+            visitor.visit_pattern(&$($mut)? arm.pattern);
+            visitor.visit_expr(&$($mut)? arm.value);
+        }
+
+        pub fn walk_if<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            ast_if: &$($mut)? If<'input, 'hacx>,
+        ) {
+            visitor.visit_expr(&$($mut)? ast_if.condition);
+            visitor.visit_block(&$($mut)? ast_if.then_branch);
+
+            if let Some(else_branch) = &$($mut)? ast_if.else_branch {
+                visitor.visit_expr(&$($mut)? else_branch.value);
+            }
+        }
+
+        pub fn walk_block<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            block: &$($mut)? Block<'input, 'hacx>,
+        ) {
+            for stmt in &$($mut)? block.stmts {
+                visitor.visit_hast(stmt);
+            }
+
+            if let Some(tail) = &$($mut)? block.tail {
+                visitor.visit_expr(tail);
+            }
+        }
+
+        pub fn walk_call<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            call: &$($mut)? Call<'input, 'hacx>,
+        ) {
+            visitor.visit_expr(&$($mut)? call.callee);
+
+            for arg in &$($mut)? call.args {
+                visitor.visit_expr(arg);
+            }
+        }
+
+        pub fn walk_field<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            field: &$($mut)? Field<'input, 'hacx>,
+        ) {
+            visitor.visit_expr(&$($mut)? field.base);
+        }
+
+        pub fn walk_tuple_index<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            tuple_index: &$($mut)? TupleIndex<'input, 'hacx>,
+        ) {
+            visitor.visit_expr(&$($mut)? tuple_index.base);
+        }
+
+        pub fn walk_method_call<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            method_call: &$($mut)? MethodCall<'input, 'hacx>,
+        ) {
+            visitor.visit_expr(&$($mut)? method_call.receiver);
+
+            if let Some(generics) = &$($mut)? method_call.generics {
+                visitor.visit_generic_args(generics);
+            }
+
+            for arg in &$($mut)? method_call.args {
+                visitor.visit_expr(arg);
+            }
+        }
+
+        pub fn walk_paren_expr<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            paren: &$($mut)? ParenExpr<'input, 'hacx>,
+        ) {
+            visitor.visit_expr(&$($mut)? paren.expr);
+        }
+
+        pub fn walk_tuple_expr<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            tuple: &$($mut)? TupleExpr<'input, 'hacx>,
+        ) {
+            for item in &$($mut)? tuple.items {
+                visitor.visit_expr(item);
+            }
+        }
+
+        pub fn walk_array_expr<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            array: &$($mut)? ArrayExpr<'input, 'hacx>,
+        ) {
+            for item in &$($mut)? array.items {
+                visitor.visit_expr(item);
+            }
+        }
+
+        pub fn walk_struct_expr<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            ast_struct: &$($mut)? StructExpr<'input, 'hacx>,
+        ) {
+            visitor.visit_path(&$($mut)? ast_struct.name);
+
+            for field in &$($mut)? ast_struct.fields {
+                visitor.visit_field_init(field);
+            }
+        }
+
+        pub fn walk_field_init<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            field: &$($mut)? FieldInit<'input, 'hacx>,
+        ) {
+            if let Some((_, value)) = &$($mut)? field.value {
+                visitor.visit_expr(value);
+            }
+        }
+
+        pub fn walk_closure<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            closure: &$($mut)? Closure<'input, 'hacx>,
+        ) {
+            for param in &$($mut)? closure.params {
+                visitor.visit_closure_param(param);
+            }
+
+            if let Some(ret) = &$($mut)? closure.ret {
+                visitor.visit_return_type(ret);
+            }
+
+            visitor.visit_expr(&$($mut)? closure.body);
+        }
+
+        pub fn walk_closure_param<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            param: &$($mut)? ClosureParam<'input, 'hacx>,
+        ) {
+            visitor.visit_pattern(&$($mut)? param.pattern);
+
+            if let Some(ty) = &$($mut)? param.ty {
+                visitor.visit_type_annotation(ty);
+            }
+        }
+
+        pub fn walk_turbofish<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            turbofish: &$($mut)? Turbofish<'input, 'hacx>,
+        ) {
+            visitor.visit_expr(&$($mut)? turbofish.base);
+            visitor.visit_generic_args(&$($mut)? turbofish.args);
+        }
+
+        pub fn walk_pattern<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            pattern: &$($mut)? Pattern<'input>,
+        ) {
+            match pattern {
+                Pattern::Literal(literal) => visitor.visit_literal(literal),
+                Pattern::Binding(binding) => visitor.visit_binding(binding),
+                Pattern::Wildcard(_) => (),
+                Pattern::Path(path) => visitor.visit_path(path),
+                Pattern::Or(patterns) => {
+                    for pattern in patterns {
+                        visitor.visit_pattern(pattern);
+                    }
+                }
+                Pattern::Tuple(tuple) => visitor.visit_tuple_pattern(tuple),
+                Pattern::Array(array) => visitor.visit_array_pattern(array),
+                Pattern::Struct(ast_struct) => visitor.visit_struct_pattern(ast_struct),
+                Pattern::TupleStruct(tuple_struct) => {
+                    visitor.visit_tuple_struct_pattern(tuple_struct)
+                }
+            }
+        }
+
+        pub fn walk_tuple_pattern<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            tuple: &$($mut)? TuplePattern<'input>,
+        ) {
+            for item in &$($mut)? tuple.items {
+                visitor.visit_pattern(item);
+            }
+        }
+
+        pub fn walk_array_pattern<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            array: &$($mut)? ArrayPattern<'input>,
+        ) {
+            for item in &$($mut)? array.items {
+                visitor.visit_pattern(item);
+            }
+        }
+
+        pub fn walk_struct_pattern<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            ast_struct: &$($mut)? StructPattern<'input>,
+        ) {
+            visitor.visit_path(&$($mut)? ast_struct.name);
+
+            for field in &$($mut)? ast_struct.fields {
+                visitor.visit_field_pattern(field);
+            }
+        }
+
+        pub fn walk_field_pattern<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            field: &$($mut)? FieldPattern<'input>,
+        ) {
+            if let Some((_, pattern)) = &$($mut)? field.pattern {
+                visitor.visit_pattern(pattern);
+            }
+        }
+
+        pub fn walk_tuple_struct_pattern<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            tuple_struct: &$($mut)? TupleStructPattern<'input>,
+        ) {
+            visitor.visit_path(&$($mut)? tuple_struct.name);
+
+            for item in &$($mut)? tuple_struct.items {
+                visitor.visit_pattern(item);
+            }
+        }
+
+        pub fn walk_type<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            ty: &$($mut)? TypeExpr<'input, 'hacx>,
+        ) {
+            match ty {
+                TypeExpr::Named(named) => visitor.visit_named_type(named),
+                TypeExpr::Tuple(tuple) => visitor.visit_tuple_type(tuple),
+                TypeExpr::Function(function) => visitor.visit_function_type(function),
+            }
+        }
+
+        pub fn walk_named_type<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            named: &$($mut)? NamedType<'input, 'hacx>,
+        ) {
+            visitor.visit_path(&$($mut)? named.path);
+
+            if let Some(args) = &$($mut)? named.args {
+                visitor.visit_generic_args(args);
+            }
+        }
+
+        pub fn walk_tuple_type<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            tuple: &$($mut)? TupleType<'input, 'hacx>,
+        ) {
+            for item in &$($mut)? tuple.items {
+                visitor.visit_type(item);
+            }
+        }
+
+        pub fn walk_function_type<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            function: &$($mut)? FunctionType<'input, 'hacx>,
+        ) {
+            for param in &$($mut)? function.params {
+                visitor.visit_type(param);
+            }
+
+            if let Some(ret) = &$($mut)? function.ret {
+                visitor.visit_return_type(ret);
+            }
+        }
+
+        pub fn walk_return_type<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            ret: &$($mut)? ReturnType<'input, 'hacx>,
+        ) {
+            visitor.visit_type(&$($mut)? ret.ty);
+        }
+
+        pub fn walk_type_annotation<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            ty: &$($mut)? TypeAnnotation<'input, 'hacx>,
+        ) {
+            visitor.visit_type(&$($mut)? ty.ty);
+        }
+
+        pub fn walk_generic_args<'input, 'hacx, V: $Visitor<'input, 'hacx> + ?Sized>(
+            visitor: &mut V,
+            args: &$($mut)? GenericArgs<'input, 'hacx>,
+        ) {
+            for arg in &$($mut)? args.args {
+                visitor.visit_type(arg);
+            }
+        }
+    };
+}
@@ -36,6 +36,15 @@ pub fn intern(value: &str) -> ThinStr {
 
 #[cold]
 fn intern_slow(interner: &Interner, value: &str) -> ThinStr {
+    let mut data = interner.write();
+
+    // another thread may have interned the value between the locks
+    if let Some(value) = data.get(value) {
+        return ThinStr {
+            ptr: ptr::NonNull::from(&**value).cast(),
+        };
+    }
+
     unsafe {
         let len = value.len() + std::mem::size_of::<usize>();
         let len = (len + std::mem::align_of::<usize>() - 1) / std::mem::align_of::<usize>()
@@ -69,7 +78,7 @@ fn intern_slow(interner: &Interner, value: &str) -> ThinStr {
             ptr: ptr::NonNull::from(&*interned_value).cast(),
         };
 
-        interner.write().insert(interned_value);
+        data.insert(interned_value);
 
         value
     }
@@ -105,6 +114,10 @@ pub struct ThinStr {
     ptr: ptr::NonNull<()>,
 }
 
+// interned strings are never changed or freed
+unsafe impl Send for ThinStr {}
+unsafe impl Sync for ThinStr {}
+
 impl fmt::Debug for ThinStr {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         fmt::Debug::fmt(self.to_str(), f)
@@ -117,6 +130,13 @@ impl fmt::Display for ThinStr {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for ThinStr {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.to_str())
+    }
+}
+
 impl fmt::Pointer for ThinStr {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         fmt::Pointer::fmt(&self.ptr, f)
@@ -186,3 +206,29 @@ fn simple_intern() {
     assert_ne!(y, w);
     assert_ne!(z, w);
 }
+
+#[test]
+fn concurrent_intern() {
+    let threads = (0..8)
+        .map(|_| {
+            std::thread::spawn(|| {
+                (0..1000)
+                    .map(|i| intern(&format!("concurrent_intern_{}", i)))
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let interned = threads
+        .into_iter()
+        .map(|thread| thread.join().unwrap())
+        .collect::<Vec<_>>();
+
+    for (i, &value) in interned[0].iter().enumerate() {
+        assert_eq!(value.to_str(), format!("concurrent_intern_{}", i));
+
+        for other in &interned[1..] {
+            assert_eq!(other[i], value);
+        }
+    }
+}